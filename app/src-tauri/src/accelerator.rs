@@ -0,0 +1,231 @@
+//! Accelerator string normalization.
+//!
+//! Users type accelerators in many forms ("Cmd+Shift+Space", "Super+Shift+Space",
+//! "CommandOrControl+Space"). This module canonicalizes modifier aliases for the
+//! current platform and validates the key token, producing the same form the
+//! global shortcut backend prints for a registered shortcut
+//! (e.g. "shift+control+KeyT").
+
+use std::fmt;
+
+/// Platform whose modifier conventions apply when normalizing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcceleratorPlatform {
+    MacOs,
+    Windows,
+    Linux,
+}
+
+impl AcceleratorPlatform {
+    /// The platform this binary was built for
+    pub const fn current() -> Self {
+        if cfg!(target_os = "macos") {
+            Self::MacOs
+        } else if cfg!(target_os = "windows") {
+            Self::Windows
+        } else {
+            Self::Linux
+        }
+    }
+}
+
+/// Errors that can occur while normalizing an accelerator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AcceleratorError {
+    /// The accelerator string is empty
+    Empty,
+    /// A `+`-separated token is empty (e.g. "ctrl++Space")
+    EmptyToken,
+    /// A token in modifier position is not a known modifier
+    UnknownModifier(String),
+    /// The final token is not a key the backend recognizes
+    InvalidKey(String),
+    /// The accelerator only contains modifiers
+    MissingKey,
+    /// More than one non-modifier key was given
+    MultipleKeys(String),
+}
+
+impl fmt::Display for AcceleratorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Shortcut is empty"),
+            Self::EmptyToken => write!(f, "Shortcut contains an empty key"),
+            Self::UnknownModifier(m) => write!(f, "Unknown modifier: {m}"),
+            Self::InvalidKey(k) => write!(f, "Unsupported key: {k}"),
+            Self::MissingKey => write!(f, "Shortcut needs a non-modifier key"),
+            Self::MultipleKeys(k) => write!(f, "Shortcut can only have one main key, found {k}"),
+        }
+    }
+}
+
+impl std::error::Error for AcceleratorError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Modifier {
+    // Declaration order is the backend's display order
+    Shift,
+    Control,
+    Alt,
+    Super,
+}
+
+impl Modifier {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Shift => "shift",
+            Self::Control => "control",
+            Self::Alt => "alt",
+            Self::Super => "super",
+        }
+    }
+
+    fn parse(token: &str, platform: AcceleratorPlatform) -> Option<Self> {
+        match token.to_lowercase().as_str() {
+            "shift" => Some(Self::Shift),
+            "ctrl" | "control" => Some(Self::Control),
+            "alt" | "option" | "opt" => Some(Self::Alt),
+            "super" | "cmd" | "command" | "meta" | "win" | "windows" => Some(Self::Super),
+            // "mod" is how the settings UI's hotkey recorder spells it
+            "commandorcontrol" | "commandorctrl" | "cmdorctrl" | "cmdorcontrol" | "mod" => {
+                if platform == AcceleratorPlatform::MacOs {
+                    Some(Self::Super)
+                } else {
+                    Some(Self::Control)
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Named keys as (canonical code, accepted aliases). Letters, digits, numpad
+/// digits and function keys are handled separately in `canonical_key`.
+const NAMED_KEYS: &[(&str, &[&str])] = &[
+    ("Space", &["space"]),
+    ("Enter", &["enter", "return"]),
+    ("Tab", &["tab"]),
+    ("Escape", &["escape", "esc"]),
+    ("Backspace", &["backspace"]),
+    ("Delete", &["delete", "del"]),
+    ("Insert", &["insert"]),
+    ("Home", &["home"]),
+    ("End", &["end"]),
+    ("PageUp", &["pageup"]),
+    ("PageDown", &["pagedown"]),
+    ("ArrowUp", &["arrowup", "up"]),
+    ("ArrowDown", &["arrowdown", "down"]),
+    ("ArrowLeft", &["arrowleft", "left"]),
+    ("ArrowRight", &["arrowright", "right"]),
+    ("Backquote", &["backquote", "`"]),
+    ("Backslash", &["backslash", "\\"]),
+    ("BracketLeft", &["bracketleft", "["]),
+    ("BracketRight", &["bracketright", "]"]),
+    ("Comma", &["comma", ","]),
+    ("Period", &["period", "."]),
+    ("Slash", &["slash", "/"]),
+    ("Semicolon", &["semicolon", ";"]),
+    ("Quote", &["quote", "'"]),
+    ("Minus", &["minus", "-"]),
+    ("Equal", &["equal", "="]),
+    ("CapsLock", &["capslock"]),
+    ("PrintScreen", &["printscreen"]),
+    ("ScrollLock", &["scrolllock"]),
+    ("Pause", &["pause", "pausebreak"]),
+    ("NumpadAdd", &["numpadadd"]),
+    ("NumpadSubtract", &["numpadsubtract"]),
+    ("NumpadMultiply", &["numpadmultiply"]),
+    ("NumpadDivide", &["numpaddivide"]),
+    ("NumpadDecimal", &["numpaddecimal"]),
+    ("NumpadEnter", &["numpadenter"]),
+    ("NumpadEqual", &["numpadequal"]),
+];
+
+/// Map a key token to the backend's canonical key code, if it is supported
+fn canonical_key(token: &str) -> Option<String> {
+    let lower = token.to_lowercase();
+
+    // Letters: "a", "KeyA"
+    let letter = lower.strip_prefix("key").unwrap_or(&lower);
+    if letter.len() == 1 && letter.chars().all(|c| c.is_ascii_lowercase()) {
+        return Some(format!("Key{}", letter.to_uppercase()));
+    }
+
+    // Digits: "1", "Digit1"
+    let digit = lower.strip_prefix("digit").unwrap_or(&lower);
+    if digit.len() == 1 && digit.chars().all(|c| c.is_ascii_digit()) {
+        return Some(format!("Digit{digit}"));
+    }
+
+    // Numpad digits: "Numpad0".."Numpad9"
+    if let Some(digit) = lower.strip_prefix("numpad") {
+        if digit.len() == 1 && digit.chars().all(|c| c.is_ascii_digit()) {
+            return Some(format!("Numpad{digit}"));
+        }
+    }
+
+    // Function keys: "F1".."F24"
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+        if (1..=24).contains(&n) {
+            return Some(format!("F{n}"));
+        }
+    }
+
+    NAMED_KEYS
+        .iter()
+        .find(|(_, aliases)| aliases.contains(&lower.as_str()))
+        .map(|(code, _)| (*code).to_string())
+}
+
+/// Normalize an accelerator for the current platform.
+pub fn normalize_accelerator(input: &str) -> Result<String, AcceleratorError> {
+    normalize_accelerator_for(input, AcceleratorPlatform::current())
+}
+
+/// Normalize an accelerator using the given platform's modifier conventions.
+///
+/// Modifiers are de-duplicated and emitted in the backend's display order,
+/// followed by the canonical key code.
+pub fn normalize_accelerator_for(
+    input: &str,
+    platform: AcceleratorPlatform,
+) -> Result<String, AcceleratorError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(AcceleratorError::Empty);
+    }
+
+    let tokens: Vec<&str> = input.split('+').map(str::trim).collect();
+    if tokens.iter().any(|t| t.is_empty()) {
+        return Err(AcceleratorError::EmptyToken);
+    }
+
+    let (key_token, modifier_tokens) = tokens
+        .split_last()
+        .expect("split always yields at least one token");
+
+    let mut modifiers = Vec::new();
+    for token in modifier_tokens {
+        match Modifier::parse(token, platform) {
+            Some(modifier) => modifiers.push(modifier),
+            None if canonical_key(token).is_some() => {
+                return Err(AcceleratorError::MultipleKeys((*token).to_string()));
+            }
+            None => return Err(AcceleratorError::UnknownModifier((*token).to_string())),
+        }
+    }
+    modifiers.sort_unstable();
+    modifiers.dedup();
+
+    let key = match canonical_key(key_token) {
+        Some(key) => key,
+        None if Modifier::parse(key_token, platform).is_some() => {
+            return Err(AcceleratorError::MissingKey);
+        }
+        None => return Err(AcceleratorError::InvalidKey((*key_token).to_string())),
+    };
+
+    let mut parts: Vec<&str> = modifiers.iter().map(|m| m.as_str()).collect();
+    parts.push(&key);
+    Ok(parts.join("+"))
+}
@@ -0,0 +1,69 @@
+//! Guessing the app from the window title when detection couldn't name it.
+//!
+//! Some Wayland backends only report the title (kdotool) or an empty class
+//! (hyprctl), which leaves profile rules matching against a title that changes
+//! with every document. Most apps put their name at one end of the title
+//! (`Document — AppName`, `AppName - Document`), so that end is used instead.
+
+use super::ActiveWindowInfo;
+
+/// Separators between the document and app parts of a title, most specific first
+const TITLE_SEPARATORS: &[&str] = &[" — ", " – ", " - ", " | ", " · "];
+
+/// Markers editors put in front of the title of a document with unsaved changes
+const MODIFIED_MARKERS: &[char] = &['●', '•', '*'];
+
+/// Longest title part, in words, that is still taken for an app name
+const MAX_APP_NAME_WORDS: usize = 4;
+
+/// App name for `info`. Keeps a real app name; when it is empty or just the
+/// window title, takes the part of the title that most likely names the app.
+/// Titles without a separator are assumed to be the app name.
+pub fn derive_app_name(info: &ActiveWindowInfo) -> String {
+    let app_name = info.app_name.trim();
+    if !app_name.is_empty() && app_name != info.window_title.trim() {
+        return app_name.to_string();
+    }
+    let title = info
+        .window_title
+        .trim()
+        .trim_start_matches(MODIFIED_MARKERS)
+        .trim();
+
+    let Some(separator) = TITLE_SEPARATORS.iter().find(|s| title.contains(*s)) else {
+        return title.to_string();
+    };
+    let parts: Vec<&str> = title
+        .split(separator)
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect();
+    // The app name is usually last ("Document — AppName")
+    let app_part = [parts.last(), parts.first()]
+        .into_iter()
+        .flatten()
+        .find(|part| looks_like_app_name(part));
+    app_part.map_or(title, |part| part).to_string()
+}
+
+/// `info` with `app_name` replaced by `derive_app_name`. The title is kept as is.
+pub fn with_derived_app_name(mut info: ActiveWindowInfo) -> ActiveWindowInfo {
+    info.app_name = derive_app_name(&info);
+    info
+}
+
+/// Short, and not a file name or path
+fn looks_like_app_name(part: &str) -> bool {
+    part.split_whitespace().count() <= MAX_APP_NAME_WORDS
+        && !part.contains(['/', '\\'])
+        && !has_file_extension(part)
+}
+
+/// Ends in something like `.rs` or `.docx`
+fn has_file_extension(part: &str) -> bool {
+    part.rsplit_once('.').is_some_and(|(stem, extension)| {
+        !stem.is_empty()
+            && (1..=4).contains(&extension.len())
+            && extension.chars().all(|c| c.is_ascii_alphanumeric())
+    })
+}
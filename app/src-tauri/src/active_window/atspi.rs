@@ -0,0 +1,432 @@
+//! Browser tab URLs on Linux via the accessibility bus (AT-SPI).
+//!
+//! X11 has no way to ask a browser for its URL, but browsers that expose
+//! accessibility publish their address bar on the AT-SPI bus. The lookup copies
+//! the browser's front window from the accessibility tree into
+//! `AccessibleNode`s and `find_address_bar_url` reads the URL from it.
+//!
+//! Chromium-based browsers expose the tree whenever the AT-SPI bus is running.
+//! Firefox only does once accessibility is enabled (by a screen reader, or
+//! `GNOME_ACCESSIBILITY=1`). Without either the lookup returns `None`.
+//!
+//! Walking the tree takes hundreds of calls, so the address bar found is kept
+//! per browser process and read directly while its window stays in front.
+
+use serde::Deserialize;
+#[cfg(target_os = "linux")]
+use std::time::Duration;
+use url::Url;
+
+use super::AddressBarLookup;
+
+/// Browsers whose address bar is looked up, by lowercase WM class or process name
+const BROWSERS: &[&str] = &[
+    "firefox",
+    "firefox-esr",
+    "librewolf",
+    "chromium",
+    "chromium-browser",
+    "chrome",
+    "google-chrome",
+    "brave",
+    "brave-browser",
+    "microsoft-edge",
+    "msedge",
+    "vivaldi",
+    "vivaldi-bin",
+];
+
+/// Accessible names of address bars (lowercase) in English builds. Other
+/// languages fall back to the first entry holding a URL.
+const ADDRESS_BAR_NAMES: &[&str] = &[
+    "search or enter address",
+    "address and search bar",
+    "location",
+];
+
+/// Window title markers of private windows (lowercase)
+const PRIVATE_TITLE_MARKERS: &[&str] = &["private browsing", "(incognito)", "inprivate"];
+
+/// Roles holding page content, which is skipped: pages can contain entries
+/// with URLs in them, and walking them would take far too long
+const DOCUMENT_ROLES: &[&str] = &["document web", "document frame"];
+
+/// How deep the window is walked; address bars sit well above this
+#[cfg(target_os = "linux")]
+const MAX_DEPTH: usize = 24;
+
+/// Nodes fetched before giving up, so a huge tree can't stall a recording
+#[cfg(target_os = "linux")]
+const MAX_NODES: usize = 600;
+
+/// How long a walk may take in total
+#[cfg(target_os = "linux")]
+const WALK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long a single call may take, so a hung browser can't block detection
+#[cfg(target_os = "linux")]
+const CALL_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// An element of an application's accessibility tree
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AccessibleNode {
+    /// AT-SPI role name, e.g. "frame", "entry", "document web"
+    pub role: String,
+    #[serde(default)]
+    pub name: String,
+    /// Text contents, only fetched for entries
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Has `STATE_ACTIVE`, i.e. this is the focused window
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default)]
+    pub children: Vec<AccessibleNode>,
+    /// Order in which the walk fetched the node, to find its object again
+    #[serde(skip)]
+    pub id: usize,
+}
+
+/// Whether `name` (a WM class or process name) is a browser we can read
+pub fn is_browser(name: &str) -> bool {
+    BROWSERS.contains(&name.to_lowercase().as_str())
+}
+
+/// Whether a browser window title marks a private/incognito window
+pub fn title_looks_private(title: &str) -> bool {
+    let title = title.to_lowercase();
+    PRIVATE_TITLE_MARKERS
+        .iter()
+        .any(|marker| title.contains(marker))
+}
+
+/// The front window among an application's windows: the active one, or the
+/// only one. With several windows and none active, it can't be told apart.
+pub fn front_window(windows: &[AccessibleNode]) -> Option<usize> {
+    windows
+        .iter()
+        .position(|w| w.active)
+        .or((windows.len() == 1).then_some(0))
+}
+
+/// URL in the address bar of the application's front window
+pub fn find_address_bar_url(app: &AccessibleNode) -> Option<String> {
+    find_address_bar(app).map(|(_, url)| url)
+}
+
+/// Address bar of the application's front window, and the URL in it
+pub fn find_address_bar(app: &AccessibleNode) -> Option<(&AccessibleNode, String)> {
+    let window = &app.children[front_window(&app.children)?];
+    let mut entries = Vec::new();
+    collect_entries(window, &mut entries);
+
+    let url_in = |entry: &&AccessibleNode| entry.text.as_deref().and_then(url_from_address_bar);
+    let named = entries
+        .iter()
+        .find(|entry| ADDRESS_BAR_NAMES.contains(&entry.name.trim().to_lowercase().as_str()));
+    match named {
+        Some(entry) => url_in(entry).map(|url| (*entry, url)),
+        None => entries
+            .iter()
+            .find_map(|entry| url_in(entry).map(|url| (*entry, url))),
+    }
+}
+
+/// Entries outside of page content, in tree order
+fn collect_entries<'a>(node: &'a AccessibleNode, entries: &mut Vec<&'a AccessibleNode>) {
+    if DOCUMENT_ROLES.contains(&node.role.as_str()) {
+        return;
+    }
+    if node.role == "entry" {
+        entries.push(node);
+    }
+    for child in &node.children {
+        collect_entries(child, entries);
+    }
+}
+
+/// Read a URL from address bar text. Browsers hide `https://`, so text that
+/// only parses with it added counts if it has a domain. Anything else is a
+/// search being typed.
+pub fn url_from_address_bar(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() || text.contains(char::is_whitespace) {
+        return None;
+    }
+    if let Ok(url) = Url::parse(text) {
+        if url.has_host() || url.scheme() == "file" {
+            return Some(url.to_string());
+        }
+    }
+    let url = Url::parse(&format!("https://{text}")).ok()?;
+    let host = url.host_str()?;
+    (host.contains('.') || host == "localhost").then(|| url.to_string())
+}
+
+/// Look up the address bar URL of the browser running as `pid`
+#[cfg(target_os = "linux")]
+pub fn browser_url(pid: u32, lookup: AddressBarLookup) -> Option<String> {
+    let url = bus::address_bar_url(pid, lookup);
+    if url.is_none() && lookup == AddressBarLookup::Find {
+        log::debug!("No address bar URL found over AT-SPI for pid {pid}");
+    }
+    url
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn browser_url(_pid: u32, _lookup: AddressBarLookup) -> Option<String> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+mod bus {
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+    use std::time::Instant;
+    use zbus::blocking::connection::Builder;
+    use zbus::blocking::Connection;
+    use zbus::zvariant::{DynamicType, OwnedObjectPath, OwnedValue, Type, Value};
+
+    use super::{
+        find_address_bar, front_window, url_from_address_bar, AccessibleNode, AddressBarLookup,
+        CALL_TIMEOUT, DOCUMENT_ROLES, MAX_DEPTH, MAX_NODES, WALK_TIMEOUT,
+    };
+
+    const ACCESSIBLE: &str = "org.a11y.atspi.Accessible";
+    const TEXT: &str = "org.a11y.atspi.Text";
+
+    /// `STATE_ACTIVE` in the first word of an AT-SPI state set
+    const STATE_ACTIVE: u32 = 1 << 1;
+
+    /// Bus name and object path of an accessible object
+    type ObjectRef = (String, OwnedObjectPath);
+
+    /// Where a browser's address bar was found: reading it again takes a few
+    /// calls while finding it takes hundreds
+    #[derive(Clone)]
+    struct AddressBar {
+        app: ObjectRef,
+        window: ObjectRef,
+        entry: ObjectRef,
+    }
+
+    /// Address bars found so far, by browser pid
+    static ADDRESS_BARS: Mutex<BTreeMap<u32, AddressBar>> = Mutex::new(BTreeMap::new());
+
+    /// Objects a walk fetched, indexed by `AccessibleNode::id`
+    struct Walk {
+        objects: Vec<ObjectRef>,
+        deadline: Instant,
+    }
+
+    impl Walk {
+        fn exhausted(&self) -> bool {
+            self.objects.len() >= MAX_NODES || Instant::now() >= self.deadline
+        }
+    }
+
+    /// URL in the address bar of the browser running as `pid`, read from the
+    /// address bar found last time if its window is still in front
+    pub fn address_bar_url(pid: u32, lookup: AddressBarLookup) -> Option<String> {
+        let known = ADDRESS_BARS.lock().unwrap().get(&pid).cloned();
+        if known.is_none() && lookup == AddressBarLookup::KnownOnly {
+            return None;
+        }
+        let bus = connect()?;
+        if let Some(url) = known.and_then(|bar| read_address_bar(&bus, &bar)) {
+            return Some(url);
+        }
+        if lookup == AddressBarLookup::KnownOnly {
+            return None;
+        }
+
+        let found = application(&bus, pid).and_then(|app| walk_to_address_bar(&bus, app));
+        let mut address_bars = ADDRESS_BARS.lock().unwrap();
+        match found {
+            Some((bar, url)) => {
+                address_bars.insert(pid, bar);
+                Some(url)
+            }
+            None => {
+                address_bars.remove(&pid);
+                None
+            }
+        }
+    }
+
+    fn read_address_bar(bus: &Connection, bar: &AddressBar) -> Option<String> {
+        if front_window_ref(bus, &bar.app)? != bar.window {
+            return None;
+        }
+        let text: String = call(bus, &bar.entry, TEXT, "GetText", &(0i32, -1i32))?;
+        url_from_address_bar(&text)
+    }
+
+    /// Walk the application's front window for its address bar
+    fn walk_to_address_bar(bus: &Connection, app: ObjectRef) -> Option<(AddressBar, String)> {
+        let window = front_window_ref(bus, &app)?;
+        let mut walk = Walk {
+            objects: Vec::new(),
+            deadline: Instant::now() + WALK_TIMEOUT,
+        };
+        // Other windows are left out so they don't eat into the node budget
+        let front_window_only = AccessibleNode {
+            role: "application".to_string(),
+            children: vec![tree(bus, &window, 0, &mut walk)?],
+            ..Default::default()
+        };
+        let (entry, url) = find_address_bar(&front_window_only)?;
+        let entry = walk.objects.get(entry.id)?.clone();
+        Some((AddressBar { app, window, entry }, url))
+    }
+
+    /// Connect to the accessibility bus, whose address the session bus hands out
+    fn connect() -> Option<Connection> {
+        let session = Builder::session()
+            .map(|builder| builder.method_timeout(CALL_TIMEOUT))
+            .and_then(|builder| builder.build())
+            .ok()?;
+        let reply = session
+            .call_method(
+                Some("org.a11y.Bus"),
+                "/org/a11y/bus",
+                Some("org.a11y.Bus"),
+                "GetAddress",
+                &(),
+            )
+            .map_err(|e| log::debug!("AT-SPI bus is not available: {e}"))
+            .ok()?;
+        let address: String = reply.body().deserialize().ok()?;
+        Builder::address(address.as_str())
+            .map(|builder| builder.method_timeout(CALL_TIMEOUT))
+            .and_then(|builder| builder.build())
+            .map_err(|e| log::debug!("Failed to connect to the AT-SPI bus: {e}"))
+            .ok()
+    }
+
+    /// The accessible application owned by process `pid`
+    fn application(bus: &Connection, pid: u32) -> Option<ObjectRef> {
+        let registry = (
+            "org.a11y.atspi.Registry".to_string(),
+            OwnedObjectPath::try_from("/org/a11y/atspi/accessible/root").ok()?,
+        );
+        let apps: Vec<ObjectRef> = call(bus, &registry, ACCESSIBLE, "GetChildren", &())?;
+        apps.into_iter().find(|(name, _)| {
+            bus.call_method(
+                Some("org.freedesktop.DBus"),
+                "/org/freedesktop/DBus",
+                Some("org.freedesktop.DBus"),
+                "GetConnectionUnixProcessID",
+                &(name.as_str(),),
+            )
+            .ok()
+            .and_then(|reply| reply.body().deserialize::<u32>().ok())
+                == Some(pid)
+        })
+    }
+
+    /// The application's front window
+    fn front_window_ref(bus: &Connection, app: &ObjectRef) -> Option<ObjectRef> {
+        let window_refs: Vec<ObjectRef> = call(bus, app, ACCESSIBLE, "GetChildren", &())?;
+        let (window_refs, windows): (Vec<ObjectRef>, Vec<AccessibleNode>) = window_refs
+            .into_iter()
+            .filter_map(|window| {
+                let states: Vec<u32> = call(bus, &window, ACCESSIBLE, "GetState", &())?;
+                let node = AccessibleNode {
+                    active: is_active(&states),
+                    ..Default::default()
+                };
+                Some((window, node))
+            })
+            .unzip();
+        window_refs.into_iter().nth(front_window(&windows)?)
+    }
+
+    fn tree(
+        bus: &Connection,
+        object: &ObjectRef,
+        depth: usize,
+        walk: &mut Walk,
+    ) -> Option<AccessibleNode> {
+        let id = walk.objects.len();
+        walk.objects.push(object.clone());
+        let mut node = node(bus, object)?;
+        node.id = id;
+        if depth >= MAX_DEPTH || DOCUMENT_ROLES.contains(&node.role.as_str()) {
+            return Some(node);
+        }
+        let children: Vec<ObjectRef> =
+            call(bus, object, ACCESSIBLE, "GetChildren", &()).unwrap_or_default();
+        for child in &children {
+            if walk.exhausted() {
+                log::debug!("AT-SPI walk stopped after {} nodes", walk.objects.len());
+                break;
+            }
+            if let Some(child) = tree(bus, child, depth + 1, walk) {
+                node.children.push(child);
+            }
+        }
+        Some(node)
+    }
+
+    /// One accessible object, without its children
+    fn node(bus: &Connection, object: &ObjectRef) -> Option<AccessibleNode> {
+        let role: String = call(bus, object, ACCESSIBLE, "GetRoleName", &())?;
+        let name = match property(bus, object, "Name").as_deref() {
+            Some(Value::Str(s)) => s.as_str().to_string(),
+            _ => String::new(),
+        };
+        let states: Vec<u32> = call(bus, object, ACCESSIBLE, "GetState", &()).unwrap_or_default();
+        let text = if role == "entry" {
+            call(bus, object, TEXT, "GetText", &(0i32, -1i32))
+        } else {
+            None
+        };
+        Some(AccessibleNode {
+            role,
+            name,
+            text,
+            active: is_active(&states),
+            ..Default::default()
+        })
+    }
+
+    fn is_active(states: &[u32]) -> bool {
+        states.first().is_some_and(|word| word & STATE_ACTIVE != 0)
+    }
+
+    fn property(bus: &Connection, object: &ObjectRef, name: &str) -> Option<OwnedValue> {
+        call(
+            bus,
+            object,
+            "org.freedesktop.DBus.Properties",
+            "Get",
+            &(ACCESSIBLE, name),
+        )
+    }
+
+    fn call<R, B>(
+        bus: &Connection,
+        (destination, path): &ObjectRef,
+        interface: &str,
+        method: &str,
+        body: &B,
+    ) -> Option<R>
+    where
+        R: DeserializeOwned + Type,
+        B: Serialize + DynamicType,
+    {
+        let reply = bus
+            .call_method(
+                Some(destination.as_str()),
+                path.as_str(),
+                Some(interface),
+                method,
+                body,
+            )
+            .ok()?;
+        reply.body().deserialize().ok()
+    }
+}
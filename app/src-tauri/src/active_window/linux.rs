@@ -0,0 +1,293 @@
+//! Linux active window detection via xdotool (X11) or, on Wayland, kdotool/hyprctl
+//! with a D-Bus fallback for other compositors. On X11, browser URLs come from
+//! the accessibility bus (see `atspi`).
+
+use std::sync::Mutex;
+
+use super::{atspi, stdout_text, ActiveWindowInfo, AddressBarLookup, CommandRunner};
+
+/// Backend that last found the active window, so changes are logged once
+static LAST_WAYLAND_BACKEND: Mutex<Option<WaylandBackend>> = Mutex::new(None);
+
+pub fn get_active_window(
+    runner: &dyn CommandRunner,
+    lookup: AddressBarLookup,
+) -> Option<ActiveWindowInfo> {
+    if std::env::var("WAYLAND_DISPLAY").is_ok() {
+        return get_active_window_wayland(runner);
+    }
+    get_active_window_x11(runner, lookup)
+}
+
+pub fn get_active_window_x11(
+    runner: &dyn CommandRunner,
+    lookup: AddressBarLookup,
+) -> Option<ActiveWindowInfo> {
+    let window_name_output = runner
+        .run("xdotool", &["getactivewindow", "getwindowname"])
+        .ok()?;
+    let window_title = stdout_text(&window_name_output);
+
+    let pid_output = runner
+        .run("xdotool", &["getactivewindow", "getwindowpid"])
+        .ok()?;
+    let pid = stdout_text(&pid_output);
+    let process_name = get_process_name_from_pid(&pid);
+
+    let app_name = process_name
+        .as_ref()
+        .map(|p| {
+            std::path::Path::new(p)
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or(p)
+                .to_string()
+        })
+        .unwrap_or_default();
+
+    // Browsers don't mark private windows anywhere but the title
+    let is_browser = atspi::is_browser(&app_name);
+    let is_private = is_browser && atspi::title_looks_private(&window_title);
+    let url = if is_browser && !is_private {
+        pid.parse()
+            .ok()
+            .and_then(|pid| atspi::browser_url(pid, lookup))
+    } else {
+        None
+    };
+
+    Some(ActiveWindowInfo {
+        window_title,
+        app_name,
+        bundle_id: None,
+        process_name,
+        url,
+        display_name: None,
+        is_private,
+    })
+}
+
+/// Ways to find the active window on Wayland, which has no common API for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaylandBackend {
+    /// KDE Plasma, via `kdotool`
+    Kdotool,
+    /// Hyprland, via `hyprctl`
+    Hyprctl,
+    /// Window introspection over the D-Bus session bus (`org.gnome.Shell.Introspect`).
+    /// xdg-desktop-portal has no interface for the focused window, so this is the
+    /// closest D-Bus equivalent; shells that restrict it deny the call.
+    DBusIntrospect,
+}
+
+impl WaylandBackend {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Kdotool => "kdotool",
+            Self::Hyprctl => "hyprctl",
+            Self::DBusIntrospect => "D-Bus introspection",
+        }
+    }
+}
+
+/// Compositor-specific tools first since they report richer data, then the D-Bus fallback
+pub const WAYLAND_BACKENDS: [WaylandBackend; 3] = [
+    WaylandBackend::Kdotool,
+    WaylandBackend::Hyprctl,
+    WaylandBackend::DBusIntrospect,
+];
+
+/// Checks which Wayland backends can be used and queries them
+pub trait WaylandProbe {
+    fn is_available(&self, backend: WaylandBackend) -> bool;
+    fn active_window(&self, backend: WaylandBackend) -> Option<ActiveWindowInfo>;
+}
+
+/// Probe that runs the real tools and talks to the real session bus
+pub struct SystemWaylandProbe<'a> {
+    runner: &'a dyn CommandRunner,
+}
+
+impl WaylandProbe for SystemWaylandProbe<'_> {
+    fn is_available(&self, backend: WaylandBackend) -> bool {
+        match backend {
+            // A missing tool fails to spawn, which is as cheap as searching PATH
+            WaylandBackend::Kdotool | WaylandBackend::Hyprctl => true,
+            WaylandBackend::DBusIntrospect => {
+                std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_some()
+            }
+        }
+    }
+
+    fn active_window(&self, backend: WaylandBackend) -> Option<ActiveWindowInfo> {
+        match backend {
+            WaylandBackend::Kdotool => get_active_window_kdotool(self.runner),
+            WaylandBackend::Hyprctl => get_active_window_hyprctl(self.runner),
+            WaylandBackend::DBusIntrospect => get_active_window_dbus(),
+        }
+    }
+}
+
+pub fn get_active_window_wayland(runner: &dyn CommandRunner) -> Option<ActiveWindowInfo> {
+    let probe = SystemWaylandProbe { runner };
+    select_wayland_backend(&WAYLAND_BACKENDS, &probe).map(|(_, info)| info)
+}
+
+/// Query each available backend in order, returning the first that finds a window
+pub fn select_wayland_backend(
+    backends: &[WaylandBackend],
+    probe: &dyn WaylandProbe,
+) -> Option<(WaylandBackend, ActiveWindowInfo)> {
+    for &backend in backends {
+        if !probe.is_available(backend) {
+            log::trace!("Wayland backend {} is not available", backend.name());
+            continue;
+        }
+        if let Some(info) = probe.active_window(backend) {
+            let mut last = LAST_WAYLAND_BACKEND.lock().unwrap();
+            if *last == Some(backend) {
+                log::debug!("Active window detected via {}", backend.name());
+            } else {
+                log::info!("Active window detected via {}", backend.name());
+                *last = Some(backend);
+            }
+            return Some((backend, info));
+        }
+        log::debug!("Wayland backend {} found no active window", backend.name());
+    }
+
+    log::debug!("No Wayland backend could detect the active window");
+    None
+}
+
+pub fn get_active_window_kdotool(runner: &dyn CommandRunner) -> Option<ActiveWindowInfo> {
+    let output = runner.run("kdotool", &["getactivewindow"]).ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let window_id = stdout_text(&output);
+
+    let name_output = runner.run("kdotool", &["getwindowname", &window_id]).ok()?;
+    let window_title = stdout_text(&name_output);
+
+    Some(ActiveWindowInfo {
+        window_title: window_title.clone(),
+        app_name: window_title,
+        bundle_id: None,
+        process_name: None,
+        url: None,
+        display_name: None,
+        is_private: false,
+    })
+}
+
+pub fn get_active_window_hyprctl(runner: &dyn CommandRunner) -> Option<ActiveWindowInfo> {
+    let output = runner.run("hyprctl", &["activewindow", "-j"]).ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_hyprctl_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(target_os = "linux")]
+fn get_active_window_dbus() -> Option<ActiveWindowInfo> {
+    use std::collections::HashMap;
+    use zbus::zvariant::{OwnedValue, Value};
+
+    let connection = zbus::blocking::Connection::session().ok()?;
+    let reply = connection
+        .call_method(
+            Some("org.gnome.Shell.Introspect"),
+            "/org/gnome/Shell/Introspect",
+            Some("org.gnome.Shell.Introspect"),
+            "GetWindows",
+            &(),
+        )
+        .map_err(|e| log::debug!("D-Bus window introspection failed: {e}"))
+        .ok()?;
+    let body = reply.body();
+    let windows: HashMap<u64, HashMap<String, OwnedValue>> = body.deserialize().ok()?;
+
+    let focused = windows.into_values().find(|props| {
+        matches!(
+            props.get("has-focus").map(|v| &**v),
+            Some(Value::Bool(true))
+        )
+    })?;
+    let string_prop = |key: &str| match focused.get(key).map(|v| &**v) {
+        Some(Value::Str(s)) => s.as_str().to_string(),
+        _ => String::new(),
+    };
+
+    Some(window_from_introspection(
+        &string_prop("app-id"),
+        &string_prop("wm-class"),
+        string_prop("title"),
+    ))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn get_active_window_dbus() -> Option<ActiveWindowInfo> {
+    None
+}
+
+/// Build window info from D-Bus introspection properties. The WM class is the
+/// closest match to other backends' app names; the app ID (a desktop file
+/// name) is the fallback for windows without one.
+pub fn window_from_introspection(app_id: &str, wm_class: &str, title: String) -> ActiveWindowInfo {
+    let app_name = if wm_class.is_empty() {
+        app_id
+            .strip_suffix(".desktop")
+            .unwrap_or(app_id)
+            .to_string()
+    } else {
+        wm_class.to_string()
+    };
+
+    ActiveWindowInfo {
+        window_title: title,
+        app_name,
+        bundle_id: None,
+        process_name: None,
+        url: None,
+        display_name: None,
+        is_private: false,
+    }
+}
+
+/// Parse the JSON printed by `hyprctl activewindow -j`
+pub fn parse_hyprctl_output(json_str: &str) -> Option<ActiveWindowInfo> {
+    let json = serde_json::from_str::<serde_json::Value>(json_str).ok()?;
+    let window_title = json
+        .get("title")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let app_name = json
+        .get("class")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+
+    Some(ActiveWindowInfo {
+        window_title,
+        app_name,
+        bundle_id: None,
+        process_name: None,
+        url: None,
+        display_name: None,
+        is_private: false,
+    })
+}
+
+fn get_process_name_from_pid(pid: &str) -> Option<String> {
+    use std::fs;
+
+    if pid.is_empty() {
+        return None;
+    }
+    let comm_path = format!("/proc/{pid}/comm");
+    fs::read_to_string(comm_path)
+        .ok()
+        .map(|s| s.trim().to_string())
+}
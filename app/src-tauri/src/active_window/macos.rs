@@ -0,0 +1,134 @@
+//! macOS active window detection via `osascript`.
+
+use super::{stdout_text, ActiveWindowInfo, CommandRunner};
+
+/// Separator between fields in the front window script output
+const FIELD_SEPARATOR: &str = "|||";
+
+/// Reports the front app, its window title and, for browsers, the privacy
+/// mode of the front window and the active tab's URL, in one `osascript` run.
+/// The URL is only read from windows the browser reports as `normal`. Safari
+/// windows are `private` when a Window menu item for the front window's title
+/// carries the "Private" marker, `normal` when one matches the title exactly,
+/// and `unknown` otherwise. Browser lookups go through `run script` so a
+/// browser's dictionary is only loaded when that browser is in front.
+const FRONT_WINDOW_SCRIPT: &str = r#"
+        tell application "System Events"
+            set frontApp to first application process whose frontmost is true
+            set appName to name of frontApp
+            set bundleId to bundle identifier of frontApp
+        end tell
+
+        tell application appName
+            try
+                set windowTitle to name of front window
+            on error
+                set windowTitle to ""
+            end try
+        end tell
+
+        set windowMode to ""
+        set tabUrl to ""
+        try
+            if bundleId starts with "com.google.Chrome" or bundleId starts with "com.brave.Browser" or bundleId starts with "com.microsoft.edgemac" then
+                set windowMode to "unknown"
+                set windowMode to run script "tell application id \"" & bundleId & "\" to get mode of front window"
+                if windowMode is "normal" then
+                    set tabUrl to run script "tell application id \"" & bundleId & "\" to get URL of active tab of front window"
+                end if
+            else if bundleId starts with "com.apple.Safari" then
+                -- Safari has no window mode property, but its Window menu
+                -- marks private windows' items with "Private"
+                set windowMode to "unknown"
+                tell application "System Events"
+                    set windowItems to name of every menu item of menu "Window" of menu bar 1 of frontApp
+                end tell
+                repeat with itemName in windowItems
+                    set itemText to itemName as text
+                    if windowTitle is not "" and itemText starts with windowTitle then
+                        if itemText contains "Private" then
+                            set windowMode to "private"
+                            exit repeat
+                        else if itemText is windowTitle then
+                            set windowMode to "normal"
+                        end if
+                    end if
+                end repeat
+                if windowMode is "normal" then
+                    set tabUrl to run script "tell application id \"" & bundleId & "\" to get URL of current tab of front window"
+                end if
+            end if
+        end try
+
+        return appName & "|||" & bundleId & "|||" & windowMode & "|||" & windowTitle & "|||" & tabUrl
+    "#;
+
+/// Privacy mode of the front window, as reported by the front window script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowMode {
+    /// Not a browser the script checks
+    NotBrowser,
+    /// A regular browser window
+    Normal,
+    /// An incognito/private browser window
+    Private,
+    /// A browser whose window mode couldn't be determined
+    Unknown,
+}
+
+impl WindowMode {
+    pub fn parse(mode: &str) -> Self {
+        match mode.trim() {
+            "" => Self::NotBrowser,
+            "normal" => Self::Normal,
+            "incognito" | "private" => Self::Private,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Whether the tab URL may be captured. Browser windows whose mode is
+    /// unknown are treated as private.
+    pub fn allows_url(self) -> bool {
+        matches!(self, Self::NotBrowser | Self::Normal)
+    }
+
+    /// Whether the window should be reported as private
+    pub fn is_private(self) -> bool {
+        matches!(self, Self::Private | Self::Unknown)
+    }
+}
+
+pub fn get_active_window(runner: &dyn CommandRunner) -> Option<ActiveWindowInfo> {
+    let output = runner.run("osascript", &["-e", FRONT_WINDOW_SCRIPT]).ok()?;
+
+    if !output.status.success() {
+        log::warn!("AppleScript execution failed");
+        return None;
+    }
+
+    parse_front_window_output(&stdout_text(&output))
+}
+
+/// Parse the `appName|||bundleId|||windowMode|||windowTitle|||url` output of
+/// the front window script. The URL is dropped unless the window mode allows
+/// it (see `WindowMode`).
+pub fn parse_front_window_output(output: &str) -> Option<ActiveWindowInfo> {
+    let mut parts = output.trim().splitn(4, FIELD_SEPARATOR);
+    let app_name = parts.next()?.to_string();
+    let bundle_id = parts.next()?.to_string();
+    let mode = WindowMode::parse(parts.next()?);
+    // Window titles may themselves contain the separator, so the URL is split
+    // off the end and the title keeps everything in between
+    let (window_title, url) = parts.next()?.rsplit_once(FIELD_SEPARATOR)?;
+    let url = url.trim();
+
+    Some(ActiveWindowInfo {
+        app_name,
+        window_title: window_title.to_string(),
+        bundle_id: Some(bundle_id),
+        process_name: None,
+        url: (mode.allows_url() && !url.is_empty()).then(|| url.to_string()),
+        display_name: None,
+        is_private: mode.is_private(),
+    })
+}
@@ -0,0 +1,317 @@
+//! Cross-platform active window detection for context-aware formatting.
+//!
+//! This module provides functionality to detect the currently active application
+//! when the user triggers a recording. This information is used to automatically
+//! select an appropriate formatting profile (e.g., Email, Chat, Code Editor).
+
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Platform-specific implementations. Those that only shell out are also built
+// for tests on every platform so their parsing can be checked with canned output.
+#[cfg(any(target_os = "linux", test))]
+pub mod atspi;
+#[cfg(any(target_os = "linux", test))]
+pub mod linux;
+#[cfg(any(target_os = "macos", test))]
+pub mod macos;
+#[cfg(any(target_os = "windows", test))]
+pub mod windows;
+
+pub mod app_name;
+mod own_windows;
+pub use app_name::with_derived_app_name;
+pub use own_windows::{ForegroundHistory, OwnWindows};
+
+/// How long a detected window is reused before querying the OS again
+const CACHE_TTL: Duration = Duration::from_millis(250);
+
+static CACHE: Mutex<Option<(Instant, Option<ActiveWindowInfo>)>> = Mutex::new(None);
+
+/// Last external foreground window, once the app's own windows are registered
+static FOREGROUND: Mutex<Option<ForegroundHistory>> = Mutex::new(None);
+
+/// Whether the user allows inspecting their windows at all
+static DETECTION_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Whether a background address bar search is running (see `find_address_bar`)
+static FINDING_ADDRESS_BAR: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ActiveWindowInfo {
+    pub window_title: String,
+    pub app_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bundle_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub process_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Friendly app name for the UI when the platform has one (e.g. the
+    /// executable's description on Windows). Profile rules match `app_name`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// The window is a private/incognito browser window. Its URL is never
+    /// captured; consumers should treat the title as sensitive too.
+    #[serde(default)]
+    pub is_private: bool,
+}
+
+/// How far detection may go to read a browser's tab URL where that is slow:
+/// on Linux it walks the browser's accessibility tree (see `atspi`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressBarLookup {
+    /// Walk the front window's tree unless its address bar is already known
+    Find,
+    /// Only read an address bar an earlier `Find` lookup found
+    KnownOnly,
+}
+
+/// Runs external programs, so detection can be tested without a real desktop
+pub trait CommandRunner {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<Output>;
+}
+
+/// Runs programs with `std::process::Command`
+pub struct SystemCommandRunner;
+
+impl CommandRunner for SystemCommandRunner {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<Output> {
+        Command::new(program).args(args).output()
+    }
+}
+
+/// Trimmed stdout of a finished command
+#[cfg(any(target_os = "linux", target_os = "macos", test))]
+fn stdout_text(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Command runner that replays canned outputs and records every call
+#[cfg(test)]
+#[derive(Default)]
+pub struct MockCommandRunner {
+    responses: Vec<(String, String, Option<String>)>,
+    calls: std::cell::RefCell<Vec<String>>,
+}
+
+#[cfg(test)]
+impl MockCommandRunner {
+    /// Reply with `stdout` (exit code 0) when `program` runs with args containing `args_contain`.
+    /// Earlier rules win; commands without a rule fail as if the program isn't installed.
+    pub fn respond(mut self, program: &str, args_contain: &str, stdout: &str) -> Self {
+        self.responses.push((
+            program.to_string(),
+            args_contain.to_string(),
+            Some(stdout.to_string()),
+        ));
+        self
+    }
+
+    /// Exit with a non-zero status when `program` runs with args containing `args_contain`
+    pub fn fail(mut self, program: &str, args_contain: &str) -> Self {
+        self.responses
+            .push((program.to_string(), args_contain.to_string(), None));
+        self
+    }
+
+    /// Every command run so far, as `program arg1 arg2...`
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.borrow().clone()
+    }
+
+    fn exit_status(code: i32) -> std::process::ExitStatus {
+        #[cfg(unix)]
+        {
+            std::os::unix::process::ExitStatusExt::from_raw(code << 8)
+        }
+        #[cfg(windows)]
+        {
+            std::os::windows::process::ExitStatusExt::from_raw(code.unsigned_abs())
+        }
+    }
+}
+
+#[cfg(test)]
+impl CommandRunner for MockCommandRunner {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<Output> {
+        let joined = args.join(" ");
+        self.calls.borrow_mut().push(format!("{program} {joined}"));
+
+        let (_, _, stdout) = self
+            .responses
+            .iter()
+            .find(|(p, needle, _)| p == program && joined.contains(needle.as_str()))
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, format!("{program} not found"))
+            })?;
+
+        Ok(Output {
+            status: Self::exit_status(i32::from(stdout.is_none())),
+            stdout: stdout.clone().unwrap_or_default().into_bytes(),
+            stderr: Vec::new(),
+        })
+    }
+}
+
+/// Turn active window detection on or off. While off, every lookup returns
+/// `None` without running a helper program or calling a platform API, so
+/// recordings use the default profile. With no window known, disabled apps
+/// can't be recognised either, so recording is never suppressed for them.
+pub fn set_detection_enabled(enabled: bool) {
+    DETECTION_ENABLED.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        *CACHE.lock().unwrap() = None;
+    }
+}
+
+pub fn is_detection_enabled() -> bool {
+    DETECTION_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Like `get_active_window`, but reuses a result from the last `CACHE_TTL`.
+/// Detection shells out on most platforms, so pollers share one query. Browser
+/// address bars are only read once a recording has found them, since finding
+/// one walks the browser's accessibility tree (see `atspi`).
+pub fn get_active_window_cached() -> Option<ActiveWindowInfo> {
+    if !is_detection_enabled() {
+        return None;
+    }
+    let mut cache = CACHE.lock().unwrap();
+    if let Some((fetched_at, ref info)) = *cache {
+        if fetched_at.elapsed() < CACHE_TTL {
+            return info.clone();
+        }
+    }
+
+    let info = resolve_active_window(&SystemCommandRunner, AddressBarLookup::KnownOnly);
+    *cache = Some((Instant::now(), info.clone()));
+    info
+}
+
+/// Tell detection which windows are the app's own, so they are skipped
+pub fn register_own_windows(own: OwnWindows) {
+    *FOREGROUND.lock().unwrap() = Some(ForegroundHistory::new(own));
+}
+
+/// Detect the window a recording is for. When one of the app's own windows
+/// (see `register_own_windows`) is in front, this is the window before it.
+/// Backends that can't name the app get one guessed from the title.
+pub fn get_active_window() -> Option<ActiveWindowInfo> {
+    get_active_window_through(&SystemCommandRunner)
+}
+
+/// `get_active_window`, running any helper programs through `runner`.
+/// Does nothing at all while detection is off (see `set_detection_enabled`).
+pub fn get_active_window_through(runner: &dyn CommandRunner) -> Option<ActiveWindowInfo> {
+    resolve_active_window(runner, AddressBarLookup::Find)
+}
+
+/// Like `get_active_window`, but only reads browser address bars that are
+/// already known, for callers that can't wait on a search. Call
+/// `find_address_bar` ahead of time so the URL is there.
+pub fn get_active_window_fast() -> Option<ActiveWindowInfo> {
+    resolve_active_window(&SystemCommandRunner, AddressBarLookup::KnownOnly)
+}
+
+/// Search the front browser window for its address bar on a background
+/// thread, so later `KnownOnly` lookups can read the URL. Only Linux has a
+/// search to run. Does nothing while detection is off or a search is running.
+pub fn find_address_bar() {
+    if !cfg!(target_os = "linux") || !is_detection_enabled() {
+        return;
+    }
+    if FINDING_ADDRESS_BAR.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    std::thread::spawn(|| {
+        let _ = detect_active_window(&SystemCommandRunner, AddressBarLookup::Find);
+        FINDING_ADDRESS_BAR.store(false, Ordering::Release);
+    });
+}
+
+fn resolve_active_window(
+    runner: &dyn CommandRunner,
+    lookup: AddressBarLookup,
+) -> Option<ActiveWindowInfo> {
+    if !is_detection_enabled() {
+        return None;
+    }
+    let detected = detect_active_window(runner, lookup);
+    match FOREGROUND.lock().unwrap().as_mut() {
+        Some(history) => history.resolve(detected),
+        None => detected,
+    }
+}
+
+/// Detect the active window through `runner`, naming the app from the title if
+/// the backend couldn't
+fn detect_active_window(
+    runner: &dyn CommandRunner,
+    lookup: AddressBarLookup,
+) -> Option<ActiveWindowInfo> {
+    get_active_window_with(runner, lookup).map(with_derived_app_name)
+}
+
+/// Detect the active window, running any helper programs through `runner`
+#[cfg(target_os = "macos")]
+pub fn get_active_window_with(
+    runner: &dyn CommandRunner,
+    _lookup: AddressBarLookup,
+) -> Option<ActiveWindowInfo> {
+    macos::get_active_window(runner)
+}
+
+/// Detect the active window, running any helper programs through `runner`
+#[cfg(target_os = "windows")]
+pub fn get_active_window_with(
+    _runner: &dyn CommandRunner,
+    _lookup: AddressBarLookup,
+) -> Option<ActiveWindowInfo> {
+    windows::get_active_window()
+}
+
+/// Detect the active window, running any helper programs through `runner`
+#[cfg(target_os = "linux")]
+pub fn get_active_window_with(
+    runner: &dyn CommandRunner,
+    lookup: AddressBarLookup,
+) -> Option<ActiveWindowInfo> {
+    linux::get_active_window(runner, lookup)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+pub fn get_active_window_with(
+    _runner: &dyn CommandRunner,
+    _lookup: AddressBarLookup,
+) -> Option<ActiveWindowInfo> {
+    log::warn!("Active window detection not supported on this platform");
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_active_window_info_serialization() {
+        let info = ActiveWindowInfo {
+            window_title: "Test Window".to_string(),
+            app_name: "TestApp".to_string(),
+            bundle_id: Some("com.test.app".to_string()),
+            process_name: None,
+            url: Some("https://example.com".to_string()),
+            display_name: None,
+            is_private: false,
+        };
+
+        let json = serde_json::to_string(&info).unwrap();
+        assert!(json.contains("window_title"));
+        assert!(json.contains("Test Window"));
+        assert!(!json.contains("process_name"));
+    }
+}
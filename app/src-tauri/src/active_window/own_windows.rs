@@ -0,0 +1,89 @@
+//! Keeps the app's own windows out of active window detection.
+//!
+//! Clicking the overlay (or having the settings window open) makes it the
+//! foreground window, but a recording is always meant for the app the user
+//! was in before. Detection remembers the last foreground window that wasn't
+//! ours and reports it whenever one of our windows is in front.
+
+use super::ActiveWindowInfo;
+
+/// How to recognize a window belonging to this app
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OwnWindows {
+    /// macOS bundle identifier (the Tauri `identifier`)
+    pub bundle_id: Option<String>,
+    /// Executable name without extension, compared case-insensitively
+    pub process_name: Option<String>,
+    /// Titles of the app's windows
+    pub titles: Vec<String>,
+}
+
+impl OwnWindows {
+    /// Own windows of the running executable with the given bundle ID and window titles
+    pub fn for_current_exe(bundle_id: &str, titles: &[&str]) -> Self {
+        let process_name = std::env::current_exe()
+            .ok()
+            .and_then(|path| path.file_stem().map(|s| s.to_string_lossy().into_owned()));
+        Self {
+            bundle_id: Some(bundle_id.to_string()),
+            process_name,
+            titles: titles.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    pub fn contains(&self, info: &ActiveWindowInfo) -> bool {
+        let same_bundle = self
+            .bundle_id
+            .as_deref()
+            .is_some_and(|own| info.bundle_id.as_deref() == Some(own));
+        let same_process = self.process_name.as_deref().is_some_and(|own| {
+            info.process_name
+                .as_deref()
+                .is_some_and(|name| process_stem(name).eq_ignore_ascii_case(own))
+        });
+        let same_title = self.titles.contains(&info.window_title);
+        same_bundle || same_process || same_title
+    }
+}
+
+/// Executable name without directory or extension
+/// (`C:\...\Tambourine.exe` → `Tambourine`, `tambourine` → `tambourine`)
+fn process_stem(name: &str) -> &str {
+    let file_name = name.rsplit(['\\', '/']).next().unwrap_or(name);
+    match file_name.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => file_name,
+    }
+}
+
+/// Remembers the last foreground window that wasn't one of ours
+#[derive(Debug, Clone, Default)]
+pub struct ForegroundHistory {
+    own: OwnWindows,
+    last_external: Option<ActiveWindowInfo>,
+}
+
+impl ForegroundHistory {
+    pub fn new(own: OwnWindows) -> Self {
+        Self {
+            own,
+            last_external: None,
+        }
+    }
+
+    /// Record a detected foreground window and return the window a recording
+    /// should target: the detected one, or the previous external window if
+    /// the detected one is ours (`None` if no external window was seen yet)
+    pub fn resolve(&mut self, detected: Option<ActiveWindowInfo>) -> Option<ActiveWindowInfo> {
+        let info = detected?;
+        if self.own.contains(&info) {
+            log::debug!(
+                "Foreground window is our own ({}), using the previous window",
+                info.window_title
+            );
+            return self.last_external.clone();
+        }
+        self.last_external = Some(info.clone());
+        Some(info)
+    }
+}
@@ -0,0 +1,228 @@
+//! Windows active window detection via the Win32 API.
+
+#[cfg(target_os = "windows")]
+use super::ActiveWindowInfo;
+
+#[cfg(target_os = "windows")]
+pub fn get_active_window() -> Option<ActiveWindowInfo> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId,
+    };
+
+    unsafe {
+        let hwnd: HWND = GetForegroundWindow();
+        if hwnd.0.is_null() {
+            return None;
+        }
+
+        // Size the buffer to the real title instead of truncating long ones
+        let title_len = GetWindowTextLengthW(hwnd);
+        let window_title = if title_len <= 0 {
+            // Untitled window (or the length query failed) - nothing to decode
+            String::new()
+        } else {
+            // +1 for the terminating null that GetWindowTextW always writes
+            let mut title_buffer = vec![0u16; title_len.unsigned_abs() as usize + 1];
+            let len = GetWindowTextW(hwnd, &mut title_buffer);
+            decode_window_title(&title_buffer, len)
+        };
+
+        let mut process_id: u32 = 0;
+        GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+
+        let process_name = get_process_name(process_id);
+        let app_name = process_name
+            .as_deref()
+            .map(app_name_from_exe_path)
+            .unwrap_or_default();
+        let display_name = process_name.as_deref().and_then(get_file_description);
+
+        Some(ActiveWindowInfo {
+            window_title,
+            app_name,
+            bundle_id: None,
+            process_name,
+            url: None,
+            display_name,
+            is_private: false,
+        })
+    }
+}
+
+/// Decode the first `len` UTF-16 units written by `GetWindowTextW`.
+/// `len` comes straight from the API, so it is clamped to the buffer. If the
+/// title was cut in the middle of a surrogate pair (e.g. it grew between the
+/// length query and the copy), the dangling high surrogate is dropped rather
+/// than decoded as a replacement character.
+pub fn decode_window_title(buffer: &[u16], len: i32) -> String {
+    let mut len = usize::try_from(len).unwrap_or(0).min(buffer.len());
+    if len > 0 && is_high_surrogate(buffer[len - 1]) {
+        len -= 1;
+    }
+    String::from_utf16_lossy(&buffer[..len])
+}
+
+const fn is_high_surrogate(unit: u16) -> bool {
+    matches!(unit, 0xD800..=0xDBFF)
+}
+
+/// App name from an executable path (e.g. `C:\...\Code.exe` → `Code`)
+pub fn app_name_from_exe_path(path: &str) -> String {
+    let file_name = path.rsplit(['\\', '/']).next().unwrap_or(path);
+    match file_name.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem.to_string(),
+        _ => file_name.to_string(),
+    }
+}
+
+/// `FileDescription` of an executable (e.g. `msedge.exe` → `Microsoft Edge`)
+#[cfg(target_os = "windows")]
+fn get_file_description(path: &str) -> Option<String> {
+    use windows::core::HSTRING;
+    use windows::Win32::Storage::FileSystem::{GetFileVersionInfoSizeW, GetFileVersionInfoW};
+
+    let path = HSTRING::from(path);
+    unsafe {
+        let size = GetFileVersionInfoSizeW(&path, None);
+        if size == 0 {
+            return None;
+        }
+        let mut buffer = vec![0u8; size as usize];
+        GetFileVersionInfoW(&path, None, size, buffer.as_mut_ptr().cast()).ok()?;
+        file_description_from_version_info(&buffer)
+    }
+}
+
+/// Read `FileDescription` from a `VS_VERSIONINFO` resource as returned by
+/// `GetFileVersionInfoW`. String tables are tried US English first, then in
+/// the order they appear. Empty descriptions count as missing.
+pub fn file_description_from_version_info(data: &[u8]) -> Option<String> {
+    let (root, _) = parse_version_block(data)?;
+    if root.key != "VS_VERSION_INFO" {
+        return None;
+    }
+    let string_file_info = version_blocks(root.children).find(|b| b.key == "StringFileInfo")?;
+
+    let mut tables: Vec<_> = version_blocks(string_file_info.children).collect();
+    tables.sort_by_key(|table| !table.key.starts_with("0409"));
+    tables.iter().find_map(|table| {
+        version_blocks(table.children)
+            .find(|entry| entry.key == "FileDescription")
+            .and_then(|entry| decode_version_string(entry.value))
+    })
+}
+
+/// A node of a version info resource: a key, a value and child nodes
+struct VersionBlock<'a> {
+    key: String,
+    value: &'a [u8],
+    children: &'a [u8],
+}
+
+/// Parse the node at the start of `data`, returning it and the data after it.
+/// Nodes are `wLength`, `wValueLength`, `wType`, a null-terminated UTF-16 key,
+/// then the value and the children, each aligned to 4 bytes.
+fn parse_version_block(data: &[u8]) -> Option<(VersionBlock<'_>, &[u8])> {
+    let length = usize::from(read_u16(data, 0)?);
+    let value_length = usize::from(read_u16(data, 2)?);
+    let is_text = read_u16(data, 4)? == 1;
+    if length < 6 {
+        return None;
+    }
+    let block = data.get(..length)?;
+
+    let key_units = utf16_until_null(&block[6..]);
+    let key_end = 6 + (key_units.len() + 1) * 2;
+    if key_end > block.len() {
+        return None;
+    }
+
+    // Text values are measured in UTF-16 units, binary ones in bytes
+    let value_size = if is_text {
+        value_length * 2
+    } else {
+        value_length
+    };
+    let value_start = align_to_dword(key_end).min(block.len());
+    let value_end = (value_start + value_size).min(block.len());
+    let children_start = align_to_dword(value_end).min(block.len());
+
+    let version_block = VersionBlock {
+        key: String::from_utf16_lossy(&key_units),
+        value: &block[value_start..value_end],
+        children: &block[children_start..],
+    };
+    let rest = &data[align_to_dword(length).min(data.len())..];
+    Some((version_block, rest))
+}
+
+/// The consecutive nodes in `data`, stopping at the first malformed one
+fn version_blocks(mut data: &[u8]) -> impl Iterator<Item = VersionBlock<'_>> {
+    std::iter::from_fn(move || {
+        let (block, rest) = parse_version_block(data)?;
+        data = rest;
+        Some(block)
+    })
+}
+
+/// A version info string value, up to its terminating null
+fn decode_version_string(value: &[u8]) -> Option<String> {
+    let text = String::from_utf16_lossy(&utf16_until_null(value))
+        .trim()
+        .to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Little-endian UTF-16 units of `bytes` before the first null
+fn utf16_until_null(bytes: &[u8]) -> Vec<u16> {
+    bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|&unit| unit != 0)
+        .collect()
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+const fn align_to_dword(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+#[cfg(target_os = "windows")]
+fn get_process_name(process_id: u32) -> Option<String> {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::OsStringExt;
+    use windows::Win32::Foundation::{CloseHandle, MAX_PATH};
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let process_handle =
+            OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id).ok()?;
+
+        let mut buffer = [0u16; MAX_PATH as usize];
+        let mut size = buffer.len() as u32;
+
+        let result = QueryFullProcessImageNameW(
+            process_handle,
+            PROCESS_NAME_WIN32,
+            windows::core::PWSTR(buffer.as_mut_ptr()),
+            &mut size,
+        );
+
+        let _ = CloseHandle(process_handle);
+
+        if result.is_ok() {
+            let path = OsString::from_wide(&buffer[..size as usize]);
+            Some(path.to_string_lossy().to_string())
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Clipboard access for paste-based text insertion.
+//!
+//! Text is inserted by putting it on the clipboard and simulating a paste.
+//! `ClipboardGuard` makes sure the user's own clipboard comes back if
+//! anything in between fails.
+
+/// Minimal clipboard interface, so insertion can be tested without a display
+pub trait ClipboardBackend {
+    /// Current clipboard text, or `None` if it's empty or holds non-text content
+    fn get_text(&mut self) -> Option<String>;
+    fn set_text(&mut self, text: &str) -> Result<(), String>;
+}
+
+impl ClipboardBackend for arboard::Clipboard {
+    fn get_text(&mut self) -> Option<String> {
+        arboard::Clipboard::get_text(self).ok()
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        arboard::Clipboard::set_text(self, text).map_err(|e| e.to_string())
+    }
+}
+
+/// Snapshots the clipboard text on construction and restores it on drop,
+/// unless `commit()` is called.
+///
+/// Only text can be restored. If the clipboard held something else (an image,
+/// copied files), nothing is written back on drop rather than replacing it
+/// with an empty string.
+pub struct ClipboardGuard<'a, C: ClipboardBackend> {
+    clipboard: &'a mut C,
+    previous: Option<String>,
+    committed: bool,
+}
+
+impl<'a, C: ClipboardBackend> ClipboardGuard<'a, C> {
+    pub fn new(clipboard: &'a mut C) -> Self {
+        let previous = clipboard.get_text();
+        Self {
+            clipboard,
+            previous,
+            committed: false,
+        }
+    }
+
+    pub fn get_text(&mut self) -> Option<String> {
+        self.clipboard.get_text()
+    }
+
+    pub fn set_text(&mut self, text: &str) -> Result<(), String> {
+        self.clipboard.set_text(text)
+    }
+
+    /// Keep the current clipboard content instead of restoring the snapshot
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl<C: ClipboardBackend> Drop for ClipboardGuard<'_, C> {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+        if let Some(ref previous) = self.previous {
+            if let Err(e) = self.clipboard.set_text(previous) {
+                log::warn!("Failed to restore clipboard: {e}");
+            }
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! Time source for the timers and schedules kept in app state, so tests can
+//! drive them with a fake clock instead of sleeping.
+
+use std::time::Instant;
+
+/// Source of the current time
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// Clock backed by `Instant::now()`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
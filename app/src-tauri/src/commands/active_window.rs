@@ -0,0 +1,77 @@
+use crate::active_window;
+use crate::events::EventName;
+use crate::profile_resolver::ProfileMatchResult;
+use crate::state::AppState;
+use crate::window_watcher::{watch_active_window, TitleChanges};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Shortest allowed poll interval, so a bad value can't spin the CPU
+const MIN_WATCH_INTERVAL_MS: u64 = 100;
+
+/// Start emitting `active-window-changed` events, polling every `interval_ms`.
+/// Title changes within an app are reported once stable for `title_debounce_ms`,
+/// unless `track_title_changes` is false. Replaces any watcher that is already running.
+#[tauri::command]
+pub fn start_window_watcher(
+    app: AppHandle,
+    interval_ms: u64,
+    track_title_changes: Option<bool>,
+    title_debounce_ms: Option<u64>,
+) {
+    let interval = Duration::from_millis(interval_ms.max(MIN_WATCH_INTERVAL_MS));
+    let defaults = TitleChanges::default();
+    let title_changes = TitleChanges {
+        enabled: track_title_changes.unwrap_or(defaults.enabled),
+        debounce: title_debounce_ms.map_or(defaults.debounce, Duration::from_millis),
+    };
+    let emitter = app.clone();
+    let handle = watch_active_window(interval, title_changes, move |info| {
+        log::debug!(
+            "Active window changed: {} ({})",
+            info.app_name,
+            info.window_title
+        );
+        // Find a browser's address bar now so recordings can read its URL
+        if info.url.is_none() && !info.is_private {
+            active_window::find_address_bar();
+        }
+        let _ = emitter.emit(EventName::ActiveWindowChanged.as_str(), info);
+    });
+
+    let state = app.state::<AppState>();
+    let previous = state.window_watcher.lock().unwrap().replace(handle);
+    if let Some(previous) = previous {
+        previous.stop();
+    }
+    log::info!(
+        "Window watcher started ({}ms interval)",
+        interval.as_millis()
+    );
+}
+
+/// Stop emitting `active-window-changed` events
+#[tauri::command]
+pub fn stop_window_watcher(app: AppHandle) {
+    let state = app.state::<AppState>();
+    let handle = state.window_watcher.lock().unwrap().take();
+    if let Some(handle) = handle {
+        handle.stop();
+        log::info!("Window watcher stopped");
+    }
+}
+
+/// Show which profile the current foreground window would get, and from which
+/// rule. Detection runs off the async runtime since it may shell out.
+#[tauri::command]
+pub async fn test_current_profile_match(app: AppHandle) -> Result<ProfileMatchResult, String> {
+    if !active_window::is_detection_enabled() {
+        return Err("Active window detection is turned off".to_string());
+    }
+    let window = tauri::async_runtime::spawn_blocking(active_window::get_active_window)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Could not detect the active window".to_string())?;
+    let state = app.state::<AppState>();
+    Ok(state.profile_selection.explain(window))
+}
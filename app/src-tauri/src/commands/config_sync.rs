@@ -1,6 +1,16 @@
-use crate::config_sync::ConfigSync;
-use crate::events::{ConfigResponse, ConfigSetting, EventName};
-use tauri::{AppHandle, Emitter};
+use crate::config_sync::{
+    sync_error_response, BulkSyncOutcome, ConfigSnapshot, ConfigSync, ConfigSyncState, SyncError,
+    SyncMetrics, SyncStatus,
+};
+use crate::events::{
+    ConfigResponse, ConfigSetting, ConnectionStatePayload, DisconnectReason, EventName,
+};
+use crate::reconnect::{ReconnectManager, ReconnectStatus};
+use crate::settings::OperatingMode;
+use crate::state::AppState;
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
 
 /// Notify Rust that we've connected to the server
 /// This stores connection info and syncs current settings
@@ -10,18 +20,55 @@ pub async fn set_server_connected(
     server_url: String,
     client_uuid: String,
     config_sync: tauri::State<'_, ConfigSync>,
+    reconnect: tauri::State<'_, ReconnectManager>,
 ) -> Result<(), String> {
     // Store connection info
     {
         let mut sync = config_sync.write().await;
-        sync.set_connected(server_url, client_uuid);
+        sync.set_connected(server_url.clone(), client_uuid);
     }
 
-    // Sync current settings to server
+    reconnect.reset();
+
+    app.state::<AppState>()
+        .connection_state
+        .set_server_url(server_url, |payload| {
+            let _ = app.emit(EventName::ConnectionState.as_str(), payload);
+        });
+
+    // Sync current settings to server, in one request if the server supports it
     let settings = super::settings::get_settings(app.clone())?;
+    let snapshot = ConfigSnapshot::from_settings(&settings);
+    if snapshot.is_empty() {
+        return Ok(());
+    }
+
     let sync = config_sync.read().await;
+    let responses = match sync.sync_all(&snapshot).await {
+        Ok(BulkSyncOutcome::Applied(results)) => snapshot.responses_for(&results),
+        Ok(BulkSyncOutcome::Skipped) => return Ok(()),
+        Ok(BulkSyncOutcome::Unsupported) => {
+            log::info!("Server has no bulk config endpoint, syncing settings individually");
+            sync_individually(&app, &sync, &snapshot).await;
+            return Ok(());
+        }
+        Err(SyncError::Cancelled) => return Ok(()),
+        Err(e) => {
+            log::warn!("Failed to sync config on connect: {e}");
+            snapshot.error_responses(&e.to_string())
+        }
+    };
+
+    for response in responses {
+        let _ = app.emit(EventName::ConfigResponse.as_str(), response);
+    }
 
-    if let Some(ref sections) = settings.cleanup_prompt_sections {
+    Ok(())
+}
+
+/// Sync each setting with its own request, for servers without the bulk endpoint
+async fn sync_individually(app: &AppHandle, sync: &ConfigSyncState, snapshot: &ConfigSnapshot) {
+    if let Some(ref sections) = snapshot.prompt_sections {
         match sync.sync_prompt_sections(sections).await {
             Ok(()) => {
                 let _ = app.emit(
@@ -30,16 +77,33 @@ pub async fn set_server_connected(
                 );
             }
             Err(e) => {
-                log::warn!("Failed to sync prompt sections on connect: {e}");
+                if let Some(response) = sync_error_response(ConfigSetting::PromptSections, &e) {
+                    log::warn!("Failed to sync prompt sections on connect: {e}");
+                    let _ = app.emit(EventName::ConfigResponse.as_str(), response);
+                }
+            }
+        }
+    }
+
+    if !snapshot.profile_prompt_overrides.is_empty() {
+        let overrides = &snapshot.profile_prompt_overrides;
+        match sync.sync_profile_prompt_overrides(overrides).await {
+            Ok(()) => {
                 let _ = app.emit(
                     EventName::ConfigResponse.as_str(),
-                    ConfigResponse::<()>::error(ConfigSetting::PromptSections, e),
+                    ConfigResponse::updated(ConfigSetting::ProfilePrompts, overrides),
                 );
             }
+            Err(e) => {
+                if let Some(response) = sync_error_response(ConfigSetting::ProfilePrompts, &e) {
+                    log::warn!("Failed to sync profile prompt overrides on connect: {e}");
+                    let _ = app.emit(EventName::ConfigResponse.as_str(), response);
+                }
+            }
         }
     }
 
-    if let Some(timeout) = settings.stt_timeout_seconds {
+    if let Some(timeout) = snapshot.stt_timeout_seconds {
         match sync.sync_stt_timeout(timeout).await {
             Ok(()) => {
                 let _ = app.emit(
@@ -48,25 +112,113 @@ pub async fn set_server_connected(
                 );
             }
             Err(e) => {
-                log::warn!("Failed to sync STT timeout on connect: {e}");
-                let _ = app.emit(
-                    EventName::ConfigResponse.as_str(),
-                    ConfigResponse::<()>::error(ConfigSetting::SttTimeout, e),
-                );
+                if let Some(response) = sync_error_response(ConfigSetting::SttTimeout, &e) {
+                    log::warn!("Failed to sync STT timeout on connect: {e}");
+                    let _ = app.emit(EventName::ConfigResponse.as_str(), response);
+                }
             }
         }
     }
-
-    Ok(())
 }
 
 /// Notify Rust that we've disconnected from the server
-/// This disables config syncing
+/// This disables config syncing; if the user asked to disconnect, reconnect
+/// progress is cleared as the overlay won't retry. The connection state itself
+/// comes from the connection machine via `set_connection_state`.
 #[tauri::command]
 pub async fn set_server_disconnected(
+    reason: Option<DisconnectReason>,
     config_sync: tauri::State<'_, ConfigSync>,
+    reconnect: tauri::State<'_, ReconnectManager>,
 ) -> Result<(), String> {
-    let mut sync = config_sync.write().await;
-    sync.set_disconnected();
+    // Abort syncs in flight first: they hold the read lock until they finish
+    config_sync.read().await.cancel_in_flight();
+    let local_only = {
+        let mut sync = config_sync.write().await;
+        sync.set_disconnected();
+        sync.mode() == OperatingMode::LocalOnly
+    };
+
+    // Nothing to reconnect to in local-only mode
+    if local_only || reason == Some(DisconnectReason::UserRequested) {
+        reconnect.reset();
+    }
     Ok(())
 }
+
+/// Report a connection state change from the overlay's connection machine,
+/// with the reason the connection was lost when it is reconnecting
+#[tauri::command]
+pub fn set_connection_state(app: AppHandle, state: String, reason: Option<DisconnectReason>) {
+    app.state::<AppState>()
+        .connection_state
+        .transition(&state, reason, |payload| {
+            let _ = app.emit(EventName::ConnectionState.as_str(), payload);
+        });
+}
+
+/// Get the current connection state, for windows opened after it was last emitted
+#[tauri::command]
+pub fn get_connection_state(state: tauri::State<'_, AppState>) -> ConnectionStatePayload {
+    state.connection_state.current()
+}
+
+/// Record the retry the overlay's connection machine just scheduled
+#[tauri::command]
+pub fn set_reconnect_scheduled(
+    attempt: u32,
+    retry_in_ms: u64,
+    reconnect: tauri::State<'_, ReconnectManager>,
+) {
+    reconnect.retry_scheduled(attempt, Duration::from_millis(retry_in_ms));
+}
+
+/// Clear reconnect progress: the overlay reconnected, or the user restarted
+/// or stopped reconnecting
+#[tauri::command]
+pub fn reset_reconnect_status(reconnect: tauri::State<'_, ReconnectManager>) {
+    reconnect.reset();
+}
+
+/// Get the current reconnect progress
+#[tauri::command]
+pub fn get_reconnect_status(reconnect: tauri::State<'_, ReconnectManager>) -> ReconnectStatus {
+    reconnect.status()
+}
+
+/// Get config sync counters, including the idempotency key of the last failed sync
+#[tauri::command]
+pub async fn get_sync_metrics(
+    config_sync: tauri::State<'_, ConfigSync>,
+) -> Result<SyncMetrics, String> {
+    Ok(config_sync.read().await.metrics())
+}
+
+/// Get whether each synced setting is pending, synced or failed, keyed by the
+/// setting names used in config responses. Complements the `config-response`
+/// events, which only report each outcome once.
+#[tauri::command]
+pub async fn sync_status(
+    config_sync: tauri::State<'_, ConfigSync>,
+) -> Result<HashMap<ConfigSetting, SyncStatus>, String> {
+    Ok(config_sync.read().await.sync_status())
+}
+
+/// Turn config sync dry-run mode on or off. While on, syncs log the request
+/// they would send (see `get_sync_metrics` for the last one) instead of sending it.
+#[tauri::command]
+pub async fn set_config_sync_dry_run(
+    config_sync: tauri::State<'_, ConfigSync>,
+    enabled: bool,
+) -> Result<(), String> {
+    config_sync.write().await.set_dry_run(enabled);
+    Ok(())
+}
+
+/// Normalized path the server's API is mounted under, for building endpoint URLs
+#[tauri::command]
+pub async fn get_api_base_path(
+    config_sync: tauri::State<'_, ConfigSync>,
+) -> Result<String, String> {
+    Ok(config_sync.read().await.api_base_path().to_string())
+}
@@ -1,14 +1,62 @@
+use crate::events::{EventName, NotificationPayload};
 use crate::history::{HistoryEntry, HistoryStorage};
-use tauri::State;
+use crate::recording_id::with_recording_id;
+use crate::recording_timer::duration_ms;
+use crate::state::{AppState, ShortcutState};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::oneshot;
 
-/// Add a new entry to the dictation history
+#[cfg(desktop)]
+use crate::commands::settings::get_setting_from_store;
+#[cfg(desktop)]
+use crate::settings::{StoreKey, DEFAULT_HISTORY_DEDUP_WINDOW_SECONDS};
+
+/// Window in which a repeated entry refreshes the previous one, if dedup is enabled
+#[cfg(desktop)]
+fn history_dedup_window(app: &AppHandle) -> Option<chrono::Duration> {
+    if !get_setting_from_store(app, StoreKey::HistoryDedupEnabled, false) {
+        return None;
+    }
+    let seconds: u64 = get_setting_from_store(
+        app,
+        StoreKey::HistoryDedupWindowSeconds,
+        DEFAULT_HISTORY_DEDUP_WINDOW_SECONDS,
+    );
+    chrono::Duration::try_seconds(i64::try_from(seconds).unwrap_or(i64::MAX))
+}
+
+#[cfg(not(desktop))]
+fn history_dedup_window(_app: &AppHandle) -> Option<chrono::Duration> {
+    None
+}
+
+/// Add a new entry to the dictation history, with the duration of the recording it came from
 #[tauri::command]
 pub async fn add_history_entry(
+    app: AppHandle,
     text: String,
     raw_text: String,
     history: State<'_, HistoryStorage>,
+    state: State<'_, AppState>,
 ) -> Result<HistoryEntry, String> {
-    history.add_entry(text, raw_text)
+    let duration_ms = state
+        .recording_timer
+        .lock()
+        .unwrap()
+        .take_last_duration()
+        .map(duration_ms);
+    let recording_id = state
+        .recording_ids
+        .lock()
+        .unwrap()
+        .current()
+        .map(str::to_string);
+    log::debug!(
+        "{}",
+        with_recording_id(recording_id.as_deref(), "Adding history entry")
+    );
+    history.add_entry(text, raw_text, duration_ms, history_dedup_window(&app))
 }
 
 /// Get dictation history entries
@@ -34,3 +82,75 @@ pub async fn delete_history_entry(
 pub async fn clear_history(history: State<'_, HistoryStorage>) -> Result<(), String> {
     history.clear()
 }
+
+/// Delete history entries older than `older_than_days` days and return how many
+/// were removed. 0 deletes all history and needs `delete_all`.
+#[tauri::command]
+pub async fn prune_history(
+    older_than_days: u32,
+    delete_all: Option<bool>,
+    history: State<'_, HistoryStorage>,
+) -> Result<usize, String> {
+    let removed = history.prune(older_than_days, delete_all.unwrap_or(false))?;
+    log::info!("Pruned {removed} history entries older than {older_than_days} days");
+    Ok(removed)
+}
+
+/// Paste the most recent history entry into the active window.
+/// Returns `false` and emits a notification when history is empty.
+/// Must run on the main thread (see `type_text`).
+pub(crate) fn paste_last_entry_blocking(app: &AppHandle) -> Result<bool, String> {
+    let history = app.state::<HistoryStorage>();
+
+    let Some(entry) = history.latest()? else {
+        log::info!("PasteLast: no history entries available");
+        let _ = app.emit(
+            EventName::Notification.as_str(),
+            NotificationPayload {
+                message: "Nothing to paste yet - dictate something first".to_string(),
+            },
+        );
+        return Ok(false);
+    };
+
+    super::text::type_text_blocking(&entry.text)?;
+    Ok(true)
+}
+
+/// Puts the shortcut state back to idle when dropped, however the paste ends
+struct IdleOnDrop<'a>(&'a Mutex<ShortcutState>);
+
+impl Drop for IdleOnDrop<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.0.lock() {
+            *state = ShortcutState::Idle;
+        }
+    }
+}
+
+/// Paste the most recent transcription into the active window
+#[tauri::command]
+pub async fn paste_last_transcription(app: AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+
+    // Go through the same state as the paste hotkey so recording can't start mid-paste
+    {
+        let mut current_state = state.shortcut_state.lock().unwrap();
+        if *current_state != ShortcutState::Idle {
+            log::info!("PasteLast: ignoring request in state {:?}", *current_state);
+            return Ok(());
+        }
+        *current_state = ShortcutState::WaitingForPasteKeyRelease;
+    }
+    let _idle = IdleOnDrop(&state.shortcut_state);
+
+    let (tx, rx) = oneshot::channel::<Result<bool, String>>();
+    let app_handle = app.clone();
+    app.run_on_main_thread(move || {
+        let _ = tx.send(paste_last_entry_blocking(&app_handle));
+    })
+    .map_err(|e| e.to_string())?;
+
+    // A dropped sender means the main-thread side never ran the paste
+    rx.await.map_err(|e| e.to_string())?.map(|_| ())
+}
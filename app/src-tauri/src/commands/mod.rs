@@ -1,3 +1,4 @@
+pub mod active_window;
 pub mod config_sync;
 pub mod export_import;
 pub mod history;
@@ -1,5 +1,18 @@
 use tauri::{AppHandle, Manager};
 
+#[cfg(desktop)]
+use crate::commands::settings::get_setting_from_store;
+#[cfg(desktop)]
+use crate::events::EventName;
+#[cfg(desktop)]
+use crate::settings::StoreKey;
+#[cfg(desktop)]
+use crate::state::AppState;
+#[cfg(desktop)]
+use std::time::{Duration, Instant};
+#[cfg(desktop)]
+use tauri::Emitter;
+
 #[tauri::command]
 pub async fn resize_overlay(app: AppHandle, width: f64, height: f64) -> Result<(), String> {
     // Enforce minimum dimensions to prevent invisible window
@@ -37,3 +50,39 @@ pub async fn resize_overlay(app: AppHandle, width: f64, height: f64) -> Result<(
     }
     Ok(())
 }
+
+/// Start the overlay auto-hide timer once a transcription has completed.
+/// Does nothing when `overlay_auto_hide_ms` is 0.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn schedule_overlay_auto_hide(app: AppHandle) {
+    let timeout_ms: u64 = get_setting_from_store(&app, StoreKey::OverlayAutoHideMs, 0);
+    let Some(ticket) = app
+        .state::<AppState>()
+        .overlay_auto_hide
+        .lock()
+        .unwrap()
+        .schedule(Duration::from_millis(timeout_ms))
+    else {
+        return;
+    };
+
+    std::thread::spawn(move || {
+        std::thread::sleep(ticket.deadline.saturating_duration_since(Instant::now()));
+        let state = app.state::<AppState>();
+        // Hide while holding the lock, so a recording starting now can't show it first
+        let mut auto_hide = state.overlay_auto_hide.lock().unwrap();
+        if !auto_hide.fire(ticket) {
+            return;
+        }
+        if let Some(overlay) = app.get_webview_window("overlay") {
+            let _ = overlay.hide();
+        }
+        log::info!("Overlay hidden after {timeout_ms} ms");
+        let _ = app.emit(EventName::OverlayHidden.as_str(), ());
+    });
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn schedule_overlay_auto_hide(_app: AppHandle) {}
@@ -1,10 +1,22 @@
+use crate::profile_matcher::ProfileRule;
 use crate::settings::{
     check_hotkey_conflict, AppSettings, CleanupPromptSections, HotkeyConfig, HotkeyType,
-    SettingsError, StoreKey, DEFAULT_SERVER_URL,
+    OperatingMode, PromptSectionOverrides, SettingsError, StoreKey, DEFAULT_API_BASE_PATH,
+    DEFAULT_HISTORY_DEDUP_WINDOW_SECONDS, DEFAULT_SERVER_URL,
 };
 use crate::state::{AppState, ShortcutErrors, ShortcutRegistrationResult};
+use std::collections::HashMap;
 use tauri::{AppHandle, Manager};
 
+#[cfg(desktop)]
+use crate::config_sync::SyncError;
+#[cfg(desktop)]
+use crate::disabled_apps::DisabledApps;
+#[cfg(desktop)]
+use crate::events::EventName;
+#[cfg(desktop)]
+use tauri::Emitter;
+
 #[cfg(desktop)]
 use tauri_plugin_global_shortcut::GlobalShortcutExt;
 
@@ -50,7 +62,10 @@ pub(crate) fn get_setting_from_store<T: serde::de::DeserializeOwned>(
 #[cfg(desktop)]
 #[tauri::command]
 pub async fn register_shortcuts(app: AppHandle) -> Result<ShortcutRegistrationResult, String> {
-    Ok(crate::do_register_shortcuts(&app))
+    // Retries sleep, so keep them off the async runtime's workers
+    tauri::async_runtime::spawn_blocking(move || crate::do_register_shortcuts(&app))
+        .await
+        .map_err(|e| e.to_string())
 }
 
 // Stub for non-desktop platforms
@@ -173,6 +188,43 @@ pub fn get_settings(app: AppHandle) -> Result<AppSettings, String> {
             StoreKey::TranslationDefaultLanguage,
             "en".to_string(),
         ),
+        profile_rules: get_setting_from_store(&app, StoreKey::ProfileRules, Vec::new()),
+        disabled_apps: get_setting_from_store(&app, StoreKey::DisabledApps, Vec::new()),
+        suppress_recording_in_disabled_apps: get_setting_from_store(
+            &app,
+            StoreKey::SuppressRecordingInDisabledApps,
+            false,
+        ),
+        store_empty_recordings: get_setting_from_store(&app, StoreKey::StoreEmptyRecordings, false),
+        history_dedup_enabled: get_setting_from_store(&app, StoreKey::HistoryDedupEnabled, false),
+        history_dedup_window_seconds: get_setting_from_store(
+            &app,
+            StoreKey::HistoryDedupWindowSeconds,
+            DEFAULT_HISTORY_DEDUP_WINDOW_SECONDS,
+        ),
+        operating_mode: get_setting_from_store(
+            &app,
+            StoreKey::OperatingMode,
+            OperatingMode::default(),
+        ),
+        overlay_auto_hide_ms: get_setting_from_store(&app, StoreKey::OverlayAutoHideMs, 0),
+        profile_prompt_overrides: get_setting_from_store(
+            &app,
+            StoreKey::ProfilePromptOverrides,
+            HashMap::new(),
+        ),
+        min_recording_ms: get_setting_from_store(&app, StoreKey::MinRecordingMs, 0),
+        active_window_detection_enabled: get_setting_from_store(
+            &app,
+            StoreKey::ActiveWindowDetectionEnabled,
+            true,
+        ),
+        history_retention_days: get_setting_from_store(&app, StoreKey::HistoryRetentionDays, None),
+        api_base_path: get_setting_from_store(
+            &app,
+            StoreKey::ApiBasePath,
+            DEFAULT_API_BASE_PATH.to_string(),
+        ),
     })
 }
 
@@ -252,6 +304,256 @@ pub async fn update_sound_enabled(_app: AppHandle, _enabled: bool) -> Result<(),
     Ok(())
 }
 
+/// Update the profile matching rules. They take effect immediately; if any
+/// pattern is invalid the update is rejected and the previous rules stay active.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn update_profile_rules(app: AppHandle, rules: Vec<ProfileRule>) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    crate::profile_matcher::replace_rules(state.profile_selection.rules(), rules.clone()).map_err(
+        |e| {
+            log::warn!("Rejected profile rules update: {e}");
+            e.to_string()
+        },
+    )?;
+
+    crate::save_setting_to_store(&app, StoreKey::ProfileRules, &rules)?;
+    log::info!("Updated profile rules ({} rules)", rules.len());
+
+    let _ = app.emit(EventName::SettingsChanged.as_str(), ());
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn update_profile_rules(_app: AppHandle, _rules: Vec<ProfileRule>) -> Result<(), String> {
+    Ok(())
+}
+
+/// Update the apps the overlay is hidden in. Patterns are validated and take
+/// effect immediately; if any is invalid the previous list stays active.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn update_disabled_apps(app: AppHandle, apps: Vec<String>) -> Result<(), String> {
+    let disabled_apps = DisabledApps::compile(&apps).map_err(|e| {
+        log::warn!("Rejected disabled apps update: {e}");
+        e.to_string()
+    })?;
+    crate::save_setting_to_store(&app, StoreKey::DisabledApps, &apps)?;
+    *app.state::<AppState>().disabled_apps.write().unwrap() = disabled_apps;
+    log::info!("Updated disabled apps: {apps:?}");
+
+    let _ = app.emit(EventName::SettingsChanged.as_str(), ());
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn update_disabled_apps(_app: AppHandle, _apps: Vec<String>) -> Result<(), String> {
+    Ok(())
+}
+
+/// Update whether disabled apps block recording entirely
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn update_suppress_recording_in_disabled_apps(
+    app: AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    crate::save_setting_to_store(&app, StoreKey::SuppressRecordingInDisabledApps, &enabled)?;
+    log::info!("Updated suppress recording in disabled apps: {enabled}");
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn update_suppress_recording_in_disabled_apps(
+    _app: AppHandle,
+    _enabled: bool,
+) -> Result<(), String> {
+    Ok(())
+}
+
+/// Update whether recordings that produced no text are kept in history
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn update_store_empty_recordings(app: AppHandle, enabled: bool) -> Result<(), String> {
+    crate::save_setting_to_store(&app, StoreKey::StoreEmptyRecordings, &enabled)?;
+    log::info!("Updated store empty recordings: {enabled}");
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn update_store_empty_recordings(_app: AppHandle, _enabled: bool) -> Result<(), String> {
+    Ok(())
+}
+
+/// Switch between connecting to a server and running locally only.
+/// Going local-only drops the server connection and stops reconnecting.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn update_operating_mode(
+    app: AppHandle,
+    mode: OperatingMode,
+    config_sync: tauri::State<'_, crate::config_sync::ConfigSync>,
+    reconnect: tauri::State<'_, crate::reconnect::ReconnectManager>,
+) -> Result<(), String> {
+    crate::save_setting_to_store(&app, StoreKey::OperatingMode, &mode)?;
+    config_sync.write().await.set_mode(mode);
+    if mode == OperatingMode::LocalOnly {
+        reconnect.reset();
+    }
+    log::info!("Updated operating mode: {mode:?}");
+
+    let _ = app.emit(EventName::SettingsChanged.as_str(), ());
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn update_operating_mode(_app: AppHandle, _mode: OperatingMode) -> Result<(), String> {
+    Ok(())
+}
+
+/// Update how long the overlay stays up after a transcription (0 = until next action)
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn update_overlay_auto_hide_ms(app: AppHandle, ms: u64) -> Result<(), String> {
+    crate::save_setting_to_store(&app, StoreKey::OverlayAutoHideMs, &ms)?;
+    log::info!("Updated overlay auto-hide: {ms} ms");
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn update_overlay_auto_hide_ms(_app: AppHandle, _ms: u64) -> Result<(), String> {
+    Ok(())
+}
+
+/// Turn active window detection on or off. While off, no window is inspected
+/// and recordings use the default profile.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn update_active_window_detection_enabled(
+    app: AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    crate::save_setting_to_store(&app, StoreKey::ActiveWindowDetectionEnabled, &enabled)?;
+    crate::active_window::set_detection_enabled(enabled);
+    log::info!("Updated active window detection: {enabled}");
+
+    let _ = app.emit(EventName::SettingsChanged.as_str(), ());
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn update_active_window_detection_enabled(
+    _app: AppHandle,
+    _enabled: bool,
+) -> Result<(), String> {
+    Ok(())
+}
+
+/// Update the path the server's API is mounted under (default `/api`), for
+/// servers behind a reverse proxy at a sub-path. The path is stored normalized.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn update_api_base_path(
+    app: AppHandle,
+    path: String,
+    config_sync: tauri::State<'_, crate::config_sync::ConfigSync>,
+) -> Result<(), String> {
+    let path = crate::config_sync::normalize_api_base_path(&path);
+    crate::save_setting_to_store(&app, StoreKey::ApiBasePath, &path)?;
+    config_sync.write().await.set_api_base_path(&path);
+
+    let _ = app.emit(EventName::SettingsChanged.as_str(), ());
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn update_api_base_path(_app: AppHandle, _path: String) -> Result<(), String> {
+    Ok(())
+}
+
+/// Update the minimum recording length; shorter recordings are discarded (0 = keep all)
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn update_min_recording_ms(app: AppHandle, ms: u64) -> Result<(), String> {
+    crate::save_setting_to_store(&app, StoreKey::MinRecordingMs, &ms)?;
+    log::info!("Updated minimum recording length: {ms} ms");
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn update_min_recording_ms(_app: AppHandle, _ms: u64) -> Result<(), String> {
+    Ok(())
+}
+
+/// Update whether repeated history entries are deduplicated
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn update_history_dedup_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
+    crate::save_setting_to_store(&app, StoreKey::HistoryDedupEnabled, &enabled)?;
+    log::info!("Updated history dedup enabled: {enabled}");
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn update_history_dedup_enabled(_app: AppHandle, _enabled: bool) -> Result<(), String> {
+    Ok(())
+}
+
+/// Update the window in which a repeated history entry counts as a duplicate
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn update_history_dedup_window_seconds(
+    app: AppHandle,
+    seconds: u64,
+) -> Result<(), String> {
+    crate::save_setting_to_store(&app, StoreKey::HistoryDedupWindowSeconds, &seconds)?;
+    log::info!("Updated history dedup window: {seconds}s");
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn update_history_dedup_window_seconds(
+    _app: AppHandle,
+    _seconds: u64,
+) -> Result<(), String> {
+    Ok(())
+}
+
+/// Update how many days history is kept (`None` = forever). Applied on the next startup.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn update_history_retention_days(
+    app: AppHandle,
+    days: Option<u32>,
+) -> Result<(), String> {
+    if days == Some(0) {
+        return Err("History retention must be at least 1 day".to_string());
+    }
+    crate::save_setting_to_store(&app, StoreKey::HistoryRetentionDays, &days)?;
+    log::info!("Updated history retention: {days:?} days");
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn update_history_retention_days(
+    _app: AppHandle,
+    _days: Option<u32>,
+) -> Result<(), String> {
+    Ok(())
+}
+
 /// Update cleanup prompt sections
 #[cfg(desktop)]
 #[tauri::command]
@@ -266,9 +568,13 @@ pub async fn update_cleanup_prompt_sections(
 
     // Sync to server
     if let Some(ref s) = sections {
-        if let Err(e) = config_sync.read().await.sync_prompt_sections(s).await {
-            log::warn!("Failed to sync prompt sections to server: {e}");
-            return Err(e);
+        match config_sync.read().await.sync_prompt_sections(s).await {
+            Ok(())
+            | Err(SyncError::NotConnected | SyncError::Cancelled | SyncError::Superseded) => {}
+            Err(e) => {
+                log::warn!("Failed to sync prompt sections to server: {e}");
+                return Err(e.to_string());
+            }
         }
     }
 
@@ -284,6 +590,46 @@ pub async fn update_cleanup_prompt_sections(
     Ok(())
 }
 
+/// Update the prompt section overrides of each formatting profile
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn update_profile_prompt_overrides(
+    app: AppHandle,
+    overrides: HashMap<String, PromptSectionOverrides>,
+    config_sync: tauri::State<'_, crate::config_sync::ConfigSync>,
+) -> Result<(), String> {
+    crate::save_setting_to_store(&app, StoreKey::ProfilePromptOverrides, &overrides)?;
+    log::info!("Updated prompt overrides for {} profiles", overrides.len());
+
+    match config_sync
+        .read()
+        .await
+        .sync_profile_prompt_overrides(&overrides)
+        .await
+    {
+        Ok(())
+        | Err(
+            SyncError::NotConnected
+            | SyncError::Cancelled
+            | SyncError::Superseded
+            | SyncError::Unsupported,
+        ) => Ok(()),
+        Err(e) => {
+            log::warn!("Failed to sync profile prompt overrides to server: {e}");
+            Err(e.to_string())
+        }
+    }
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn update_profile_prompt_overrides(
+    _app: AppHandle,
+    _overrides: HashMap<String, PromptSectionOverrides>,
+) -> Result<(), String> {
+    Ok(())
+}
+
 /// Update STT provider
 #[cfg(desktop)]
 #[tauri::command]
@@ -343,9 +689,13 @@ pub async fn update_stt_timeout(
 
     // Sync to server
     if let Some(timeout) = timeout_seconds {
-        if let Err(e) = config_sync.read().await.sync_stt_timeout(timeout).await {
-            log::warn!("Failed to sync STT timeout to server: {e}");
-            return Err(e);
+        match config_sync.read().await.sync_stt_timeout(timeout).await {
+            Ok(())
+            | Err(SyncError::NotConnected | SyncError::Cancelled | SyncError::Superseded) => {}
+            Err(e) => {
+                log::warn!("Failed to sync STT timeout to server: {e}");
+                return Err(e.to_string());
+            }
         }
     }
 
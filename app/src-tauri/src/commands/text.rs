@@ -3,10 +3,13 @@ use enigo::{Direction, Enigo, Key, Keyboard, Settings};
 use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 use tauri_plugin_store::StoreExt;
 
+use crate::clipboard::ClipboardGuard;
+use crate::recording_id::with_recording_id;
 use crate::settings::{StoreKey, DEFAULT_SERVER_URL};
+use crate::state::AppState;
 
 /// Delay after clipboard operations to ensure system stability
 const CLIPBOARD_STABILIZATION_DELAY_MS: u64 = 50;
@@ -29,6 +32,21 @@ pub async fn get_server_url(app: AppHandle) -> Result<String, String> {
 
 #[tauri::command]
 pub async fn type_text(app: AppHandle, text: String) -> Result<(), String> {
+    let recording_id = app
+        .state::<AppState>()
+        .recording_ids
+        .lock()
+        .unwrap()
+        .current()
+        .map(str::to_string);
+    log::info!(
+        "{}",
+        with_recording_id(
+            recording_id.as_deref(),
+            format_args!("Pasting transcription ({} chars)", text.chars().count())
+        )
+    );
+
     // macOS HIToolbox APIs (used by enigo) must run on the main thread
     // Use a channel to get the result back from the main thread
     let (tx, rx) = mpsc::channel::<Result<(), String>>();
@@ -44,14 +62,15 @@ pub async fn type_text(app: AppHandle, text: String) -> Result<(), String> {
 }
 
 /// Type text using clipboard and paste. Used internally by shortcut handlers.
+/// The previous clipboard text is restored afterwards, including when the paste fails.
 pub fn type_text_blocking(text: &str) -> Result<(), String> {
     let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
 
-    // Save previous clipboard content
-    let previous = clipboard.get_text().unwrap_or_default();
+    // Save previous clipboard content; dropping the guard on an early return restores it
+    let mut guard = ClipboardGuard::new(&mut clipboard);
 
     // Set new text
-    clipboard.set_text(text).map_err(|e| e.to_string())?;
+    guard.set_text(text)?;
 
     // Small delay for clipboard to stabilize
     thread::sleep(Duration::from_millis(CLIPBOARD_STABILIZATION_DELAY_MS));
@@ -68,17 +87,23 @@ pub fn type_text_blocking(text: &str) -> Result<(), String> {
         .key(modifier, Direction::Press)
         .map_err(|e| e.to_string())?;
     thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
-    enigo
+    let paste_result = enigo
         .key(Key::Unicode('v'), Direction::Click)
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.to_string());
     thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
-    enigo
+    // Always release the modifier, even if the paste keystroke failed
+    let release_result = enigo
         .key(modifier, Direction::Release)
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.to_string());
+    paste_result?;
+    release_result?;
 
-    // Restore previous clipboard after a delay
+    // Restore previous clipboard after a delay (when the guard drops)
     thread::sleep(Duration::from_millis(CLIPBOARD_RESTORE_DELAY_MS));
-    let _ = clipboard.set_text(&previous);
+    if guard.get_text().as_deref() != Some(text) {
+        // Something else was copied since the paste - keep it
+        guard.commit();
+    }
 
     Ok(())
 }
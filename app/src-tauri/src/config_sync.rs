@@ -1,19 +1,668 @@
-use serde::Serialize;
-use std::sync::Arc;
-use std::time::Duration;
-use tauri_plugin_http::reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri_plugin_http::reqwest::{self, Client, StatusCode};
 use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
 
-use crate::settings::CleanupPromptSections;
+use crate::events::{ConfigResponse, ConfigSetting};
+use crate::settings::{
+    AppSettings, CleanupPromptSections, OperatingMode, PromptSectionOverrides,
+    DEFAULT_API_BASE_PATH,
+};
 
 /// Default STT timeout in seconds (matches server's `DEFAULT_TRANSCRIPTION_WAIT_TIMEOUT_SECONDS`)
 pub const DEFAULT_STT_TIMEOUT_SECONDS: f64 = 0.5;
 
+/// Header the server uses to drop duplicate deliveries of the same config update
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Header identifying this client to the server
+pub const CLIENT_UUID_HEADER: &str = "X-Client-UUID";
+
+/// Headers that identify or authenticate the client, never written to logs
+const REDACTED_HEADERS: &[&str] = &[CLIENT_UUID_HEADER, "Authorization"];
+
+/// Settings whose endpoint older servers don't have, so a 404 means the server
+/// doesn't support the setting rather than that the sync failed
+const SETTINGS_NEWER_THAN_SERVER: &[ConfigSetting] = &[ConfigSetting::ProfilePrompts];
+
+/// Normalize an API base path to `/segment/...` with no trailing slash.
+/// Empty segments are dropped, so `""` and `"/"` mean the server root.
+pub fn normalize_api_base_path(path: &str) -> String {
+    let segments: Vec<&str> = path
+        .split('/')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    if segments.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", segments.join("/"))
+    }
+}
+
+/// URL of an endpoint (`/config/prompts`) on a server whose API lives under
+/// `base_path` (already normalized)
+pub fn endpoint_url(server_url: &str, base_path: &str, endpoint: &str) -> String {
+    format!("{}{base_path}{endpoint}", server_url.trim_end_matches('/'))
+}
+
+/// Errors that can occur while syncing config to the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncError {
+    /// No server connection to sync to
+    NotConnected,
+    /// The request never got a response (connection refused, timeout, bad URL...)
+    Network(String),
+    /// The server responded with a non-success status
+    Http { status: u16, body: String },
+    /// The server rejected this client's credentials (401/403)
+    Unauthorized,
+    /// The request or response body couldn't be (de)serialized
+    Serialization(String),
+    /// The server disconnected while the sync was in flight
+    Cancelled,
+    /// A newer change to the same setting came in while this one waited its
+    /// turn, so this one was dropped unsent
+    Superseded,
+    /// The server doesn't have the endpoint for this setting (older server)
+    Unsupported,
+}
+
+impl SyncError {
+    /// Map a non-success HTTP status to the matching variant
+    pub fn from_status(status: u16, body: impl Into<String>) -> Self {
+        if status == StatusCode::UNAUTHORIZED.as_u16() || status == StatusCode::FORBIDDEN.as_u16() {
+            Self::Unauthorized
+        } else {
+            Self::Http {
+                status,
+                body: body.into(),
+            }
+        }
+    }
+
+    /// Whether retrying the same request might succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Network(_) => true,
+            Self::Http { status, .. } => *status >= 500,
+            Self::NotConnected
+            | Self::Unauthorized
+            | Self::Serialization(_)
+            | Self::Cancelled
+            | Self::Superseded
+            | Self::Unsupported => false,
+        }
+    }
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotConnected => write!(f, "Not connected to a server"),
+            Self::Network(e) => write!(f, "Network error: {e}"),
+            Self::Http { status, body } if body.is_empty() => {
+                write!(f, "Server returned HTTP {status}")
+            }
+            Self::Http { status, body } => write!(f, "Server returned HTTP {status}: {body}"),
+            Self::Unauthorized => write!(f, "Server rejected this client's credentials"),
+            Self::Serialization(e) => write!(f, "Invalid config data: {e}"),
+            Self::Cancelled => write!(f, "Sync cancelled because the server disconnected"),
+            Self::Superseded => write!(f, "Sync dropped for a newer change to the same setting"),
+            Self::Unsupported => write!(f, "Server doesn't support this setting"),
+        }
+    }
+}
+
+impl std::error::Error for SyncError {}
+
+/// Config error response to show the user for a failed sync. Cancelled and
+/// superseded syncs, and settings an older server doesn't know, aren't failures
+/// worth reporting, so they get none.
+pub fn sync_error_response(
+    setting: ConfigSetting,
+    error: &SyncError,
+) -> Option<ConfigResponse<()>> {
+    (!matches!(
+        error,
+        SyncError::Cancelled | SyncError::Superseded | SyncError::Unsupported
+    ))
+    .then(|| ConfigResponse::<()>::error(setting, error))
+}
+
+impl From<reqwest::Error> for SyncError {
+    fn from(e: reqwest::Error) -> Self {
+        if let Some(status) = e.status() {
+            Self::from_status(status.as_u16(), String::new())
+        } else if e.is_decode() {
+            Self::Serialization(e.to_string())
+        } else {
+            Self::Network(e.to_string())
+        }
+    }
+}
+
+// ============================================================================
+// TRANSPORT
+// ============================================================================
+
+/// Status and body of a config endpoint response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransportResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl TransportResponse {
+    /// Turn a non-success response into a `SyncError`, keeping the body for context
+    pub fn error_for_status(self) -> Result<Self, SyncError> {
+        if (200..300).contains(&self.status) {
+            Ok(self)
+        } else {
+            Err(SyncError::from_status(self.status, self.body))
+        }
+    }
+}
+
+/// Future returned by `ConfigTransport` requests
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<TransportResponse, SyncError>> + Send + 'a>>;
+
+/// Sends config requests to the server. Abstracted so syncing can be tested
+/// without a network.
+pub trait ConfigTransport: Send + Sync {
+    fn put_json(
+        &self,
+        url: String,
+        headers: Vec<(&'static str, String)>,
+        body: Value,
+    ) -> TransportFuture<'_>;
+
+    fn post_json(
+        &self,
+        url: String,
+        headers: Vec<(&'static str, String)>,
+        body: Value,
+    ) -> TransportFuture<'_>;
+}
+
+/// The real transport, over HTTP
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl Default for ReqwestTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReqwestTransport {
+    pub fn new() -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .expect("Failed to create HTTP client"),
+        }
+    }
+
+    fn send(
+        request: reqwest::RequestBuilder,
+        headers: Vec<(&'static str, String)>,
+        body: Value,
+    ) -> TransportFuture<'static> {
+        Box::pin(async move {
+            let request = headers.into_iter().fold(request, |request, (name, value)| {
+                request.header(name, value)
+            });
+            let response = request.json(&body).send().await?;
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            Ok(TransportResponse { status, body })
+        })
+    }
+}
+
+impl ConfigTransport for ReqwestTransport {
+    fn put_json(
+        &self,
+        url: String,
+        headers: Vec<(&'static str, String)>,
+        body: Value,
+    ) -> TransportFuture<'_> {
+        Self::send(self.client.put(url), headers, body)
+    }
+
+    fn post_json(
+        &self,
+        url: String,
+        headers: Vec<(&'static str, String)>,
+        body: Value,
+    ) -> TransportFuture<'_> {
+        Self::send(self.client.post(url), headers, body)
+    }
+}
+
+fn to_json<T: Serialize + ?Sized>(value: &T) -> Result<Value, SyncError> {
+    serde_json::to_value(value).map_err(|e| SyncError::Serialization(e.to_string()))
+}
+
+/// A request built but not sent in dry-run mode, with credentials redacted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DryRunRequest {
+    pub method: &'static str,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    /// The JSON body exactly as it would have been sent
+    pub body: String,
+}
+
+impl DryRunRequest {
+    pub fn new(
+        method: &'static str,
+        url: String,
+        headers: &[(&'static str, String)],
+        body: &Value,
+    ) -> Self {
+        let headers = headers
+            .iter()
+            .map(|(name, value)| {
+                let redacted = REDACTED_HEADERS
+                    .iter()
+                    .any(|secret| secret.eq_ignore_ascii_case(name));
+                let value = if redacted {
+                    "<redacted>"
+                } else {
+                    value.as_str()
+                };
+                ((*name).to_string(), value.to_string())
+            })
+            .collect();
+        Self {
+            method,
+            url,
+            headers,
+            body: body.to_string(),
+        }
+    }
+
+    /// The request as a curl command, for replaying it by hand
+    pub fn to_curl(&self) -> String {
+        let mut parts = vec![
+            "curl".to_string(),
+            format!("-X {}", self.method),
+            shell_quote(&self.url),
+        ];
+        parts.extend(
+            self.headers
+                .iter()
+                .map(|(name, value)| format!("-H {}", shell_quote(&format!("{name}: {value}")))),
+        );
+        parts.push("-H 'Content-Type: application/json'".to_string());
+        parts.push(format!("-d {}", shell_quote(&self.body)));
+        parts.join(" ")
+    }
+}
+
+/// Quote `s` as a single POSIX shell word
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+// ============================================================================
+// SYNC STATE
+// ============================================================================
+
+/// Identifies one logical config update. Generated once when a sync starts and
+/// sent with every retry of it, so a retry of a request that actually reached
+/// the server isn't applied twice.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IdempotencyKey(String);
+
+impl IdempotencyKey {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4().to_string())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for IdempotencyKey {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How often a config update is retried after a transient failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first
+    pub max_attempts: u32,
+    /// Pause between attempts
+    pub delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            delay: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Run one sync intent, retrying transient failures with the same idempotency key.
+/// Returns the final result and the number of attempts made.
+pub async fn retry_sync<F, Fut>(
+    policy: RetryPolicy,
+    key: &IdempotencyKey,
+    mut attempt: F,
+) -> (Result<(), SyncError>, u32)
+where
+    F: FnMut(IdempotencyKey) -> Fut,
+    Fut: Future<Output = Result<(), SyncError>>,
+{
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match attempt(key.clone()).await {
+            Err(e) if e.is_retryable() && attempts < policy.max_attempts => {
+                log::debug!(
+                    "Config sync attempt {attempts} failed ({e}), retrying with key {}",
+                    key.as_str()
+                );
+                if !policy.delay.is_zero() {
+                    tokio::time::sleep(policy.delay).await;
+                }
+            }
+            result => return (result, attempts),
+        }
+    }
+}
+
+/// Details of the most recent failed sync, for debugging
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SyncErrorContext {
+    pub error: String,
+    /// Key sent with every attempt, to find the request in server logs
+    pub idempotency_key: String,
+    pub attempts: u32,
+}
+
+/// Counters for config syncs since the app started
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SyncMetrics {
+    pub succeeded: u64,
+    pub failed: u64,
+    /// Attempts beyond the first, across all syncs
+    pub retries: u64,
+    pub last_error: Option<SyncErrorContext>,
+    /// Requests built but not sent because dry-run mode is on
+    pub dry_runs: u64,
+    pub last_dry_run: Option<DryRunRequest>,
+}
+
+impl SyncMetrics {
+    /// Record the outcome of one sync intent
+    pub fn record(&mut self, key: &IdempotencyKey, attempts: u32, result: &Result<(), SyncError>) {
+        self.retries += u64::from(attempts.saturating_sub(1));
+        match result {
+            Ok(()) => self.succeeded += 1,
+            // Dropped on purpose, not a failure
+            Err(SyncError::Cancelled | SyncError::Superseded) => {}
+            Err(e) => {
+                self.failed += 1;
+                self.last_error = Some(SyncErrorContext {
+                    error: e.to_string(),
+                    idempotency_key: key.as_str().to_string(),
+                    attempts,
+                });
+            }
+        }
+    }
+
+    /// Record a request that dry-run mode built instead of sending
+    pub fn record_dry_run(&mut self, request: DryRunRequest) {
+        self.dry_runs += 1;
+        self.last_dry_run = Some(request);
+    }
+}
+
+/// Where the last change to a setting stands with the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncStatus {
+    /// Sent (or about to be) and not answered yet. A change made while
+    /// disconnected, or a sync cancelled by a disconnect, stays pending until
+    /// the next connect syncs it again.
+    Pending,
+    /// The server applied it
+    Synced { at: Instant },
+    /// The server rejected it or couldn't be reached, retries included
+    Failed { error: String },
+}
+
+impl SyncStatus {
+    /// Status after a sync resolved with `result`
+    pub fn from_result(result: &Result<(), SyncError>) -> Self {
+        match result {
+            Ok(()) => Self::Synced { at: Instant::now() },
+            Err(e) => Self::from_error(e),
+        }
+    }
+
+    /// Status after a sync failed with `error`
+    pub fn from_error(error: &SyncError) -> Self {
+        match error {
+            SyncError::NotConnected | SyncError::Cancelled | SyncError::Superseded => Self::Pending,
+            e => Self::Failed {
+                error: e.to_string(),
+            },
+        }
+    }
+}
+
+impl Serialize for SyncStatus {
+    /// `{"status": "synced", "synced_ms_ago": 1200}`, as an `Instant` has no
+    /// meaning outside the app
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        #[serde(tag = "status", rename_all = "snake_case")]
+        enum View<'a> {
+            Pending,
+            Synced { synced_ms_ago: u64 },
+            Failed { error: &'a str },
+        }
+
+        match self {
+            Self::Pending => View::Pending,
+            Self::Synced { at } => View::Synced {
+                synced_ms_ago: u64::try_from(at.elapsed().as_millis()).unwrap_or(u64::MAX),
+            },
+            Self::Failed { error } => View::Failed { error },
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Every setting synced to the server, sent in one request on connect
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigSnapshot {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_sections: Option<CleanupPromptSections>,
+    /// Per-profile overrides of `prompt_sections`, by profile ID
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub profile_prompt_overrides: HashMap<String, PromptSectionOverrides>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stt_timeout_seconds: Option<f64>,
+}
+
+impl ConfigSnapshot {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            prompt_sections: settings.cleanup_prompt_sections.clone(),
+            profile_prompt_overrides: settings.profile_prompt_overrides.clone(),
+            stt_timeout_seconds: settings.stt_timeout_seconds,
+        }
+    }
+
+    /// Check if there is nothing to sync
+    pub fn is_empty(&self) -> bool {
+        self.prompt_sections.is_none()
+            && self.profile_prompt_overrides.is_empty()
+            && self.stt_timeout_seconds.is_none()
+    }
+
+    /// The settings in this snapshot with their values, in sync order
+    fn values(&self) -> Vec<(ConfigSetting, Value)> {
+        let mut values = Vec::new();
+        if let Some(ref sections) = self.prompt_sections {
+            values.push((
+                ConfigSetting::PromptSections,
+                serde_json::to_value(sections).unwrap_or(Value::Null),
+            ));
+        }
+        if !self.profile_prompt_overrides.is_empty() {
+            values.push((
+                ConfigSetting::ProfilePrompts,
+                serde_json::to_value(&self.profile_prompt_overrides).unwrap_or(Value::Null),
+            ));
+        }
+        if let Some(timeout) = self.stt_timeout_seconds {
+            values.push((ConfigSetting::SttTimeout, Value::from(timeout)));
+        }
+        values
+    }
+
+    /// Settings in this snapshot, in sync order
+    pub fn settings(&self) -> Vec<ConfigSetting> {
+        self.values()
+            .into_iter()
+            .map(|(setting, _)| setting)
+            .collect()
+    }
+
+    /// Build the config responses to emit for a bulk sync result.
+    /// Settings the server didn't report on are treated as rejected.
+    pub fn responses_for(&self, results: &[BulkSettingResult]) -> Vec<ConfigResponse<Value>> {
+        self.values()
+            .into_iter()
+            .map(|(setting, value)| match bulk_result(results, setting) {
+                Ok(()) => ConfigResponse::updated(setting, value),
+                Err(error) => ConfigResponse::Error {
+                    setting: setting.as_str().to_string(),
+                    error,
+                },
+            })
+            .collect()
+    }
+
+    /// Build an error response for every setting in this snapshot
+    pub fn error_responses(&self, error: &str) -> Vec<ConfigResponse<Value>> {
+        self.values()
+            .into_iter()
+            .map(|(setting, _)| ConfigResponse::Error {
+                setting: setting.as_str().to_string(),
+                error: error.to_string(),
+            })
+            .collect()
+    }
+}
+
+/// Outcome of `setting` in a bulk sync, or why it wasn't applied
+fn bulk_result(results: &[BulkSettingResult], setting: ConfigSetting) -> Result<(), String> {
+    let result = results
+        .iter()
+        .find(|r| ConfigSetting::from_name(&r.setting) == Some(setting));
+    match result {
+        Some(r) if r.accepted => Ok(()),
+        Some(r) => Err(r
+            .error
+            .clone()
+            .unwrap_or_else(|| "Rejected by server".to_string())),
+        None => Err("Not reported by server".to_string()),
+    }
+}
+
+/// Per-setting outcome reported by the bulk config endpoint
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BulkSettingResult {
+    pub setting: String,
+    pub accepted: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkSyncResponse {
+    results: Vec<BulkSettingResult>,
+}
+
+/// Result of a bulk config sync
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkSyncOutcome {
+    /// The server applied the snapshot and reported on each setting
+    Applied(Vec<BulkSettingResult>),
+    /// The server doesn't have the bulk endpoint (older server)
+    Unsupported,
+    /// Nothing was sent because the app is in local-only or dry-run mode
+    Skipped,
+}
+
+impl BulkSyncOutcome {
+    /// Interpret the bulk endpoint's HTTP status and body
+    pub fn from_response(status: u16, body: &str) -> Result<Self, SyncError> {
+        if status == StatusCode::NOT_FOUND.as_u16() {
+            return Ok(Self::Unsupported);
+        }
+        if !(200..300).contains(&status) {
+            return Err(SyncError::from_status(status, body));
+        }
+        let response: BulkSyncResponse = serde_json::from_str(body)
+            .map_err(|e| SyncError::Serialization(format!("bulk config response: {e}")))?;
+        Ok(Self::Applied(response.results))
+    }
+}
+
+/// Puts syncs of one setting in order, so an older value can't land last
+#[derive(Default)]
+struct SettingSyncGuard {
+    /// Held for the whole of a sync, retries included
+    turn: tokio::sync::Mutex<()>,
+    /// Version of the newest change handed to `put_config`
+    latest: AtomicU64,
+}
+
 /// Tracks server connection state for config syncing
 pub struct ConfigSyncState {
-    client: Client,
+    transport: Arc<dyn ConfigTransport>,
+    mode: OperatingMode,
+    /// Build and log requests instead of sending them
+    dry_run: bool,
     server_url: Option<String>,
     client_uuid: Option<String>,
+    /// Normalized path the server's API is mounted under (see `set_api_base_path`)
+    api_base_path: String,
+    retry_policy: RetryPolicy,
+    metrics: Mutex<SyncMetrics>,
+    /// Status of each setting synced since the app started
+    statuses: Mutex<HashMap<ConfigSetting, SyncStatus>>,
+    /// One guard per setting: syncs of the same setting run one at a time,
+    /// syncs of different settings run concurrently
+    guards: Mutex<HashMap<ConfigSetting, Arc<SettingSyncGuard>>>,
+    /// Cancelled (and replaced) on disconnect, aborting every sync in flight
+    cancellation: Mutex<CancellationToken>,
 }
 
 impl Default for ConfigSyncState {
@@ -24,78 +673,394 @@ impl Default for ConfigSyncState {
 
 impl ConfigSyncState {
     pub fn new() -> Self {
+        Self::with_transport(Arc::new(ReqwestTransport::new()))
+    }
+
+    /// Sync through `transport` instead of over HTTP
+    pub fn with_transport(transport: Arc<dyn ConfigTransport>) -> Self {
         Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(10))
-                .build()
-                .expect("Failed to create HTTP client"),
+            transport,
+            mode: OperatingMode::default(),
+            dry_run: false,
             server_url: None,
             client_uuid: None,
+            api_base_path: DEFAULT_API_BASE_PATH.to_string(),
+            retry_policy: RetryPolicy::default(),
+            metrics: Mutex::new(SyncMetrics::default()),
+            statuses: Mutex::new(HashMap::new()),
+            guards: Mutex::new(HashMap::new()),
+            cancellation: Mutex::new(CancellationToken::new()),
+        }
+    }
+
+    pub fn mode(&self) -> OperatingMode {
+        self.mode
+    }
+
+    /// Switch operating mode. Going local-only drops any server connection.
+    pub fn set_mode(&mut self, mode: OperatingMode) {
+        if mode == OperatingMode::LocalOnly && self.server_url.is_some() {
+            self.set_disconnected();
         }
+        self.mode = mode;
     }
 
-    /// Set connection info when connected to server
+    fn is_local_only(&self) -> bool {
+        self.mode == OperatingMode::LocalOnly
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// In dry-run mode every sync builds its request, logs it (with credentials
+    /// redacted) and records it in the metrics, but never sends it
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        log::info!("Config sync dry run: {dry_run}");
+        self.dry_run = dry_run;
+    }
+
+    fn record_dry_run(&self, request: DryRunRequest) {
+        log::info!("Config sync dry run, not sending: {}", request.to_curl());
+        self.metrics.lock().unwrap().record_dry_run(request);
+    }
+
+    pub fn api_base_path(&self) -> &str {
+        &self.api_base_path
+    }
+
+    /// Set the path the server's API is mounted under, e.g. `/stt/api` behind a
+    /// reverse proxy. Slashes are normalized; empty means the server root.
+    pub fn set_api_base_path(&mut self, path: &str) {
+        self.api_base_path = normalize_api_base_path(path);
+        log::info!("Config sync API base path: '{}'", self.api_base_path);
+    }
+
+    /// Set connection info when connected to server. Ignored in local-only mode.
     pub fn set_connected(&mut self, server_url: String, client_uuid: String) {
+        if self.is_local_only() {
+            log::debug!("Ignoring server connection in local-only mode");
+            return;
+        }
         log::info!("Config sync connected: {server_url} (uuid: {client_uuid})");
         self.server_url = Some(server_url);
         self.client_uuid = Some(client_uuid);
     }
 
-    /// Clear connection info when disconnected
+    /// Clear connection info when disconnected, cancelling syncs in flight
     pub fn set_disconnected(&mut self) {
+        self.cancel_in_flight();
         self.server_url = None;
         self.client_uuid = None;
         log::info!("Config sync disconnected");
     }
 
-    /// Check if connected to a server
+    /// Check if connected to a server (never in local-only mode)
     pub fn is_connected(&self) -> bool {
-        self.server_url.is_some() && self.client_uuid.is_some()
+        !self.is_local_only() && self.server_url.is_some() && self.client_uuid.is_some()
     }
 
-    /// Sync prompt sections to server (best-effort, logs errors)
-    pub async fn sync_prompt_sections(
+    /// URL of `endpoint` on the connected server and the client UUID, if connected
+    fn connection(&self, endpoint: &str) -> Result<(String, &str), SyncError> {
+        match (&self.server_url, &self.client_uuid) {
+            (Some(url), Some(uuid)) => Ok((endpoint_url(url, &self.api_base_path, endpoint), uuid)),
+            _ => Err(SyncError::NotConnected),
+        }
+    }
+
+    /// Cancel every sync in flight; they resolve to `SyncError::Cancelled`.
+    /// Takes `&self` so it can run while those syncs hold the read lock.
+    pub fn cancel_in_flight(&self) {
+        let mut token = self.cancellation.lock().unwrap();
+        token.cancel();
+        *token = CancellationToken::new();
+    }
+
+    /// Token for one sync, cancelled by the next `cancel_in_flight`
+    fn sync_token(&self) -> CancellationToken {
+        self.cancellation.lock().unwrap().child_token()
+    }
+
+    /// Sync counters, including context for the last failure
+    pub fn metrics(&self) -> SyncMetrics {
+        self.metrics.lock().unwrap().clone()
+    }
+
+    /// Where each setting synced since the app started stands with the server.
+    /// Settings that were never synced (e.g. in local-only mode) are absent.
+    pub fn sync_status(&self) -> HashMap<ConfigSetting, SyncStatus> {
+        self.statuses.lock().unwrap().clone()
+    }
+
+    fn set_status(&self, setting: ConfigSetting, status: SyncStatus) {
+        self.statuses.lock().unwrap().insert(setting, status);
+    }
+
+    fn guard(&self, setting: ConfigSetting) -> Arc<SettingSyncGuard> {
+        self.guards
+            .lock()
+            .unwrap()
+            .entry(setting)
+            .or_default()
+            .clone()
+    }
+
+    /// Wait, for at most `deadline`, for the syncs already started to finish,
+    /// including those queued behind a sync of the same setting, so quitting
+    /// doesn't drop the user's last change. Changes are synced as they are made,
+    /// so these are the only ones not yet sent. Returns the settings still
+    /// pending afterwards, which are logged.
+    pub async fn shutdown_flush(&self, deadline: Duration) -> Vec<ConfigSetting> {
+        let guards: Vec<_> = self.guards.lock().unwrap().values().cloned().collect();
+        // Each lock is only passed through, never held while waiting for the
+        // next one, so this can't deadlock with a bulk sync holding several
+        let drained = tokio::time::timeout(deadline, async {
+            for guard in guards {
+                drop(guard.turn.lock().await);
+            }
+        })
+        .await;
+        if drained.is_err() {
+            log::warn!("Config sync flush gave up after {deadline:?}");
+        }
+
+        let mut unflushed: Vec<ConfigSetting> = self
+            .statuses
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, status)| **status == SyncStatus::Pending)
+            .map(|(&setting, _)| setting)
+            .collect();
+        unflushed.sort_by_key(|setting| setting.as_str());
+        if !unflushed.is_empty() {
+            let names: Vec<&str> = unflushed.iter().map(|setting| setting.as_str()).collect();
+            log::warn!(
+                "Config changes not synced before quitting: {}",
+                names.join(", ")
+            );
+        }
+        unflushed
+    }
+
+    /// PUT a config value, retrying transient failures under one idempotency key.
+    /// Waits for any sync of the same setting to finish first, and returns
+    /// `Superseded` without sending if a newer value came in meanwhile.
+    /// A no-op in local-only mode.
+    async fn put_config<T: Serialize + ?Sized>(
         &self,
-        sections: &CleanupPromptSections,
-    ) -> Result<(), String> {
-        let (Some(url), Some(uuid)) = (&self.server_url, &self.client_uuid) else {
-            return Ok(()); // Not connected, skip silently
+        setting: ConfigSetting,
+        endpoint: &str,
+        body: &T,
+    ) -> Result<(), SyncError> {
+        if self.is_local_only() {
+            return Ok(());
+        }
+        let guard = self.guard(setting);
+        let version = guard.latest.fetch_add(1, AtomicOrdering::SeqCst) + 1;
+        // Pending from the moment the change is handed over, so a change queued
+        // behind another sync counts as unsent (e.g. by `shutdown_flush`)
+        if !self.dry_run {
+            self.set_status(setting, SyncStatus::Pending);
+        }
+        // Taken before waiting, so a disconnect meanwhile cancels this sync too.
+        // The turn can be granted in the same poll that sees the cancel, which
+        // `run_until_cancelled` resolves in favour of the lock, so check again.
+        let token = self.sync_token();
+        let turn = token.run_until_cancelled(guard.turn.lock()).await;
+        let Some(_turn) = turn.filter(|_| !token.is_cancelled()) else {
+            return Err(SyncError::Cancelled);
+        };
+        // A newer change queued meanwhile is still pending, whatever becomes of this one
+        let is_latest = || guard.latest.load(AtomicOrdering::SeqCst) == version;
+        if !is_latest() {
+            log::debug!("Dropping stale {} sync", setting.as_str());
+            return Err(SyncError::Superseded);
+        }
+
+        let set_final_status = |status: SyncStatus| {
+            if is_latest() {
+                self.set_status(setting, status);
+            }
         };
+        let failed = |e: &SyncError| set_final_status(SyncStatus::from_error(e));
+        let (url, uuid) = self.connection(endpoint).inspect_err(failed)?;
+        let body = to_json(body).inspect_err(failed)?;
+        let key = IdempotencyKey::new();
+        let headers_for = |key: &IdempotencyKey| {
+            vec![
+                (CLIENT_UUID_HEADER, uuid.to_string()),
+                (IDEMPOTENCY_KEY_HEADER, key.as_str().to_string()),
+            ]
+        };
+        if self.dry_run {
+            self.record_dry_run(DryRunRequest::new("PUT", url, &headers_for(&key), &body));
+            return Ok(());
+        }
+
+        let (result, attempts) = retry_sync(self.retry_policy, &key, |key| {
+            let headers = headers_for(&key);
+            let response = self.transport.put_json(url.clone(), headers, body.clone());
+            let token = token.clone();
+            async move {
+                token
+                    .run_until_cancelled(response)
+                    .await
+                    .ok_or(SyncError::Cancelled)??
+                    .error_for_status()
+                    .map(|_| ())
+            }
+        })
+        .await;
+
+        self.metrics.lock().unwrap().record(&key, attempts, &result);
+        let result = result.map_err(|e| match e {
+            SyncError::Http { status, .. }
+                if status == StatusCode::NOT_FOUND.as_u16()
+                    && SETTINGS_NEWER_THAN_SERVER.contains(&setting) =>
+            {
+                log::info!("Server doesn't support {}", setting.as_str());
+                SyncError::Unsupported
+            }
+            e => e,
+        });
+        set_final_status(SyncStatus::from_result(&result));
+        result
+    }
+
+    /// Sync every setting in one request. Returns `Unsupported` if the server
+    /// predates the bulk endpoint, so the caller can fall back to individual syncs.
+    pub async fn sync_all(&self, snapshot: &ConfigSnapshot) -> Result<BulkSyncOutcome, SyncError> {
+        if self.is_local_only() {
+            return Ok(BulkSyncOutcome::Skipped);
+        }
+        let (url, uuid) = self.connection("/config/bulk")?;
+        let headers = vec![(CLIENT_UUID_HEADER, uuid.to_string())];
+        let body = to_json(snapshot)?;
+        if self.dry_run {
+            self.record_dry_run(DryRunRequest::new("POST", url, &headers, &body));
+            return Ok(BulkSyncOutcome::Skipped);
+        }
 
-        self.client
-            .put(format!("{url}/api/config/prompts"))
-            .header("X-Client-UUID", uuid)
-            .json(sections)
-            .send()
+        let settings = snapshot.settings();
+        // Wait out individual syncs of these settings, and hold them off until
+        // the snapshot is applied. Always locked in the same order.
+        let guards: Vec<_> = settings
+            .iter()
+            .map(|&setting| self.guard(setting))
+            .collect();
+        for &setting in &settings {
+            self.set_status(setting, SyncStatus::Pending);
+        }
+        let token = self.sync_token();
+        let turns = token
+            .run_until_cancelled(async {
+                let mut turns = Vec::with_capacity(guards.len());
+                for guard in &guards {
+                    turns.push(guard.turn.lock().await);
+                }
+                turns
+            })
             .await
-            .map_err(|e| e.to_string())?
-            .error_for_status()
-            .map_err(|e| e.to_string())?;
+            .filter(|_| !token.is_cancelled())
+            .ok_or(SyncError::Cancelled)?;
+        // Individual changes queued behind this request are newer than the
+        // snapshot, so their settings stay pending whatever the outcome
+        let versions: Vec<u64> = guards
+            .iter()
+            .map(|guard| guard.latest.load(AtomicOrdering::SeqCst))
+            .collect();
+        let set_final_status = |index: usize, status: SyncStatus| {
+            if guards[index].latest.load(AtomicOrdering::SeqCst) == versions[index] {
+                self.set_status(settings[index], status);
+            }
+        };
+        let request = self.transport.post_json(url, headers, body);
+        let outcome = async {
+            let response = token
+                .run_until_cancelled(request)
+                .await
+                .ok_or(SyncError::Cancelled)??;
+            BulkSyncOutcome::from_response(response.status, &response.body)
+        }
+        .await;
+
+        match outcome {
+            Ok(BulkSyncOutcome::Applied(ref results)) => {
+                for (index, &setting) in settings.iter().enumerate() {
+                    let status = match bulk_result(results, setting) {
+                        Ok(()) => SyncStatus::Synced { at: Instant::now() },
+                        Err(error) => SyncStatus::Failed { error },
+                    };
+                    set_final_status(index, status);
+                }
+            }
+            // Left pending for the individual syncs that follow
+            Ok(_) => {}
+            Err(ref e) => {
+                for index in 0..settings.len() {
+                    set_final_status(index, SyncStatus::from_error(e));
+                }
+            }
+        }
+        drop(turns);
+        log::debug!("Bulk config sync: {outcome:?}");
+        outcome
+    }
+
+    /// Sync prompt sections to server
+    pub async fn sync_prompt_sections(
+        &self,
+        sections: &CleanupPromptSections,
+    ) -> Result<(), SyncError> {
+        self.put_config(ConfigSetting::PromptSections, "/config/prompts", sections)
+            .await?;
 
         log::debug!("Synced prompt sections to server");
         Ok(())
     }
 
+    /// Sync per-profile prompt overrides to server. They apply on top of the
+    /// sections synced by `sync_prompt_sections`. Returns `Unsupported` if the
+    /// server predates the endpoint; the overrides then only apply locally.
+    pub async fn sync_profile_prompt_overrides(
+        &self,
+        overrides: &HashMap<String, PromptSectionOverrides>,
+    ) -> Result<(), SyncError> {
+        #[derive(Serialize)]
+        struct ProfilePromptsBody<'a> {
+            profiles: &'a HashMap<String, PromptSectionOverrides>,
+        }
+
+        self.put_config(
+            ConfigSetting::ProfilePrompts,
+            "/config/prompts/profiles",
+            &ProfilePromptsBody {
+                profiles: overrides,
+            },
+        )
+        .await?;
+
+        log::debug!(
+            "Synced prompt overrides for {} profiles to server",
+            overrides.len()
+        );
+        Ok(())
+    }
+
     /// Sync STT timeout to server
-    pub async fn sync_stt_timeout(&self, timeout_seconds: f64) -> Result<(), String> {
+    pub async fn sync_stt_timeout(&self, timeout_seconds: f64) -> Result<(), SyncError> {
         #[derive(Serialize)]
         struct TimeoutBody {
             timeout_seconds: f64,
         }
 
-        let (Some(url), Some(uuid)) = (&self.server_url, &self.client_uuid) else {
-            return Ok(()); // Not connected, skip silently
-        };
-
-        self.client
-            .put(format!("{url}/api/config/stt-timeout"))
-            .header("X-Client-UUID", uuid)
-            .json(&TimeoutBody { timeout_seconds })
-            .send()
-            .await
-            .map_err(|e| e.to_string())?
-            .error_for_status()
-            .map_err(|e| e.to_string())?;
+        self.put_config(
+            ConfigSetting::SttTimeout,
+            "/config/stt-timeout",
+            &TimeoutBody { timeout_seconds },
+        )
+        .await?;
 
         log::debug!("Synced STT timeout ({timeout_seconds}) to server");
         Ok(())
@@ -0,0 +1,77 @@
+//! The latest server connection state.
+//!
+//! `CONNECTION_STATE` is a broadcast, so a window created after the last one
+//! (e.g. settings opened later) would otherwise show "disconnected" until the
+//! state next changes. Every emission goes through `ConnectionStateCache`, which
+//! new windows can query or ask to re-emit. The overlay's connection machine owns
+//! the state; Rust only adds the server URL once settings sync is set up.
+
+use std::sync::Mutex;
+
+use crate::events::{ConnectionStatePayload, DisconnectReason};
+
+/// Connection states in which the overlay is talking to a server
+const CONNECTED_STATES: [&str; 3] = ["idle", "recording", "processing"];
+
+pub struct ConnectionStateCache {
+    latest: Mutex<ConnectionStatePayload>,
+}
+
+impl Default for ConnectionStateCache {
+    fn default() -> Self {
+        Self {
+            latest: Mutex::new(ConnectionStatePayload::disconnected(None)),
+        }
+    }
+}
+
+impl ConnectionStateCache {
+    /// Move to `state` as reported by the overlay's connection machine, keeping
+    /// the server URL while still connected, and emit it. The lock is held while
+    /// emitting so the stored state always matches the last payload sent.
+    pub fn transition(
+        &self,
+        state: &str,
+        reason: Option<DisconnectReason>,
+        emit: impl FnOnce(&ConnectionStatePayload),
+    ) {
+        let mut latest = self.latest.lock().unwrap();
+        let server_url = if CONNECTED_STATES.contains(&state) {
+            latest.server_url.take()
+        } else {
+            None
+        };
+        let payload = ConnectionStatePayload {
+            state: state.to_string(),
+            server_url,
+            reason: reason.map(|r| r.as_str().to_string()),
+        };
+        emit(&payload);
+        *latest = payload;
+    }
+
+    /// Record the server the overlay connected to and emit it with the current
+    /// state. Ignored if the connection machine has already moved to a
+    /// disconnected state, so a late report can't contradict it.
+    pub fn set_server_url(&self, server_url: String, emit: impl FnOnce(&ConnectionStatePayload)) {
+        let mut latest = self.latest.lock().unwrap();
+        if !CONNECTED_STATES.contains(&latest.state.as_str()) {
+            return;
+        }
+        let payload = ConnectionStatePayload {
+            state: latest.state.clone(),
+            ..ConnectionStatePayload::connected(server_url)
+        };
+        emit(&payload);
+        *latest = payload;
+    }
+
+    pub fn current(&self) -> ConnectionStatePayload {
+        self.latest.lock().unwrap().clone()
+    }
+
+    /// Emit the current state again, for a window that just subscribed
+    pub fn reemit(&self, emit: impl FnOnce(&ConnectionStatePayload)) {
+        emit(&self.latest.lock().unwrap());
+    }
+}
@@ -0,0 +1,76 @@
+//! Apps the recording overlay (and optionally recording) is disabled in.
+//!
+//! Users list patterns such as `^steam$` or `com\.agilebits\.onepassword`.
+//! Each is matched like a `MatcherKind::App` profile rule, against the app
+//! name, bundle ID and process name of the active window.
+
+use serde::Serialize;
+
+use crate::active_window::ActiveWindowInfo;
+use crate::profile_matcher::{CompiledMatcher, MatcherKind, ProfileRuleError};
+
+/// What to do when a recording is requested
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingGate {
+    /// Record as usual
+    Record,
+    /// Record, but keep the overlay hidden
+    HideOverlay,
+    /// Don't record at all
+    Suppress,
+}
+
+/// What was suppressed, reported in the `recording-suppressed` event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SuppressionScope {
+    /// Recording went ahead without the overlay
+    Overlay,
+    /// Nothing was recorded
+    Recording,
+}
+
+/// Compiled `disabled_apps` patterns
+#[derive(Debug, Clone, Default)]
+pub struct DisabledApps {
+    matchers: Vec<CompiledMatcher>,
+}
+
+impl DisabledApps {
+    /// Compile every pattern, failing on the first invalid one
+    pub fn compile(patterns: &[String]) -> Result<Self, ProfileRuleError> {
+        let matchers = patterns
+            .iter()
+            .map(|pattern| {
+                CompiledMatcher::compile(MatcherKind::App {
+                    pattern: pattern.clone(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { matchers })
+    }
+
+    /// Check if the window belongs to a disabled app
+    pub fn contains(&self, info: &ActiveWindowInfo) -> bool {
+        self.matchers.iter().any(|m| m.matches(info))
+    }
+
+    /// Decide how to handle a recording started in `window`.
+    /// With no window info the recording always goes ahead.
+    pub fn gate(
+        &self,
+        window: Option<&ActiveWindowInfo>,
+        suppress_recording: bool,
+    ) -> RecordingGate {
+        match window {
+            Some(info) if self.contains(info) => {
+                if suppress_recording {
+                    RecordingGate::Suppress
+                } else {
+                    RecordingGate::HideOverlay
+                }
+            }
+            _ => RecordingGate::Record,
+        }
+    }
+}
@@ -6,9 +6,12 @@
 //! IMPORTANT: Event names and payload types must match the TypeScript side.
 //! See: src/lib/events.ts
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::active_window::ActiveWindowInfo;
+use crate::disabled_apps::SuppressionScope;
+use crate::history::HistoryEntry;
+use crate::settings::CleanupPromptSections;
 
 // =============================================================================
 // Event Names - Must match src/lib/events.ts
@@ -32,18 +35,30 @@ pub enum EventName {
     SettingsChanged,
     /// Main → Overlay: Request reconnection
     ReconnectRequest,
-    /// Overlay → Main: Connection state updates
+    /// Rust → All: Connection state updates
     ConnectionState,
+    /// Any window → Rust: Re-emit the current connection state
+    ConnectionStateRequest,
     /// Overlay → Main: Reconnection started
     ReconnectStarted,
     /// Overlay → Main: Reconnection result
     ReconnectResult,
-    /// Rust → All: History changed
+    /// Rust → All: History changed (see `HistoryChangedPayload`)
     HistoryChanged,
     /// Rust → Overlay: Native audio data from mic capture
     NativeAudioData,
     /// Rust → Overlay: Translation mode triggered
     TranslationTrigger,
+    /// Rust → All: Non-fatal notification to show the user
+    Notification,
+    /// Rust → All: Foreground window changed while the window watcher is running
+    ActiveWindowChanged,
+    /// Rust → All: A recording was started in a disabled app
+    RecordingSuppressed,
+    /// Rust → All: The overlay was hidden after a transcription
+    OverlayHidden,
+    /// Rust → All: A recording was shorter than `min_recording_ms` and was dropped
+    RecordingDiscarded,
 }
 
 impl EventName {
@@ -59,11 +74,17 @@ impl EventName {
             Self::SettingsChanged => "settings-changed",
             Self::ReconnectRequest => "request-reconnect",
             Self::ConnectionState => "connection-state-changed",
+            Self::ConnectionStateRequest => "connection-state-request",
             Self::ReconnectStarted => "reconnect-started",
             Self::ReconnectResult => "reconnect-result",
             Self::HistoryChanged => "history-changed",
             Self::NativeAudioData => "native-audio-data",
             Self::TranslationTrigger => "translation-trigger",
+            Self::Notification => "notification",
+            Self::ActiveWindowChanged => "active-window-changed",
+            Self::RecordingSuppressed => "recording-suppressed",
+            Self::OverlayHidden => "overlay-hidden",
+            Self::RecordingDiscarded => "recording-discarded",
         }
     }
 }
@@ -73,9 +94,10 @@ impl EventName {
 // =============================================================================
 
 /// Type-safe config setting names for config sync responses.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ConfigSetting {
     PromptSections,
+    ProfilePrompts,
     SttTimeout,
     SttProvider,
     LlmProvider,
@@ -87,11 +109,32 @@ impl ConfigSetting {
     pub const fn as_str(self) -> &'static str {
         match self {
             Self::PromptSections => "prompt-sections",
+            Self::ProfilePrompts => "profile-prompts",
             Self::SttTimeout => "stt-timeout",
             Self::SttProvider => "stt-provider",
             Self::LlmProvider => "llm-provider",
         }
     }
+
+    /// Parse a setting name as reported by the server.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            Self::PromptSections,
+            Self::ProfilePrompts,
+            Self::SttTimeout,
+            Self::SttProvider,
+            Self::LlmProvider,
+        ]
+        .into_iter()
+        .find(|setting| setting.as_str() == name)
+    }
+}
+
+impl Serialize for ConfigSetting {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
 }
 
 // =============================================================================
@@ -123,9 +166,98 @@ impl<T: Serialize> ConfigResponse<T> {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// Why the connection to the server was lost
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "String")]
+pub enum DisconnectReason {
+    Timeout,
+    AuthFailed,
+    UserRequested,
+    ServerClosed,
+    Error,
+    /// A reason this version doesn't know, kept as sent
+    Unknown(String),
+}
+
+impl DisconnectReason {
+    /// Returns the string representation for connection state payloads.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Timeout => "timeout",
+            Self::AuthFailed => "auth-failed",
+            Self::UserRequested => "user-requested",
+            Self::ServerClosed => "server-closed",
+            Self::Error => "error",
+            Self::Unknown(reason) => reason,
+        }
+    }
+}
+
+impl From<String> for DisconnectReason {
+    fn from(reason: String) -> Self {
+        match reason.as_str() {
+            "timeout" => Self::Timeout,
+            "auth-failed" => Self::AuthFailed,
+            "user-requested" => Self::UserRequested,
+            "server-closed" => Self::ServerClosed,
+            "error" => Self::Error,
+            _ => {
+                log::warn!("Unknown disconnect reason: {reason}");
+                Self::Unknown(reason)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ConnectionStatePayload {
     pub state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl ConnectionStatePayload {
+    /// Connected and idle, talking to `server_url`
+    pub fn connected(server_url: impl Into<String>) -> Self {
+        Self {
+            state: "idle".to_string(),
+            server_url: Some(server_url.into()),
+            reason: None,
+        }
+    }
+
+    /// Disconnected, optionally with the reason the connection was lost
+    pub fn disconnected(reason: Option<DisconnectReason>) -> Self {
+        Self {
+            state: "disconnected".to_string(),
+            server_url: None,
+            reason: reason.map(|r| r.as_str().to_string()),
+        }
+    }
+}
+
+/// What changed in the dictation history, so windows can update incrementally
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HistoryChangedPayload {
+    /// A new entry was added at the top
+    Added {
+        entry: HistoryEntry,
+    },
+    /// An existing entry changed and moved to the top (e.g. a deduplicated repeat)
+    Updated {
+        entry: HistoryEntry,
+    },
+    /// Entries were deleted, one or many at once (e.g. pruning old history)
+    Deleted {
+        ids: Vec<String>,
+    },
+    Cleared,
+    /// Many entries changed at once (e.g. an import); refetch with `get_history`
+    Reloaded,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -137,6 +269,41 @@ pub struct ReconnectResultPayload {
 
 #[derive(Debug, Clone, Serialize)]
 pub struct RecordingStartPayload {
+    /// Correlates this recording's logs, events and server requests
+    pub recording_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub window_info: Option<ActiveWindowInfo>,
+    /// Profile picked by the user's profile rules for `window_info`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile_id: Option<String>,
+    /// Effective prompt sections, when the matched profile overrides the base ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_sections: Option<CleanupPromptSections>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingStopPayload {
+    /// Same ID as the `recording-start` event of this recording
+    pub recording_id: String,
+}
+
+/// Sent instead of `recording-stop` when a recording is too short to keep
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingDiscardedPayload {
+    /// Same ID as the `recording-start` event of this recording
+    pub recording_id: String,
+    pub duration_ms: u64,
+    pub min_recording_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingSuppressedPayload {
+    /// The disabled app that was active
+    pub app_name: String,
+    pub scope: SuppressionScope,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationPayload {
+    pub message: String,
 }
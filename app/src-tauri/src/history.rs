@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fs;
@@ -6,6 +6,8 @@ use std::path::PathBuf;
 use std::sync::RwLock;
 use uuid::Uuid;
 
+use crate::events::HistoryChangedPayload;
+
 const MAX_HISTORY_ENTRIES: usize = 500;
 
 /// Strategy for importing history entries
@@ -36,6 +38,9 @@ pub struct HistoryEntry {
     pub text: String,
     #[serde(default)]
     pub raw_text: String,
+    /// How long the recording lasted, when it was started from a hotkey
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
 }
 
 impl HistoryEntry {
@@ -45,6 +50,7 @@ impl HistoryEntry {
             timestamp: Utc::now(),
             text,
             raw_text,
+            duration_ms: None,
         }
     }
 }
@@ -55,10 +61,14 @@ struct HistoryData {
     entries: Vec<HistoryEntry>,
 }
 
+/// Called after each saved change to the history
+type ChangeListener = Box<dyn Fn(&HistoryChangedPayload) + Send + Sync>;
+
 /// Manages loading and saving of dictation history
 pub struct HistoryStorage {
     data: RwLock<HistoryData>,
     file_path: PathBuf,
+    on_change: Option<ChangeListener>,
 }
 
 impl HistoryStorage {
@@ -75,6 +85,23 @@ impl HistoryStorage {
         Self {
             data: RwLock::new(data),
             file_path,
+            on_change: None,
+        }
+    }
+
+    /// Report every change to `on_change` once it is saved (used to emit `HISTORY_CHANGED`)
+    #[must_use]
+    pub fn with_change_listener(
+        mut self,
+        on_change: impl Fn(&HistoryChangedPayload) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_change = Some(Box::new(on_change));
+        self
+    }
+
+    fn notify(&self, change: &HistoryChangedPayload) {
+        if let Some(ref on_change) = self.on_change {
+            on_change(change);
         }
     }
 
@@ -100,22 +127,53 @@ impl HistoryStorage {
         Ok(())
     }
 
-    /// Add a new entry to the history
-    pub fn add_entry(&self, text: String, raw_text: String) -> Result<HistoryEntry, String> {
-        let entry = HistoryEntry::new(text, raw_text);
-        {
+    /// Add a new entry to the history.
+    /// With a `dedup_window`, text matching the most recent entry within that
+    /// window refreshes the entry's timestamp instead of adding a duplicate.
+    pub fn add_entry(
+        &self,
+        text: String,
+        raw_text: String,
+        duration_ms: Option<u64>,
+        dedup_window: Option<Duration>,
+    ) -> Result<HistoryEntry, String> {
+        let entry = HistoryEntry {
+            duration_ms,
+            ..HistoryEntry::new(text, raw_text)
+        };
+        let (entry, refreshed) = {
             let mut data = self
                 .data
                 .write()
                 .map_err(|e| format!("Failed to write history: {e}"))?;
 
-            data.entries.insert(0, entry.clone());
-
-            if data.entries.len() > MAX_HISTORY_ENTRIES {
-                data.entries.truncate(MAX_HISTORY_ENTRIES);
+            if let Some(index) =
+                dedup_window.and_then(|window| find_duplicate(&data.entries, &entry, window))
+            {
+                let mut existing = data.entries.remove(index);
+                existing.timestamp = entry.timestamp;
+                data.entries.insert(0, existing.clone());
+                (existing, true)
+            } else {
+                data.entries.insert(0, entry.clone());
+                if data.entries.len() > MAX_HISTORY_ENTRIES {
+                    data.entries.truncate(MAX_HISTORY_ENTRIES);
+                }
+                (entry, false)
             }
-        }
+        };
         self.save()?;
+
+        let change = if refreshed {
+            HistoryChangedPayload::Updated {
+                entry: entry.clone(),
+            }
+        } else {
+            HistoryChangedPayload::Added {
+                entry: entry.clone(),
+            }
+        };
+        self.notify(&change);
         Ok(entry)
     }
 
@@ -134,6 +192,16 @@ impl HistoryStorage {
         Ok(entries)
     }
 
+    /// Get the most recent history entry, if any
+    pub fn latest(&self) -> Result<Option<HistoryEntry>, String> {
+        let data = self
+            .data
+            .read()
+            .map_err(|e| format!("Failed to read history: {e}"))?;
+
+        Ok(data.entries.iter().max_by_key(|e| e.timestamp).cloned())
+    }
+
     /// Delete an entry by ID
     pub fn delete(&self, id: &str) -> Result<bool, String> {
         let deleted = {
@@ -149,6 +217,9 @@ impl HistoryStorage {
 
         if deleted {
             self.save()?;
+            self.notify(&HistoryChangedPayload::Deleted {
+                ids: vec![id.to_string()],
+            });
         }
 
         Ok(deleted)
@@ -163,7 +234,68 @@ impl HistoryStorage {
                 .map_err(|e| format!("Failed to write history: {e}"))?;
             data.entries.clear();
         }
-        self.save()
+        self.save()?;
+        self.notify(&HistoryChangedPayload::Cleared);
+        Ok(())
+    }
+
+    /// Delete entries older than `older_than_days` days, returning how many were
+    /// removed. 0 deletes all history, and only with `delete_all` set, so a zero
+    /// that slipped through a setting or the UI can't wipe it by accident.
+    pub fn prune(&self, older_than_days: u32, delete_all: bool) -> Result<usize, String> {
+        if older_than_days == 0 {
+            if !delete_all {
+                return Err(
+                    "Pruning history older than 0 days deletes all of it; confirm with delete_all"
+                        .to_string(),
+                );
+            }
+            return self.remove_where(|_| true);
+        }
+        match Utc::now().checked_sub_signed(Duration::days(i64::from(older_than_days))) {
+            Some(cutoff) => self.prune_before(cutoff),
+            // Nothing is that old
+            None => Ok(0),
+        }
+    }
+
+    /// Delete entries from before `cutoff`; an entry at exactly `cutoff` is kept
+    pub fn prune_before(&self, cutoff: DateTime<Utc>) -> Result<usize, String> {
+        self.remove_where(|e| e.timestamp < cutoff)
+    }
+
+    /// Delete the entries matching `remove` and report them in one event.
+    /// Returns how many were removed.
+    fn remove_where(&self, remove: impl Fn(&HistoryEntry) -> bool) -> Result<usize, String> {
+        let (removed_ids, remaining) = {
+            let mut data = self
+                .data
+                .write()
+                .map_err(|e| format!("Failed to write history: {e}"))?;
+
+            let mut removed_ids = Vec::new();
+            data.entries.retain(|e| {
+                if remove(e) {
+                    removed_ids.push(e.id.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+            (removed_ids, data.entries.len())
+        };
+
+        let removed = removed_ids.len();
+        if removed > 0 {
+            self.save()?;
+            self.notify(&if remaining == 0 {
+                HistoryChangedPayload::Cleared
+            } else {
+                HistoryChangedPayload::Deleted { ids: removed_ids }
+            });
+        }
+
+        Ok(removed)
     }
 
     /// Import entries with the specified strategy
@@ -232,6 +364,7 @@ impl HistoryStorage {
         }
 
         self.save()?;
+        self.notify(&HistoryChangedPayload::Reloaded);
 
         Ok(HistoryImportResult {
             success: true,
@@ -240,3 +373,22 @@ impl HistoryStorage {
         })
     }
 }
+
+/// Collapse runs of whitespace so re-recordings that only differ in spacing compare equal
+fn normalize_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Index of the most recent entry, if `entry` repeats its text within `window`
+fn find_duplicate(
+    entries: &[HistoryEntry],
+    entry: &HistoryEntry,
+    window: Duration,
+) -> Option<usize> {
+    let (index, latest) = entries
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, e)| e.timestamp)?;
+    let within_window = entry.timestamp - latest.timestamp <= window;
+    (within_window && normalize_text(&latest.text) == normalize_text(&entry.text)).then_some(index)
+}
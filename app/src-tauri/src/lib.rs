@@ -1,29 +1,51 @@
 use tauri::{
     menu::{Menu, MenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Emitter, Manager,
+    AppHandle, Emitter, Listener, Manager,
 };
 use tauri_utils::config::BackgroundThrottlingPolicy;
 
+mod accelerator;
 mod active_window;
 mod audio;
 mod audio_mute;
+mod clipboard;
+mod clock;
 mod commands;
 mod config_sync;
+mod connection_state;
+mod disabled_apps;
 pub mod events;
 mod history;
 
-use events::{EventName, RecordingStartPayload};
+use events::{
+    EventName, RecordingDiscardedPayload, RecordingStartPayload, RecordingStopPayload,
+    RecordingSuppressedPayload,
+};
 mod mic_capture;
+mod migrations;
+mod overlay_auto_hide;
+mod profile_matcher;
+mod profile_resolver;
+mod reconnect;
+mod recording_id;
+mod recording_timer;
 mod settings;
+#[cfg(desktop)]
+mod shortcut_registrar;
 mod state;
+mod window_watcher;
 
 #[cfg(test)]
 mod tests;
 
 use audio_mute::AudioMuteManager;
+use disabled_apps::{RecordingGate, SuppressionScope};
 use history::HistoryStorage;
 use mic_capture::{AudioDeviceInfo, MicCapture, MicCaptureManager};
+#[cfg(desktop)]
+use profile_resolver::{ChainResolver, FixedResolver};
+use reconnect::ReconnectManager;
 use settings::{HotkeyConfig, HotkeyType, StoreKey};
 use state::{AppState, ShortcutState};
 
@@ -37,6 +59,12 @@ use tauri_plugin_global_shortcut::{
 
 #[cfg(desktop)]
 use commands::settings::get_setting_from_store;
+#[cfg(desktop)]
+use recording_id::with_recording_id;
+#[cfg(desktop)]
+use recording_timer::StoppedRecording;
+#[cfg(desktop)]
+use std::fmt;
 
 /// Events that can trigger state transitions in the shortcut state machine
 #[cfg(desktop)]
@@ -52,6 +80,12 @@ pub enum ShortcutEvent {
     TranslationReleased,
 }
 
+/// Title of the recording overlay window
+const OVERLAY_WINDOW_TITLE: &str = "Voice Overlay";
+
+/// How long quitting waits for config syncs in flight to reach the server
+const SHUTDOWN_FLUSH_DEADLINE: std::time::Duration = std::time::Duration::from_secs(2);
+
 // Define NSPanel type for overlay on macOS
 #[cfg(target_os = "macos")]
 tauri_nspanel::tauri_panel! {
@@ -90,55 +124,20 @@ pub(crate) fn normalize_shortcut_string(s: &str) -> String {
         .join("+")
 }
 
-/// Get the normalized shortcut string for a hotkey config, falling back to default if invalid
-#[cfg(desktop)]
-fn get_normalized_shortcut_string(
-    hotkey: &HotkeyConfig,
-    default_fn: fn() -> HotkeyConfig,
-) -> String {
-    let shortcut_str = hotkey.to_shortcut().map_or_else(
-        |_| default_fn().to_shortcut_string(),
-        |_| hotkey.to_shortcut_string(),
-    );
-    normalize_shortcut_string(&shortcut_str)
-}
-
-/// Match a shortcut string against configured hotkeys
+/// Match a shortcut string against the currently registered hotkeys
 #[cfg(desktop)]
 fn match_hotkey(app: &AppHandle, shortcut_str: &str) -> Option<HotkeyType> {
-    let toggle_hotkey: HotkeyConfig =
-        get_setting_from_store(app, StoreKey::ToggleHotkey, HotkeyConfig::default_toggle());
-    let hold_hotkey: HotkeyConfig =
-        get_setting_from_store(app, StoreKey::HoldHotkey, HotkeyConfig::default_hold());
-    let paste_last_hotkey: HotkeyConfig = get_setting_from_store(
-        app,
-        StoreKey::PasteLastHotkey,
-        HotkeyConfig::default_paste_last(),
-    );
-    let translation_hotkey: HotkeyConfig = get_setting_from_store(
-        app,
-        StoreKey::TranslationHotkey,
-        HotkeyConfig::default_translation(),
+    let state = app.try_state::<AppState>()?;
+    let registered = state.registered_shortcuts.read().ok()?;
+    let shortcut = registered
+        .iter()
+        .find(|shortcut| normalize_shortcut_string(&shortcut.normalized) == shortcut_str)?;
+    log::debug!(
+        "Matched {shortcut_str} to {} hotkey (configured as {})",
+        shortcut.hotkey_type.display_name(),
+        shortcut.raw
     );
-
-    if shortcut_str == get_normalized_shortcut_string(&toggle_hotkey, HotkeyConfig::default_toggle)
-    {
-        Some(HotkeyType::Toggle)
-    } else if shortcut_str
-        == get_normalized_shortcut_string(&hold_hotkey, HotkeyConfig::default_hold)
-    {
-        Some(HotkeyType::Hold)
-    } else if shortcut_str
-        == get_normalized_shortcut_string(&paste_last_hotkey, HotkeyConfig::default_paste_last)
-    {
-        Some(HotkeyType::PasteLast)
-    } else if shortcut_str
-        == get_normalized_shortcut_string(&translation_hotkey, HotkeyConfig::default_translation)
-    {
-        Some(HotkeyType::Translation)
-    } else {
-        None
-    }
+    Some(shortcut.hotkey_type)
 }
 
 /// Save a setting to the store
@@ -159,7 +158,8 @@ pub(crate) fn save_setting_to_store<T: serde::Serialize>(
     Ok(())
 }
 
-/// Start recording with sound and audio mute handling
+/// Start recording with sound and audio mute handling.
+/// Returns false if the active app is disabled and recording was suppressed.
 #[cfg(desktop)]
 fn start_recording(
     app: &AppHandle,
@@ -167,14 +167,80 @@ fn start_recording(
     audio_mute_manager: Option<&AudioMuteManager>,
     auto_mute_audio: bool,
     source: &str,
-) {
-    log::info!("{source}: starting recording");
-
-    let window_info = active_window::get_active_window();
+) -> bool {
+    let state = app.state::<AppState>();
+    let recording_id = state.recording_ids.lock().unwrap().start();
+    let log_line = |message: fmt::Arguments| with_recording_id(Some(&recording_id), message);
+    log::info!("{}", log_line(format_args!("{source}: starting recording")));
+
+    // The shortcut state is locked until this returns, so browser address bars
+    // are only read if already found; a search runs in the background instead
+    let window_info = active_window::get_active_window_fast();
+    if window_info
+        .as_ref()
+        .is_some_and(|info| info.url.is_none() && !info.is_private)
+    {
+        active_window::find_address_bar();
+    }
     if let Some(ref info) = window_info {
-        log::info!("Active window: {} ({})", info.app_name, info.window_title);
+        log::info!(
+            "{}",
+            log_line(format_args!(
+                "Active window: {} ({})",
+                info.app_name, info.window_title
+            ))
+        );
     }
 
+    let suppress_recording: bool =
+        get_setting_from_store(app, StoreKey::SuppressRecordingInDisabledApps, false);
+    let gate = state
+        .disabled_apps
+        .read()
+        .unwrap()
+        .gate(window_info.as_ref(), suppress_recording);
+    if gate != RecordingGate::Record {
+        let app_name = window_info
+            .as_ref()
+            .map(|info| info.app_name.clone())
+            .unwrap_or_default();
+        let scope = if gate == RecordingGate::Suppress {
+            SuppressionScope::Recording
+        } else {
+            SuppressionScope::Overlay
+        };
+        log::info!(
+            "{}",
+            log_line(format_args!(
+                "{app_name} is a disabled app, suppressing {scope:?}"
+            ))
+        );
+        let _ = app.emit(
+            EventName::RecordingSuppressed.as_str(),
+            RecordingSuppressedPayload { app_name, scope },
+        );
+
+        if gate == RecordingGate::Suppress {
+            state.recording_ids.lock().unwrap().discard();
+            return false;
+        }
+        if let Some(overlay) = app.get_webview_window("overlay") {
+            let _ = overlay.hide();
+            *state.overlay_hidden_for_recording.lock().unwrap() = true;
+        }
+    }
+
+    // Bring back the overlay if it auto-hid after the last transcription
+    // (unless it was just hidden for a disabled app)
+    let auto_hidden = state.overlay_auto_hide.lock().unwrap().recording_started();
+    if auto_hidden && gate == RecordingGate::Record {
+        if let Some(overlay) = app.get_webview_window("overlay") {
+            let _ = overlay.show();
+        }
+    }
+
+    state.recording_timer.lock().unwrap().start();
+
     if sound_enabled {
         audio::play_sound(audio::SoundType::RecordingStart);
         std::thread::sleep(std::time::Duration::from_millis(150));
@@ -182,12 +248,32 @@ fn start_recording(
     if auto_mute_audio {
         if let Some(manager) = audio_mute_manager {
             if let Err(e) = manager.mute() {
-                log::warn!("Failed to mute audio: {e}");
+                log::warn!("{}", log_line(format_args!("Failed to mute audio: {e}")));
             }
         }
     }
-    let payload = RecordingStartPayload { window_info };
+    let profile_id = window_info
+        .as_ref()
+        .and_then(|info| state.profile_selection.resolve(info));
+    if let Some(ref id) = profile_id {
+        log::info!("{}", log_line(format_args!("Matched profile: {id}")));
+    }
+    let prompt_sections = profile_id.as_deref().and_then(|id| {
+        let settings = commands::settings::get_settings(app.clone()).ok()?;
+        settings
+            .profile_prompt_overrides
+            .contains_key(id)
+            .then(|| settings::resolve_prompt_sections(Some(id), &settings))
+    });
+
+    let payload = RecordingStartPayload {
+        recording_id,
+        window_info,
+        profile_id,
+        prompt_sections,
+    };
     let _ = app.emit(EventName::RecordingStart.as_str(), payload);
+    true
 }
 
 /// Stop recording with sound and audio unmute handling
@@ -199,35 +285,87 @@ fn stop_recording(
     auto_mute_audio: bool,
     source: &str,
 ) {
-    log::info!("{source}: stopping recording");
+    let state = app.state::<AppState>();
+    let recording_id = state
+        .recording_ids
+        .lock()
+        .unwrap()
+        .current()
+        .map(str::to_string)
+        .unwrap_or_default();
+    let log_line = |message: fmt::Arguments| with_recording_id(Some(&recording_id), message);
+    log::info!("{}", log_line(format_args!("{source}: stopping recording")));
+    let min_recording_ms: u64 = get_setting_from_store(app, StoreKey::MinRecordingMs, 0);
+    let stopped = state
+        .recording_timer
+        .lock()
+        .unwrap()
+        .stop_with_min_length(std::time::Duration::from_millis(min_recording_ms));
+    let discarded_after = match stopped {
+        Some(StoppedRecording::Kept(duration)) => {
+            log::info!(
+                "{}",
+                log_line(format_args!(
+                    "{source}: recorded for {} ms",
+                    duration.as_millis()
+                ))
+            );
+            None
+        }
+        Some(StoppedRecording::TooShort(duration)) => {
+            log::info!(
+                "{}",
+                log_line(format_args!(
+                    "{source}: discarding recording of {} ms (minimum {min_recording_ms} ms)",
+                    duration.as_millis()
+                ))
+            );
+            Some(duration)
+        }
+        None => None,
+    };
     // Unmute system audio if it was muted
     if auto_mute_audio {
         if let Some(manager) = audio_mute_manager {
             if let Err(e) = manager.unmute() {
-                log::warn!("Failed to unmute audio: {e}");
+                log::warn!("{}", log_line(format_args!("Failed to unmute audio: {e}")));
             }
         }
     }
     if sound_enabled {
         audio::play_sound(audio::SoundType::RecordingStop);
     }
-    let _ = app.emit(EventName::RecordingStop.as_str(), ());
+    // A discarded recording is never sent for transcription, so it never
+    // reaches the history either
+    let _ = match discarded_after {
+        Some(duration) => app.emit(
+            EventName::RecordingDiscarded.as_str(),
+            RecordingDiscardedPayload {
+                recording_id,
+                duration_ms: recording_timer::duration_ms(duration),
+                min_recording_ms,
+            },
+        ),
+        None => app.emit(
+            EventName::RecordingStop.as_str(),
+            RecordingStopPayload { recording_id },
+        ),
+    };
+
+    // Bring back the overlay if it was hidden for a disabled app
+    if std::mem::take(&mut *state.overlay_hidden_for_recording.lock().unwrap()) {
+        if let Some(overlay) = app.get_webview_window("overlay") {
+            let _ = overlay.show();
+        }
+    }
 }
 
 /// Paste the last transcription from history
 #[cfg(desktop)]
 fn paste_last_transcription(app: &AppHandle) {
     log::info!("PasteLast: pasting last transcription");
-    let history_storage = app.state::<HistoryStorage>();
-
-    if let Ok(entries) = history_storage.get_all(Some(1)) {
-        if let Some(entry) = entries.first() {
-            if let Err(e) = commands::text::type_text_blocking(&entry.text) {
-                log::error!("Failed to paste last transcription: {e}");
-            }
-        } else {
-            log::info!("PasteLast: no history entries available");
-        }
+    if let Err(e) = commands::history::paste_last_entry_blocking(app) {
+        log::error!("Failed to paste last transcription: {e}");
     }
 }
 
@@ -280,18 +418,30 @@ pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: TauriS
 
     *current_state = match (&*current_state, shortcut_event) {
         (ShortcutState::Idle, ShortcutEvent::TogglePressed) => {
+            // Don't let the overlay auto-hide between the press and the release
+            state.overlay_auto_hide.lock().unwrap().cancel();
+            let recording_id = state.recording_ids.lock().unwrap().prepare();
+            log::debug!(
+                "{}",
+                with_recording_id(Some(&recording_id), "Toggle: preparing to record")
+            );
             let _ = app.emit(EventName::PrepareRecording.as_str(), ());
+            // Give the address bar search a head start before the release
+            active_window::find_address_bar();
             ShortcutState::PreparingToRecordViaToggle
         }
         (ShortcutState::PreparingToRecordViaToggle, ShortcutEvent::ToggleReleased) => {
-            start_recording(
+            if start_recording(
                 app,
                 sound_enabled,
                 audio_mute_manager.as_deref(),
                 auto_mute_audio,
                 "Toggle",
-            );
-            ShortcutState::RecordingViaToggle
+            ) {
+                ShortcutState::RecordingViaToggle
+            } else {
+                ShortcutState::Idle
+            }
         }
         (ShortcutState::RecordingViaToggle, ShortcutEvent::TogglePressed) => {
             ShortcutState::RecordingViaToggle
@@ -307,14 +457,17 @@ pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: TauriS
             ShortcutState::Idle
         }
         (ShortcutState::Idle, ShortcutEvent::HoldPressed) => {
-            start_recording(
+            if start_recording(
                 app,
                 sound_enabled,
                 audio_mute_manager.as_deref(),
                 auto_mute_audio,
                 "Hold",
-            );
-            ShortcutState::RecordingViaHold
+            ) {
+                ShortcutState::RecordingViaHold
+            } else {
+                ShortcutState::Idle
+            }
         }
         (ShortcutState::RecordingViaHold, ShortcutEvent::HoldReleased) => {
             stop_recording(
@@ -442,6 +595,7 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .manage(AppState::default())
         .manage(config_sync::new_config_sync())
+        .manage(ReconnectManager::default())
         .invoke_handler(tauri::generate_handler![
             commands::text::type_text,
             commands::text::get_server_url,
@@ -454,6 +608,19 @@ pub fn run() {
             commands::settings::update_selected_mic,
             commands::settings::update_sound_enabled,
             commands::settings::update_cleanup_prompt_sections,
+            commands::settings::update_profile_prompt_overrides,
+            commands::settings::update_profile_rules,
+            commands::settings::update_disabled_apps,
+            commands::settings::update_suppress_recording_in_disabled_apps,
+            commands::settings::update_store_empty_recordings,
+            commands::settings::update_operating_mode,
+            commands::settings::update_history_dedup_enabled,
+            commands::settings::update_history_dedup_window_seconds,
+            commands::settings::update_history_retention_days,
+            commands::settings::update_overlay_auto_hide_ms,
+            commands::settings::update_min_recording_ms,
+            commands::settings::update_active_window_detection_enabled,
+            commands::settings::update_api_base_path,
             commands::settings::update_stt_provider,
             commands::settings::update_llm_provider,
             commands::settings::update_auto_mute_audio,
@@ -465,6 +632,8 @@ pub fn run() {
             commands::history::get_history,
             commands::history::delete_history_entry,
             commands::history::clear_history,
+            commands::history::prune_history,
+            commands::history::paste_last_transcription,
             commands::export_import::generate_settings_export,
             commands::export_import::generate_history_export,
             commands::export_import::generate_prompt_exports,
@@ -475,8 +644,21 @@ pub fn run() {
             commands::export_import::import_history,
             commands::export_import::factory_reset,
             commands::overlay::resize_overlay,
+            commands::overlay::schedule_overlay_auto_hide,
+            commands::active_window::start_window_watcher,
+            commands::active_window::stop_window_watcher,
+            commands::active_window::test_current_profile_match,
             commands::config_sync::set_server_connected,
             commands::config_sync::set_server_disconnected,
+            commands::config_sync::set_reconnect_scheduled,
+            commands::config_sync::reset_reconnect_status,
+            commands::config_sync::get_reconnect_status,
+            commands::config_sync::set_connection_state,
+            commands::config_sync::get_connection_state,
+            commands::config_sync::get_sync_metrics,
+            commands::config_sync::sync_status,
+            commands::config_sync::set_config_sync_dry_run,
+            commands::config_sync::get_api_base_path,
             start_native_mic,
             stop_native_mic,
             pause_native_mic,
@@ -492,7 +674,31 @@ pub fn run() {
                 .app_data_dir()
                 .expect("Failed to get app data directory");
 
-            let history_storage = HistoryStorage::new(app_data_dir);
+            // Upgrade settings.json before anything loads the store
+            if let Err(e) = migrations::migrate_file(&app_data_dir.join("settings.json")) {
+                log::error!("{e}");
+            }
+
+            // Recordings target the app the user was in, not our own windows
+            let own_titles: Vec<&str> = app
+                .config()
+                .app
+                .windows
+                .iter()
+                .map(|window| window.title.as_str())
+                .chain([OVERLAY_WINDOW_TITLE])
+                .collect();
+            active_window::register_own_windows(active_window::OwnWindows::for_current_exe(
+                &app.config().identifier,
+                &own_titles,
+            ));
+
+            // Every window hears about history changes, whichever one made them
+            let app_handle = app.handle().clone();
+            let history_storage =
+                HistoryStorage::new(app_data_dir).with_change_listener(move |change| {
+                    let _ = app_handle.emit(EventName::HistoryChanged.as_str(), change);
+                });
             app.manage(history_storage);
 
             // Initialize audio mute manager (may be None on unsupported platforms)
@@ -508,11 +714,28 @@ pub fn run() {
             });
             app.manage(mic_capture_manager);
 
+            // Windows opened after the last connection state change ask for it again
+            let app_handle = app.handle().clone();
+            app.listen(EventName::ConnectionStateRequest.as_str(), move |_| {
+                app_handle
+                    .state::<AppState>()
+                    .connection_state
+                    .reemit(|payload| {
+                        let _ = app_handle.emit(EventName::ConnectionState.as_str(), payload);
+                    });
+            });
+
             // Register shortcuts from store (now that store plugin is available)
             // This function handles errors gracefully - it never fails the app startup
             #[cfg(desktop)]
             {
                 register_initial_shortcuts(app.handle());
+                load_profile_rules(app.handle());
+                load_disabled_apps(app.handle());
+                load_operating_mode(app.handle());
+                load_api_base_path(app.handle());
+                load_active_window_detection(app.handle());
+                prune_expired_history(app.handle());
             }
 
             // Create overlay window
@@ -521,7 +744,7 @@ pub fn run() {
                 "overlay",
                 tauri::WebviewUrl::App("overlay.html".into()),
             )
-            .title("Voice Overlay")
+            .title(OVERLAY_WINDOW_TITLE)
             .inner_size(48.0, 48.0)
             .decorations(false)
             .transparent(true)
@@ -625,6 +848,23 @@ fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
             "quit" => {
+                // Let the last config change reach the server; disconnecting
+                // would cancel it. The deadline also covers waiting for the
+                // lock, which a connect or disconnect may be holding.
+                let config_sync = app.state::<config_sync::ConfigSync>().inner().clone();
+                let flushed = tauri::async_runtime::block_on(tokio::time::timeout(
+                    SHUTDOWN_FLUSH_DEADLINE,
+                    async move {
+                        config_sync
+                            .read()
+                            .await
+                            .shutdown_flush(SHUTDOWN_FLUSH_DEADLINE)
+                            .await
+                    },
+                ));
+                if flushed.is_err() {
+                    log::warn!("Config sync flush gave up after {SHUTDOWN_FLUSH_DEADLINE:?}");
+                }
                 // Emit disconnect request to frontend before exiting
                 if let Some(window) = app.get_webview_window("overlay") {
                     let _ = window.emit(EventName::RequestDisconnect.as_str(), ());
@@ -664,12 +904,53 @@ fn build_global_shortcut_plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
     tauri_plugin_global_shortcut::Builder::new().build()
 }
 
-/// Core shortcut registration logic - used by both initial startup and re-registration command
+/// Registers accelerators with the global shortcut plugin, routing them to `handle_shortcut_event`
+#[cfg(desktop)]
+struct GlobalShortcutRegistrar<'a> {
+    app: &'a AppHandle,
+}
+
+#[cfg(desktop)]
+impl shortcut_registrar::ShortcutRegistrar for GlobalShortcutRegistrar<'_> {
+    fn register(&self, accelerator: &str) -> Result<(), state::ShortcutError> {
+        use state::{ShortcutError, ShortcutErrorKind};
+        use std::str::FromStr;
+        use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+        let shortcut = Shortcut::from_str(accelerator).map_err(|e| {
+            log::warn!("Failed to parse shortcut '{accelerator}': {e:?}");
+            ShortcutError::new(accelerator, ShortcutErrorKind::ParseFailed)
+        })?;
+
+        self.app
+            .global_shortcut()
+            .on_shortcut(shortcut, |app_handle, shortcut, event| {
+                handle_shortcut_event(app_handle, shortcut, event);
+            })
+            .map_err(|e| {
+                log::warn!("Failed to register shortcut '{accelerator}': {e}");
+                ShortcutError::new(
+                    accelerator,
+                    ShortcutErrorKind::from_backend_message(&e.to_string()),
+                )
+            })
+    }
+}
+
+/// Core shortcut registration logic - used by both initial startup and re-registration command.
+/// Sleeps between retries, so it must not run on the main thread.
 #[cfg(desktop)]
 pub(crate) fn do_register_shortcuts(app: &AppHandle) -> state::ShortcutRegistrationResult {
-    use state::{ShortcutErrors, ShortcutRegistrationResult};
+    use shortcut_registrar::{register_with_retry, RetryPolicy};
+    use state::{
+        RegisteredShortcut, ShortcutError, ShortcutErrorKind, ShortcutErrors,
+        ShortcutRegistrationResult,
+    };
     use tauri_plugin_global_shortcut::GlobalShortcutExt;
 
+    let state = app.state::<AppState>();
+    let _registering = state.shortcut_registration.lock().unwrap();
+
     // Read hotkeys from store with defaults
     let mut toggle_hotkey: HotkeyConfig =
         get_setting_from_store(app, StoreKey::ToggleHotkey, HotkeyConfig::default_toggle());
@@ -709,31 +990,78 @@ pub(crate) fn do_register_shortcuts(app: &AppHandle) -> state::ShortcutRegistrat
         errors: ShortcutErrors::default(),
     };
 
+    let registrar = GlobalShortcutRegistrar { app };
+    let retry_policy = RetryPolicy::default();
+    let mut registered_shortcuts = Vec::new();
+
     // Helper to try registering a single shortcut
-    let try_register = |hotkey: &mut HotkeyConfig,
-                        name: &str,
-                        store_key: StoreKey,
-                        default_fn: fn() -> HotkeyConfig,
-                        registered: &mut bool,
-                        error: &mut Option<String>| {
+    let mut try_register = |hotkey: &mut HotkeyConfig,
+                            name: &str,
+                            hotkey_type: HotkeyType,
+                            registered: &mut bool,
+                            error: &mut Option<ShortcutError>| {
         if !hotkey.enabled {
             log::info!("{name} shortcut is disabled, skipping");
             return;
         }
 
-        let shortcut = hotkey.to_shortcut_or_default(default_fn);
-        match shortcut_manager.on_shortcut(shortcut, |app_handle, shortcut, event| {
-            handle_shortcut_event(app_handle, shortcut, event);
-        }) {
+        let mut raw_accelerator = hotkey.to_shortcut_string();
+        let accelerator = match accelerator::normalize_accelerator(&raw_accelerator) {
+            Ok(accelerator) => accelerator,
+            Err(e) => {
+                // Report the invalid hotkey, but keep the action reachable with its default
+                let default = hotkey_type.default_hotkey().to_shortcut_string();
+                log::warn!(
+                    "{name} shortcut '{raw_accelerator}' is invalid: {e}. Falling back to {default}."
+                );
+                *error = Some(ShortcutError::new(
+                    std::mem::replace(&mut raw_accelerator, default),
+                    ShortcutErrorKind::ParseFailed,
+                ));
+                accelerator::normalize_accelerator(&raw_accelerator)
+                    .expect("Default hotkey must be valid")
+            }
+        };
+
+        // An invalid hotkey stays reported while its default is registered
+        let fallback_error = error.clone();
+        let outcome = register_with_retry(
+            &registrar,
+            &accelerator,
+            &retry_policy,
+            std::thread::sleep,
+            |_attempt, result| {
+                // Publish each attempt's outcome before telling the UI to refresh
+                let attempt_error = result.as_ref().err().or(fallback_error.as_ref());
+                if let Ok(mut shortcut_errors) = state.shortcut_errors.write() {
+                    shortcut_errors.set(hotkey_type, attempt_error.cloned());
+                }
+                let _ = app.emit(EventName::SettingsChanged.as_str(), ());
+            },
+        );
+
+        match outcome {
             Ok(()) => {
                 *registered = true;
-                log::info!("{name} shortcut registered");
+                log::info!("{name} shortcut registered as {accelerator}");
+                registered_shortcuts.push(RegisteredShortcut {
+                    hotkey_type,
+                    raw: raw_accelerator,
+                    normalized: accelerator,
+                });
+            }
+            Err(e) if e.kind == ShortcutErrorKind::ParseFailed => {
+                log::warn!("{name} shortcut is invalid: {}", e.message);
+                *error = Some(e);
             }
             Err(e) => {
-                *error = Some(format!("Hotkey conflict: {e}"));
-                log::warn!("Failed to register {name} shortcut: {e}. Auto-disabling.");
+                log::warn!(
+                    "Failed to register {name} shortcut: {}. Auto-disabling.",
+                    e.message
+                );
+                *error = Some(e);
                 hotkey.enabled = false;
-                let _ = save_setting_to_store(app, store_key, hotkey);
+                let _ = save_setting_to_store(app, hotkey_type.store_key(), hotkey);
             }
         }
     };
@@ -741,53 +1069,134 @@ pub(crate) fn do_register_shortcuts(app: &AppHandle) -> state::ShortcutRegistrat
     try_register(
         &mut toggle_hotkey,
         "Toggle",
-        StoreKey::ToggleHotkey,
-        HotkeyConfig::default_toggle,
+        HotkeyType::Toggle,
         &mut result.toggle_registered,
         &mut result.errors.toggle_error,
     );
     try_register(
         &mut hold_hotkey,
         "Hold",
-        StoreKey::HoldHotkey,
-        HotkeyConfig::default_hold,
+        HotkeyType::Hold,
         &mut result.hold_registered,
         &mut result.errors.hold_error,
     );
     try_register(
         &mut paste_last_hotkey,
         "PasteLast",
-        StoreKey::PasteLastHotkey,
-        HotkeyConfig::default_paste_last,
+        HotkeyType::PasteLast,
         &mut result.paste_last_registered,
         &mut result.errors.paste_last_error,
     );
     try_register(
         &mut translation_hotkey,
         "Translation",
-        StoreKey::TranslationHotkey,
-        HotkeyConfig::default_translation,
+        HotkeyType::Translation,
         &mut result.translation_registered,
         &mut result.errors.translation_error,
     );
 
-    // Store errors in app state
-    if let Some(state) = app.try_state::<AppState>() {
-        if let Ok(mut shortcut_errors) = state.shortcut_errors.write() {
-            *shortcut_errors = result.errors.clone();
-        }
+    // Store errors and registered shortcuts in app state
+    if let Ok(mut shortcut_errors) = state.shortcut_errors.write() {
+        *shortcut_errors = result.errors.clone();
+    }
+    if let Ok(mut registered) = state.registered_shortcuts.write() {
+        *registered = registered_shortcuts;
     }
 
     result
 }
 
-/// Register shortcuts from store settings (called from `setup()` after store plugin is available)
+/// Load profile rules from the store into app state (called from `setup()`).
+/// With a default profile set, windows no rule matches get that profile.
 #[cfg(desktop)]
-fn register_initial_shortcuts(app: &AppHandle) {
-    let result = do_register_shortcuts(app);
-    if result.errors.has_any_error() {
-        log::warn!("Some shortcuts failed to register. Check settings to resolve conflicts.");
-    } else {
-        log::info!("All shortcuts registered successfully");
+fn load_profile_rules(app: &AppHandle) {
+    let rules: Vec<profile_matcher::ProfileRule> =
+        get_setting_from_store(app, StoreKey::ProfileRules, Vec::new());
+    let state = app.state::<AppState>();
+    let selection = &state.profile_selection;
+    if let Err(e) = profile_matcher::replace_rules(selection.rules(), rules) {
+        log::warn!("Ignoring stored profile rules: {e}");
+    }
+
+    let default_profile: Option<String> =
+        get_setting_from_store(app, StoreKey::DefaultProfileId, None);
+    if let Some(profile_id) = default_profile.filter(|id| !id.is_empty()) {
+        log::info!("Falling back to profile {profile_id} when no rule matches");
+        selection.set_resolver(Box::new(ChainResolver::new(vec![
+            Box::new(selection.rule_based()),
+            Box::new(FixedResolver::new(profile_id)),
+        ])));
+    }
+}
+
+/// Load disabled app patterns from the store into app state (called from `setup()`)
+#[cfg(desktop)]
+fn load_disabled_apps(app: &AppHandle) {
+    let apps: Vec<String> = get_setting_from_store(app, StoreKey::DisabledApps, Vec::new());
+    match disabled_apps::DisabledApps::compile(&apps) {
+        Ok(disabled_apps) => {
+            *app.state::<AppState>().disabled_apps.write().unwrap() = disabled_apps;
+        }
+        Err(e) => log::warn!("Ignoring stored disabled apps: {e}"),
     }
 }
+
+/// Apply the stored operating mode to config sync before the overlay connects
+#[cfg(desktop)]
+fn load_operating_mode(app: &AppHandle) {
+    let mode: settings::OperatingMode =
+        get_setting_from_store(app, StoreKey::OperatingMode, Default::default());
+    app.state::<config_sync::ConfigSync>()
+        .blocking_write()
+        .set_mode(mode);
+}
+
+/// Apply the stored API base path to config sync before the overlay connects
+#[cfg(desktop)]
+fn load_api_base_path(app: &AppHandle) {
+    let path: String = get_setting_from_store(
+        app,
+        StoreKey::ApiBasePath,
+        settings::DEFAULT_API_BASE_PATH.to_string(),
+    );
+    app.state::<config_sync::ConfigSync>()
+        .blocking_write()
+        .set_api_base_path(&path);
+}
+
+/// Apply the stored active window detection switch before the first recording
+#[cfg(desktop)]
+fn load_active_window_detection(app: &AppHandle) {
+    let enabled: bool = get_setting_from_store(app, StoreKey::ActiveWindowDetectionEnabled, true);
+    active_window::set_detection_enabled(enabled);
+}
+
+/// Prune history older than the retention setting, if one is set
+#[cfg(desktop)]
+fn prune_expired_history(app: &AppHandle) {
+    let days: Option<u32> = get_setting_from_store(app, StoreKey::HistoryRetentionDays, None);
+    let Some(days) = days else {
+        return;
+    };
+    // A stored 0 is refused by `prune` rather than taken as "delete all"
+    match app.state::<HistoryStorage>().prune(days, false) {
+        Ok(0) => {}
+        Ok(removed) => log::info!("Pruned {removed} history entries older than {days} days"),
+        Err(e) => log::warn!("Failed to prune history: {e}"),
+    }
+}
+
+/// Register shortcuts from store settings (called from `setup()` after store plugin is available).
+/// Runs in the background so retries don't hold up startup.
+#[cfg(desktop)]
+fn register_initial_shortcuts(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = do_register_shortcuts(&app);
+        if result.errors.has_any_error() {
+            log::warn!("Some shortcuts failed to register. Check settings to resolve conflicts.");
+        } else {
+            log::info!("All shortcuts registered successfully");
+        }
+    });
+}
@@ -0,0 +1,165 @@
+//! Upgrades for the on-disk settings file (`settings.json`).
+//!
+//! The file records its `schema_version`; a file without one predates
+//! versioning and is version 0. `MIGRATIONS[n]` upgrades a version `n` file to
+//! `n + 1`, so they are applied in order from the file's version up to
+//! `CURRENT_SCHEMA_VERSION`.
+
+use serde_json::{Map, Value};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::settings::{AppSettings, StoreKey};
+
+/// A single upgrade step from one schema version to the next
+type Migration = fn(Value) -> Value;
+
+const MIGRATIONS: &[Migration] = &[v0_add_profile_and_app_settings];
+
+/// Schema version written by this build: one past the last migration
+pub const CURRENT_SCHEMA_VERSION: u64 = MIGRATIONS.len() as u64;
+
+/// Errors that can occur while migrating settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationError {
+    /// The settings file isn't a JSON object
+    NotAnObject,
+    /// `schema_version` isn't a non-negative integer
+    InvalidVersion(String),
+    /// The file was written by a newer version of the app
+    UnsupportedVersion { found: u64, supported: u64 },
+    /// The migrated settings don't match the current schema
+    Invalid(String),
+    /// The settings file or its backup couldn't be read or written
+    Io(String),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAnObject => write!(f, "Settings file is not a JSON object"),
+            Self::InvalidVersion(version) => {
+                write!(f, "Invalid settings schema version: {version}")
+            }
+            Self::UnsupportedVersion { found, supported } => write!(
+                f,
+                "Settings schema version {found} is newer than this app supports ({supported}); \
+                 update the app to use these settings"
+            ),
+            Self::Invalid(e) => write!(f, "Invalid settings after migration: {e}"),
+            Self::Io(e) => write!(f, "Settings migration failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+/// Schema version recorded in raw settings (0 if absent)
+pub fn schema_version(raw: &Value) -> Result<u64, MigrationError> {
+    let object = raw.as_object().ok_or(MigrationError::NotAnObject)?;
+    match object.get(StoreKey::SchemaVersion.as_str()) {
+        None => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| MigrationError::InvalidVersion(version.to_string())),
+    }
+}
+
+/// Upgrade raw settings to the current schema, keeping keys the migrations
+/// don't touch (including ones `AppSettings` doesn't model)
+pub fn migrate_value(raw: Value) -> Result<Value, MigrationError> {
+    let version = schema_version(&raw)?;
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(MigrationError::UnsupportedVersion {
+            found: version,
+            supported: CURRENT_SCHEMA_VERSION,
+        });
+    }
+
+    let mut value = MIGRATIONS
+        .iter()
+        .skip(usize::try_from(version).unwrap_or(usize::MAX))
+        .fold(raw, |value, migration| migration(value));
+
+    if let Value::Object(ref mut object) = value {
+        object.insert(
+            StoreKey::SchemaVersion.as_str().to_string(),
+            Value::from(CURRENT_SCHEMA_VERSION),
+        );
+    }
+    Ok(value)
+}
+
+/// Upgrade raw settings to the current schema and parse them.
+/// Settings missing from the file take their default values.
+pub fn migrate(raw: Value) -> Result<AppSettings, MigrationError> {
+    let migrated = migrate_value(raw)?;
+
+    let mut merged = serde_json::to_value(AppSettings::default())
+        .map_err(|e| MigrationError::Invalid(e.to_string()))?;
+    if let (Value::Object(defaults), Value::Object(stored)) = (&mut merged, migrated) {
+        defaults.extend(stored);
+    }
+    serde_json::from_value(merged).map_err(|e| MigrationError::Invalid(e.to_string()))
+}
+
+/// Path of the backup kept from before migrating a version `version` file
+pub fn backup_path(path: &Path, version: u64) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(".v{version}.bak"));
+    path.with_file_name(file_name)
+}
+
+/// Migrate the settings file at `path` in place, backing up the original first.
+/// Returns whether the file was changed. A missing file is left alone.
+pub fn migrate_file(path: &Path) -> Result<bool, MigrationError> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(MigrationError::Io(e.to_string())),
+    };
+    let raw: Value =
+        serde_json::from_str(&contents).map_err(|e| MigrationError::Invalid(e.to_string()))?;
+
+    let version = schema_version(&raw)?;
+    if version == CURRENT_SCHEMA_VERSION {
+        return Ok(false);
+    }
+    let migrated = migrate_value(raw)?;
+
+    let backup = backup_path(path, version);
+    fs::write(&backup, &contents).map_err(|e| MigrationError::Io(e.to_string()))?;
+    let json = serde_json::to_string_pretty(&migrated)
+        .map_err(|e| MigrationError::Invalid(e.to_string()))?;
+    fs::write(path, json).map_err(|e| MigrationError::Io(e.to_string()))?;
+
+    log::info!(
+        "Migrated settings from schema version {version} to {CURRENT_SCHEMA_VERSION} (backup: {})",
+        backup.display()
+    );
+    Ok(true)
+}
+
+// ============================================================================
+// MIGRATIONS
+// ============================================================================
+
+/// Insert `value` under `key` unless the settings already have it
+fn insert_missing(object: &mut Map<String, Value>, key: StoreKey, value: Value) {
+    object.entry(key.as_str()).or_insert(value);
+}
+
+/// v0 → v1: add profile rules and disabled apps, which older files don't have
+fn v0_add_profile_and_app_settings(mut value: Value) -> Value {
+    if let Value::Object(ref mut object) = value {
+        insert_missing(object, StoreKey::ProfileRules, Value::Array(Vec::new()));
+        insert_missing(object, StoreKey::DisabledApps, Value::Array(Vec::new()));
+        insert_missing(
+            object,
+            StoreKey::SuppressRecordingInDisabledApps,
+            Value::Bool(false),
+        );
+    }
+    value
+}
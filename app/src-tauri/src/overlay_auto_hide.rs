@@ -0,0 +1,91 @@
+//! Hides the overlay a while after a transcription completes.
+//!
+//! `overlay_auto_hide_ms` sets the delay, with `0` keeping the overlay up
+//! until the next action. Every scheduled hide gets a ticket, and the timer
+//! thread only hides the overlay if its ticket is still the pending one when
+//! it wakes up. Starting a new recording cancels the pending ticket, so a
+//! timer that fires just after the user started again doesn't make the
+//! overlay flicker away mid-recording.
+
+use std::time::{Duration, Instant};
+
+use crate::clock::{Clock, SystemClock};
+
+/// A scheduled hide, handed to the thread that waits for its deadline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HideTicket {
+    generation: u64,
+    pub deadline: Instant,
+}
+
+pub struct OverlayAutoHide<C: Clock = SystemClock> {
+    clock: C,
+    generation: u64,
+    pending: Option<HideTicket>,
+    hidden: bool,
+}
+
+impl Default for OverlayAutoHide {
+    fn default() -> Self {
+        Self::new(SystemClock)
+    }
+}
+
+impl<C: Clock> OverlayAutoHide<C> {
+    pub fn new(clock: C) -> Self {
+        Self {
+            clock,
+            generation: 0,
+            pending: None,
+            hidden: false,
+        }
+    }
+
+    /// Schedule hiding the overlay `timeout` from now, replacing any pending
+    /// hide. A zero timeout keeps the overlay up and returns `None`.
+    pub fn schedule(&mut self, timeout: Duration) -> Option<HideTicket> {
+        self.cancel();
+        if timeout.is_zero() {
+            return None;
+        }
+        let ticket = HideTicket {
+            generation: self.generation,
+            deadline: self.clock.now() + timeout,
+        };
+        self.pending = Some(ticket);
+        Some(ticket)
+    }
+
+    /// Drop the pending hide, if any. Tickets already handed out stop firing.
+    pub fn cancel(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        self.pending = None;
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Called when `ticket`'s timer wakes up. Returns whether the overlay
+    /// should be hidden now: the ticket is still pending and its deadline
+    /// has passed.
+    pub fn fire(&mut self, ticket: HideTicket) -> bool {
+        if self.pending != Some(ticket) || self.clock.now() < ticket.deadline {
+            return false;
+        }
+        self.pending = None;
+        self.hidden = true;
+        true
+    }
+
+    /// A recording is about to start: cancel the pending hide and return
+    /// whether the overlay was auto-hidden and has to be shown again
+    pub fn recording_started(&mut self) -> bool {
+        self.cancel();
+        std::mem::take(&mut self.hidden)
+    }
+}
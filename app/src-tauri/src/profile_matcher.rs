@@ -0,0 +1,279 @@
+//! Matching the active window to a formatting profile.
+//!
+//! Users configure an ordered list of rules, each pointing at a profile. The
+//! first rule that matches the active window wins. Rules are compiled once
+//! when they are updated, so matching at record time never fails.
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::RwLock;
+use url::Url;
+
+use crate::active_window::ActiveWindowInfo;
+
+/// What a rule looks at in the active window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MatcherKind {
+    /// Regex matched against the app name, bundle ID or process name
+    App { pattern: String },
+    /// Regex matched against the window title
+    Title { pattern: String },
+    /// Host and/or path prefix of the browser tab's URL (see `UrlMatcher`)
+    Url {
+        host: Option<String>,
+        path_prefix: Option<String>,
+    },
+}
+
+/// A user-configured rule mapping matching windows to a profile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileRule {
+    pub profile_id: String,
+    pub matcher: MatcherKind,
+}
+
+/// Errors that can occur while compiling profile rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileRuleError {
+    /// A rule's pattern is not a valid regex
+    InvalidPattern { pattern: String, message: String },
+}
+
+impl fmt::Display for ProfileRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPattern { pattern, message } => {
+                write!(f, "Invalid pattern '{pattern}': {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProfileRuleError {}
+
+/// Host pattern of a `UrlMatcher`
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HostPattern {
+    /// Only this host
+    Exact(String),
+    /// `*.example.com`: the domain itself and any subdomain of it
+    Subdomains(String),
+}
+
+/// Matches a URL by host and path prefix, without the user writing a regex.
+///
+/// Hosts are compared case-insensitively and ignore a trailing dot; a leading
+/// `*.` also matches any subdomain. The path prefix matches whole segments, so
+/// `/mail` matches `/mail` and `/mail/u/0` but not `/mailbox`. URLs that can't
+/// be parsed never match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlMatcher {
+    host: Option<HostPattern>,
+    path_prefix: Option<String>,
+}
+
+impl UrlMatcher {
+    pub fn new(host: Option<&str>, path_prefix: Option<&str>) -> Result<Self, ProfileRuleError> {
+        let host = host
+            .map(str::trim)
+            .filter(|h| !h.is_empty())
+            .map(parse_host_pattern)
+            .transpose()?;
+        let path_prefix = path_prefix
+            .map(|p| p.trim().trim_end_matches('/'))
+            .filter(|p| !p.is_empty())
+            .map(|p| {
+                if p.starts_with('/') {
+                    p.to_string()
+                } else {
+                    format!("/{p}")
+                }
+            });
+        Ok(Self { host, path_prefix })
+    }
+
+    pub fn matches(&self, url: &str) -> bool {
+        let Some(url) = parse_url(url) else {
+            return false;
+        };
+        self.matches_host(url.host_str()) && self.matches_path(url.path())
+    }
+
+    fn matches_host(&self, host: Option<&str>) -> bool {
+        let Some(ref pattern) = self.host else {
+            return true;
+        };
+        let Some(host) = host else {
+            return false;
+        };
+        let host = normalize_host(host);
+        match pattern {
+            HostPattern::Exact(expected) => host == *expected,
+            HostPattern::Subdomains(domain) => {
+                host == *domain
+                    || host
+                        .strip_suffix(domain.as_str())
+                        .is_some_and(|sub| sub.ends_with('.'))
+            }
+        }
+    }
+
+    fn matches_path(&self, path: &str) -> bool {
+        let Some(ref prefix) = self.path_prefix else {
+            return true;
+        };
+        path.strip_prefix(prefix.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_end_matches('.').to_lowercase()
+}
+
+fn parse_host_pattern(pattern: &str) -> Result<HostPattern, ProfileRuleError> {
+    let invalid = |message: &str| ProfileRuleError::InvalidPattern {
+        pattern: pattern.to_string(),
+        message: message.to_string(),
+    };
+    let (domain, subdomains) = match pattern.strip_prefix("*.") {
+        Some(domain) => (domain, true),
+        None => (pattern, false),
+    };
+    let domain = normalize_host(domain);
+    if domain.is_empty() {
+        return Err(invalid("host is empty"));
+    }
+    if domain.contains(['*', '/', ':']) {
+        return Err(invalid("expected a host like example.com or *.example.com"));
+    }
+    Ok(if subdomains {
+        HostPattern::Subdomains(domain)
+    } else {
+        HostPattern::Exact(domain)
+    })
+}
+
+/// Parse a URL as reported by a browser. Some report it without a scheme.
+fn parse_url(url: &str) -> Option<Url> {
+    let url = url.trim();
+    match Url::parse(url) {
+        Ok(url) => Some(url),
+        Err(url::ParseError::RelativeUrlWithoutBase) => Url::parse(&format!("https://{url}")).ok(),
+        Err(_) => None,
+    }
+}
+
+#[derive(Debug, Clone)]
+enum CompiledPattern {
+    App(Regex),
+    Title(Regex),
+    Url(UrlMatcher),
+}
+
+/// A matcher with its pattern compiled, ready to test against windows
+#[derive(Debug, Clone)]
+pub struct CompiledMatcher {
+    pattern: CompiledPattern,
+}
+
+impl CompiledMatcher {
+    pub fn compile(kind: MatcherKind) -> Result<Self, ProfileRuleError> {
+        let pattern = match kind {
+            MatcherKind::App { pattern } => CompiledPattern::App(compile_regex(&pattern)?),
+            MatcherKind::Title { pattern } => CompiledPattern::Title(compile_regex(&pattern)?),
+            MatcherKind::Url { host, path_prefix } => {
+                CompiledPattern::Url(UrlMatcher::new(host.as_deref(), path_prefix.as_deref())?)
+            }
+        };
+        Ok(Self { pattern })
+    }
+
+    pub fn matches(&self, info: &ActiveWindowInfo) -> bool {
+        match self.pattern {
+            CompiledPattern::App(ref regex) => [
+                Some(info.app_name.as_str()),
+                info.bundle_id.as_deref(),
+                info.process_name.as_deref(),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|identifier| regex.is_match(identifier)),
+            CompiledPattern::Title(ref regex) => regex.is_match(&info.window_title),
+            CompiledPattern::Url(ref matcher) => {
+                info.url.as_deref().is_some_and(|url| matcher.matches(url))
+            }
+        }
+    }
+}
+
+fn compile_regex(pattern: &str) -> Result<Regex, ProfileRuleError> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| ProfileRuleError::InvalidPattern {
+            pattern: pattern.to_string(),
+            message: e.to_string(),
+        })
+}
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    profile_id: String,
+    matcher: CompiledMatcher,
+}
+
+impl CompiledRule {
+    fn compile(rule: ProfileRule) -> Result<Self, ProfileRuleError> {
+        Ok(Self {
+            profile_id: rule.profile_id,
+            matcher: CompiledMatcher::compile(rule.matcher)?,
+        })
+    }
+}
+
+/// An ordered, pre-compiled set of profile rules
+#[derive(Debug, Clone, Default)]
+pub struct ProfileRuleSet {
+    rules: Vec<CompiledRule>,
+}
+
+impl ProfileRuleSet {
+    /// Compile every rule, failing on the first invalid pattern
+    pub fn compile(rules: Vec<ProfileRule>) -> Result<Self, ProfileRuleError> {
+        let rules = rules
+            .into_iter()
+            .map(CompiledRule::compile)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Index of the first rule matching the window
+    pub fn matching_rule(&self, info: &ActiveWindowInfo) -> Option<usize> {
+        self.rules.iter().position(|r| r.matcher.matches(info))
+    }
+
+    /// Profile ID of the first rule matching the window
+    pub fn match_profile(&self, info: &ActiveWindowInfo) -> Option<&str> {
+        self.matching_rule(info)
+            .and_then(|index| self.profile_id(index))
+    }
+
+    /// Profile ID of the rule at `index`
+    pub fn profile_id(&self, index: usize) -> Option<&str> {
+        self.rules.get(index).map(|r| r.profile_id.as_str())
+    }
+}
+
+/// Compile `rules` and swap them into `rule_set`. On error the previous rules stay active.
+pub fn replace_rules(
+    rule_set: &RwLock<ProfileRuleSet>,
+    rules: Vec<ProfileRule>,
+) -> Result<(), ProfileRuleError> {
+    // Compile outside the lock so matching is never blocked on regex compilation
+    let compiled = ProfileRuleSet::compile(rules)?;
+    *rule_set.write().unwrap() = compiled;
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! Choosing the formatting profile for a recording.
+//!
+//! Recordings ask a `ProfileResolver` for the profile of the active window
+//! instead of going to the profile rules directly, so other ways of picking a
+//! profile can be slotted in. By default the user's rules decide
+//! (`RuleBasedResolver`); resolvers can be combined with `ChainResolver`.
+
+use serde::Serialize;
+use std::sync::{Arc, RwLock};
+
+use crate::active_window::ActiveWindowInfo;
+use crate::profile_matcher::ProfileRuleSet;
+
+/// Picks a formatting profile for a window
+pub trait ProfileResolver: Send + Sync {
+    /// Profile ID for the window, or `None` to use the default profile
+    fn resolve(&self, info: &ActiveWindowInfo) -> Option<String>;
+}
+
+/// Resolves with the user's profile rules. Shares the rule set, so rule
+/// updates apply without replacing the resolver.
+pub struct RuleBasedResolver {
+    rules: Arc<RwLock<ProfileRuleSet>>,
+}
+
+impl RuleBasedResolver {
+    pub fn new(rules: Arc<RwLock<ProfileRuleSet>>) -> Self {
+        Self { rules }
+    }
+}
+
+impl ProfileResolver for RuleBasedResolver {
+    fn resolve(&self, info: &ActiveWindowInfo) -> Option<String> {
+        self.rules
+            .read()
+            .unwrap()
+            .match_profile(info)
+            .map(str::to_string)
+    }
+}
+
+/// Always picks the same profile
+pub struct FixedResolver {
+    profile_id: String,
+}
+
+impl FixedResolver {
+    pub fn new(profile_id: impl Into<String>) -> Self {
+        Self {
+            profile_id: profile_id.into(),
+        }
+    }
+}
+
+impl ProfileResolver for FixedResolver {
+    fn resolve(&self, _info: &ActiveWindowInfo) -> Option<String> {
+        Some(self.profile_id.clone())
+    }
+}
+
+/// Tries resolvers in order; the first one to pick a profile wins
+pub struct ChainResolver {
+    resolvers: Vec<Box<dyn ProfileResolver>>,
+}
+
+impl ChainResolver {
+    pub fn new(resolvers: Vec<Box<dyn ProfileResolver>>) -> Self {
+        Self { resolvers }
+    }
+}
+
+impl ProfileResolver for ChainResolver {
+    fn resolve(&self, info: &ActiveWindowInfo) -> Option<String> {
+        self.resolvers
+            .iter()
+            .find_map(|resolver| resolver.resolve(info))
+    }
+}
+
+/// Which profile a window gets, and why, for checking rules from the settings UI
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileMatchResult {
+    pub window: ActiveWindowInfo,
+    /// Profile the resolver picked, or `None` for the default profile
+    pub matched: Option<String>,
+    /// Index of the rule that picked `matched`. `None` when no rule matched or
+    /// the profile came from another resolver (e.g. the default profile).
+    pub matched_rule_index: Option<usize>,
+}
+
+/// The user's profile rules and the resolver recordings use
+pub struct ProfileSelection {
+    rules: Arc<RwLock<ProfileRuleSet>>,
+    resolver: RwLock<Box<dyn ProfileResolver>>,
+}
+
+impl Default for ProfileSelection {
+    /// Resolves with the (initially empty) profile rules
+    fn default() -> Self {
+        let rules = Arc::new(RwLock::new(ProfileRuleSet::default()));
+        Self {
+            resolver: RwLock::new(Box::new(RuleBasedResolver::new(rules.clone()))),
+            rules,
+        }
+    }
+}
+
+impl ProfileSelection {
+    /// The compiled profile rules, for `profile_matcher::replace_rules`
+    pub fn rules(&self) -> &RwLock<ProfileRuleSet> {
+        &self.rules
+    }
+
+    /// A resolver over the profile rules, e.g. to chain with other resolvers
+    pub fn rule_based(&self) -> RuleBasedResolver {
+        RuleBasedResolver::new(self.rules.clone())
+    }
+
+    /// Replace the resolver recordings use. Takes effect from the next recording.
+    pub fn set_resolver(&self, resolver: Box<dyn ProfileResolver>) {
+        *self.resolver.write().unwrap() = resolver;
+    }
+
+    /// Profile for the window, as picked by the current resolver
+    pub fn resolve(&self, info: &ActiveWindowInfo) -> Option<String> {
+        self.resolver.read().unwrap().resolve(info)
+    }
+
+    /// Resolve the window's profile and find the rule responsible for it
+    pub fn explain(&self, window: ActiveWindowInfo) -> ProfileMatchResult {
+        let matched = self.resolve(&window);
+        let matched_rule_index = {
+            let rules = self.rules.read().unwrap();
+            rules
+                .matching_rule(&window)
+                .filter(|&index| rules.profile_id(index) == matched.as_deref())
+        };
+        ProfileMatchResult {
+            window,
+            matched,
+            matched_rule_index,
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Reconnect progress reported by the overlay.
+//!
+//! The connection and its backoff schedule live in the overlay's connection
+//! machine; each time it enters `retrying` it reports the attempt number and
+//! delay via `set_reconnect_scheduled`. `ReconnectController` only records that
+//! schedule so other windows can render "retrying in 8s (attempt 4)". It is
+//! clock-agnostic so it can be tested with a fake clock.
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::clock::{Clock, SystemClock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ScheduledRetry {
+    attempt: u32,
+    next_attempt_at: Instant,
+}
+
+/// Reconnect progress for the UI (e.g. "retrying in 8s (attempt 4)")
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ReconnectStatus {
+    pub active: bool,
+    pub attempt: u32,
+    /// Time left before the attempt starts; absent once it is in flight
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_in_ms: Option<u64>,
+}
+
+/// Last retry scheduled by the overlay's connection machine
+pub struct ReconnectController<C: Clock = SystemClock> {
+    clock: C,
+    scheduled: Option<ScheduledRetry>,
+}
+
+impl<C: Clock> ReconnectController<C> {
+    pub fn new(clock: C) -> Self {
+        Self {
+            clock,
+            scheduled: None,
+        }
+    }
+
+    /// Whether a reconnection is in progress
+    pub fn is_active(&self) -> bool {
+        self.scheduled.is_some()
+    }
+
+    /// The overlay will start `attempt` after `delay`
+    pub fn retry_scheduled(&mut self, attempt: u32, delay: Duration) {
+        self.scheduled = Some(ScheduledRetry {
+            attempt,
+            next_attempt_at: self.clock.now() + delay,
+        });
+    }
+
+    /// Connected, or disconnected on purpose - nothing left to retry
+    pub fn reset(&mut self) {
+        self.scheduled = None;
+    }
+
+    /// Current progress for the UI
+    pub fn status(&self) -> ReconnectStatus {
+        let Some(scheduled) = self.scheduled else {
+            return ReconnectStatus {
+                active: false,
+                attempt: 0,
+                retry_in_ms: None,
+            };
+        };
+        let remaining = scheduled
+            .next_attempt_at
+            .saturating_duration_since(self.clock.now());
+        ReconnectStatus {
+            active: true,
+            attempt: scheduled.attempt,
+            retry_in_ms: (!remaining.is_zero())
+                .then(|| u64::try_from(remaining.as_millis()).unwrap_or(u64::MAX)),
+        }
+    }
+}
+
+/// Shared reconnect progress, managed by Tauri
+pub struct ReconnectManager {
+    controller: Mutex<ReconnectController>,
+}
+
+impl Default for ReconnectManager {
+    fn default() -> Self {
+        Self {
+            controller: Mutex::new(ReconnectController::new(SystemClock)),
+        }
+    }
+}
+
+impl ReconnectManager {
+    /// Current progress for the UI
+    pub fn status(&self) -> ReconnectStatus {
+        self.controller.lock().unwrap().status()
+    }
+
+    /// Record the retry the overlay just scheduled
+    pub fn retry_scheduled(&self, attempt: u32, delay: Duration) {
+        self.controller
+            .lock()
+            .unwrap()
+            .retry_scheduled(attempt, delay);
+    }
+
+    /// Connected or disconnected on purpose - clear the progress
+    pub fn reset(&self) {
+        self.controller.lock().unwrap().reset();
+    }
+}
@@ -0,0 +1,58 @@
+//! Correlation IDs for following one dictation through the logs.
+//!
+//! Every recording gets a fresh ID when it is prepared (toggle press) or
+//! started (hold press). Log lines for that cycle are prefixed with it, the
+//! recording start/stop events carry it, and the overlay sends it along with
+//! the app context so server-side logs can be matched. The ID outlives the
+//! stop event so the transcription and paste that follow still carry it.
+
+use std::fmt;
+use uuid::Uuid;
+
+/// ID of the current (or last) recording
+#[derive(Debug, Default)]
+pub struct RecordingIds {
+    current: Option<String>,
+    /// The current ID was handed out by `prepare` and no recording started with it yet
+    prepared: bool,
+}
+
+impl RecordingIds {
+    /// A toggle press is about to start a recording: give it a new ID
+    pub fn prepare(&mut self) -> String {
+        self.prepared = true;
+        self.current.insert(Uuid::new_v4().to_string()).clone()
+    }
+
+    /// A recording is starting. Reuses the ID from `prepare` if this is the
+    /// recording it was prepared for, otherwise hands out a new one.
+    pub fn start(&mut self) -> String {
+        if std::mem::take(&mut self.prepared) {
+            if let Some(ref id) = self.current {
+                return id.clone();
+            }
+        }
+        self.current.insert(Uuid::new_v4().to_string()).clone()
+    }
+
+    /// The recording was suppressed (e.g. in a disabled app) and never ran:
+    /// drop its ID so nothing that follows is attributed to it
+    pub fn discard(&mut self) {
+        self.current = None;
+        self.prepared = false;
+    }
+
+    /// ID of the recording in progress, or of the last one once it stopped
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+}
+
+/// Prefix a log message with the recording it belongs to:
+/// `log::info!("{}", with_recording_id(id, format_args!("...")))`
+pub fn with_recording_id(id: Option<&str>, message: impl fmt::Display) -> String {
+    match id {
+        Some(id) => format!("[recording {id}] {message}"),
+        None => message.to_string(),
+    }
+}
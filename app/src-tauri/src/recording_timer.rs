@@ -0,0 +1,85 @@
+//! Measures how long each recording lasts, so history entries can show it.
+//!
+//! The timer starts when a hotkey moves the shortcut state machine into a
+//! recording state and stops when the recording ends. Toggle recordings start
+//! and stop on separate key presses, so the start time lives in `AppState`
+//! between the two. Transcription finishes after the recording stops, so the
+//! duration is kept until the resulting history entry takes it.
+
+use std::time::{Duration, Instant};
+
+use crate::clock::{Clock, SystemClock};
+
+/// A recording that just stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoppedRecording {
+    /// Long enough to transcribe
+    Kept(Duration),
+    /// Shorter than the minimum length, so it is dropped without transcribing
+    TooShort(Duration),
+}
+
+pub struct RecordingTimer<C: Clock = SystemClock> {
+    clock: C,
+    started_at: Option<Instant>,
+    last_duration: Option<Duration>,
+}
+
+impl Default for RecordingTimer {
+    fn default() -> Self {
+        Self::new(SystemClock)
+    }
+}
+
+impl<C: Clock> RecordingTimer<C> {
+    pub fn new(clock: C) -> Self {
+        Self {
+            clock,
+            started_at: None,
+            last_duration: None,
+        }
+    }
+
+    /// Mark the start of a recording. A recording that was never stopped is
+    /// discarded, as is the duration of one no history entry has taken.
+    pub fn start(&mut self) {
+        self.started_at = Some(self.clock.now());
+        self.last_duration = None;
+    }
+
+    /// Mark the end of the recording and return how long it lasted,
+    /// or `None` if no recording was running
+    pub fn stop(&mut self) -> Option<Duration> {
+        let started_at = self.started_at.take()?;
+        let duration = self.clock.now().saturating_duration_since(started_at);
+        self.last_duration = Some(duration);
+        Some(duration)
+    }
+
+    /// Like `stop`, but a recording shorter than `min_length` counts as an
+    /// accidental tap: it is reported as `TooShort` and leaves no duration
+    /// for a history entry to take
+    pub fn stop_with_min_length(&mut self, min_length: Duration) -> Option<StoppedRecording> {
+        let duration = self.stop()?;
+        if duration < min_length {
+            self.last_duration = None;
+            return Some(StoppedRecording::TooShort(duration));
+        }
+        Some(StoppedRecording::Kept(duration))
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    /// Duration of the last finished recording. Cleared once taken so it is
+    /// only attached to one history entry.
+    pub fn take_last_duration(&mut self) -> Option<Duration> {
+        self.last_duration.take()
+    }
+}
+
+/// Whole milliseconds in `duration`, as stored in history
+pub fn duration_ms(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
@@ -1,8 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::str::FromStr;
 
-#[cfg(desktop)]
-use tauri_plugin_global_shortcut::Shortcut;
+use crate::profile_matcher::ProfileRule;
 
 // ============================================================================
 // DEFAULT SETTINGS CONSTANTS - Single source of truth for all defaults
@@ -11,6 +11,9 @@ use tauri_plugin_global_shortcut::Shortcut;
 /// Default server URL
 pub const DEFAULT_SERVER_URL: &str = "http://127.0.0.1:8765";
 
+/// Default path the server's API is mounted under
+pub const DEFAULT_API_BASE_PATH: &str = "/api";
+
 // ============================================================================
 // DEFAULT HOTKEY CONSTANTS - Single source of truth for all default hotkeys
 // ============================================================================
@@ -77,6 +80,36 @@ pub enum StoreKey {
     TranslationTargetLanguages,
     /// Default translation target language
     TranslationDefaultLanguage,
+    /// Ordered rules matching the active window to a formatting profile
+    ProfileRules,
+    /// App patterns the recording overlay is hidden in
+    DisabledApps,
+    /// Whether disabled apps also block recording, not just the overlay
+    SuppressRecordingInDisabledApps,
+    /// Whether recordings that produced no text are still added to history
+    StoreEmptyRecordings,
+    /// Whether repeating the most recent history entry refreshes it instead of adding a duplicate
+    HistoryDedupEnabled,
+    /// How recent (in seconds) an entry must be for a repeat to count as a duplicate
+    HistoryDedupWindowSeconds,
+    /// Recordings shorter than this many milliseconds are discarded (0 = keep all)
+    MinRecordingMs,
+    /// Whether the app connects to a server (see `OperatingMode`)
+    OperatingMode,
+    /// Milliseconds after a transcription before the overlay hides (0 = stay until next action)
+    OverlayAutoHideMs,
+    /// Prompt sections that replace the base ones for recordings matched to a profile
+    ProfilePromptOverrides,
+    /// Days history entries are kept; older ones are pruned on startup (unset = forever)
+    HistoryRetentionDays,
+    /// Whether the active window is inspected at all (off = default profile
+    /// always, and disabled apps can't be recognised so are never suppressed)
+    ActiveWindowDetectionEnabled,
+    /// Path the server's API is mounted under, for servers behind a reverse
+    /// proxy at a sub-path
+    ApiBasePath,
+    /// Version of the settings file layout (see `migrations`)
+    SchemaVersion,
 }
 
 impl StoreKey {
@@ -103,6 +136,20 @@ impl StoreKey {
             Self::TranslationEnabled => "translation_enabled",
             Self::TranslationTargetLanguages => "translation_target_languages",
             Self::TranslationDefaultLanguage => "translation_default_language",
+            Self::ProfileRules => "profile_rules",
+            Self::DisabledApps => "disabled_apps",
+            Self::SuppressRecordingInDisabledApps => "suppress_recording_in_disabled_apps",
+            Self::StoreEmptyRecordings => "store_empty_recordings",
+            Self::HistoryDedupEnabled => "history_dedup_enabled",
+            Self::HistoryDedupWindowSeconds => "history_dedup_window_seconds",
+            Self::MinRecordingMs => "min_recording_ms",
+            Self::OperatingMode => "operating_mode",
+            Self::OverlayAutoHideMs => "overlay_auto_hide_ms",
+            Self::ProfilePromptOverrides => "profile_prompt_overrides",
+            Self::HistoryRetentionDays => "history_retention_days",
+            Self::ActiveWindowDetectionEnabled => "active_window_detection_enabled",
+            Self::ApiBasePath => "api_base_path",
+            Self::SchemaVersion => "schema_version",
         }
     }
 }
@@ -173,24 +220,6 @@ impl HotkeyConfig {
         parts.join("+")
     }
 
-    /// Convert to a tauri Shortcut using `FromStr` parsing
-    #[cfg(desktop)]
-    pub fn to_shortcut(&self) -> Result<Shortcut, String> {
-        let shortcut_str = self.to_shortcut_string();
-        Shortcut::from_str(&shortcut_str)
-            .map_err(|e| format!("Failed to parse shortcut '{shortcut_str}': {e:?}"))
-    }
-
-    /// Convert to a tauri Shortcut, falling back to a default if parsing fails
-    #[cfg(desktop)]
-    pub fn to_shortcut_or_default(&self, default_fn: fn() -> Self) -> Shortcut {
-        self.to_shortcut().unwrap_or_else(|_| {
-            default_fn()
-                .to_shortcut()
-                .expect("Default hotkey must be valid")
-        })
-    }
-
     /// Check if two hotkey configs are equivalent (case-insensitive comparison)
     pub fn is_same_as(&self, other: &HotkeyConfig) -> bool {
         if self.key.to_lowercase() != other.key.to_lowercase() {
@@ -309,12 +338,70 @@ impl CleanupPromptSections {
             PromptSectionType::Dictionary => self.dictionary = section,
         }
     }
+
+    /// Replace every section the overrides set, keeping the rest
+    #[must_use]
+    pub fn merged_with(mut self, overrides: &PromptSectionOverrides) -> Self {
+        for section_type in PromptSectionType::ALL {
+            if let Some(section) = overrides.get(section_type) {
+                self.set(section_type, section.clone());
+            }
+        }
+        self
+    }
+}
+
+/// A profile's changes to the base prompt sections. Sections left out use the
+/// base section, so a full `CleanupPromptSections` is also a valid override.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PromptSectionOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub main: Option<PromptSection>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub advanced: Option<PromptSection>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dictionary: Option<PromptSection>,
+}
+
+impl PromptSectionOverrides {
+    pub fn get(&self, section_type: PromptSectionType) -> Option<&PromptSection> {
+        match section_type {
+            PromptSectionType::Main => self.main.as_ref(),
+            PromptSectionType::Advanced => self.advanced.as_ref(),
+            PromptSectionType::Dictionary => self.dictionary.as_ref(),
+        }
+    }
+}
+
+/// Prompt sections for a recording matched to `profile`: the profile's
+/// overrides merged onto the base sections. Without a profile, or for a
+/// profile with no overrides, this is just the base sections.
+pub fn resolve_prompt_sections(
+    profile: Option<&str>,
+    settings: &AppSettings,
+) -> CleanupPromptSections {
+    let base = settings.cleanup_prompt_sections.clone().unwrap_or_default();
+    match profile.and_then(|id| settings.profile_prompt_overrides.get(id)) {
+        Some(overrides) => base.merged_with(overrides),
+        None => base,
+    }
 }
 
 // ============================================================================
 // APP SETTINGS - Complete settings structure
 // ============================================================================
 
+/// Whether the app talks to a server or runs purely locally
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OperatingMode {
+    /// Connect to the configured server and sync settings to it
+    #[default]
+    Connected,
+    /// Never connect or sync; history and profile matching work offline
+    LocalOnly,
+}
+
 /// Complete application settings matching the TypeScript `AppSettings` interface
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
@@ -334,6 +421,47 @@ pub struct AppSettings {
     pub translation_enabled: bool,
     pub translation_target_languages: Vec<String>,
     pub translation_default_language: String,
+    #[serde(default)]
+    pub profile_rules: Vec<ProfileRule>,
+    #[serde(default)]
+    pub disabled_apps: Vec<String>,
+    #[serde(default)]
+    pub suppress_recording_in_disabled_apps: bool,
+    #[serde(default)]
+    pub store_empty_recordings: bool,
+    #[serde(default)]
+    pub history_dedup_enabled: bool,
+    #[serde(default = "default_history_dedup_window_seconds")]
+    pub history_dedup_window_seconds: u64,
+    #[serde(default)]
+    pub operating_mode: OperatingMode,
+    #[serde(default)]
+    pub overlay_auto_hide_ms: u64,
+    #[serde(default)]
+    pub profile_prompt_overrides: HashMap<String, PromptSectionOverrides>,
+    #[serde(default)]
+    pub min_recording_ms: u64,
+    #[serde(default = "default_active_window_detection_enabled")]
+    pub active_window_detection_enabled: bool,
+    #[serde(default)]
+    pub history_retention_days: Option<u32>,
+    #[serde(default = "default_api_base_path")]
+    pub api_base_path: String,
+}
+
+/// Default window in which a repeated history entry counts as a duplicate
+pub const DEFAULT_HISTORY_DEDUP_WINDOW_SECONDS: u64 = 30;
+
+const fn default_history_dedup_window_seconds() -> u64 {
+    DEFAULT_HISTORY_DEDUP_WINDOW_SECONDS
+}
+
+const fn default_active_window_detection_enabled() -> bool {
+    true
+}
+
+fn default_api_base_path() -> String {
+    DEFAULT_API_BASE_PATH.to_string()
 }
 
 impl Default for AppSettings {
@@ -363,6 +491,19 @@ impl Default for AppSettings {
                 "ko".to_string(),
             ],
             translation_default_language: "en".to_string(),
+            profile_rules: Vec::new(),
+            disabled_apps: Vec::new(),
+            suppress_recording_in_disabled_apps: false,
+            store_empty_recordings: false,
+            history_dedup_enabled: false,
+            history_dedup_window_seconds: DEFAULT_HISTORY_DEDUP_WINDOW_SECONDS,
+            operating_mode: OperatingMode::Connected,
+            overlay_auto_hide_ms: 0,
+            profile_prompt_overrides: HashMap::new(),
+            min_recording_ms: 0,
+            active_window_detection_enabled: true,
+            history_retention_days: None,
+            api_base_path: DEFAULT_API_BASE_PATH.to_string(),
         }
     }
 }
@@ -0,0 +1,77 @@
+//! Global shortcut registration with retry for transient OS failures.
+//!
+//! On some systems the global shortcut backend isn't ready the instant the app
+//! launches, so the first registration attempt can be rejected by the OS even
+//! though a later attempt would succeed. Parse failures are never retried since
+//! they can't succeed on a later attempt.
+
+use std::time::Duration;
+
+use crate::state::{ShortcutError, ShortcutErrorKind};
+
+/// Something that can register a global shortcut from an accelerator string
+pub trait ShortcutRegistrar {
+    /// Register the accelerator (e.g. "ctrl+alt+Space")
+    fn register(&self, accelerator: &str) -> Result<(), ShortcutError>;
+}
+
+/// How many times to attempt a registration and how long to wait in between
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay to wait before the given attempt (1-based), doubling after each retry
+    pub fn delay_before(&self, attempt: u32) -> Duration {
+        if attempt <= 1 {
+            Duration::ZERO
+        } else {
+            self.base_delay * 2u32.saturating_pow(attempt - 2)
+        }
+    }
+}
+
+/// Register a shortcut, retrying OS rejections according to `policy`.
+///
+/// `sleep` is called between attempts and `on_attempt` is called after every
+/// attempt with the attempt number and its outcome. Returns the final outcome.
+pub fn register_with_retry<R: ShortcutRegistrar + ?Sized>(
+    registrar: &R,
+    accelerator: &str,
+    policy: &RetryPolicy,
+    sleep: impl Fn(Duration),
+    mut on_attempt: impl FnMut(u32, &Result<(), ShortcutError>),
+) -> Result<(), ShortcutError> {
+    let mut attempt = 1;
+    loop {
+        let result = registrar.register(accelerator);
+        on_attempt(attempt, &result);
+
+        match result {
+            Err(ref e)
+                if e.kind == ShortcutErrorKind::OsRejected && attempt < policy.max_attempts =>
+            {
+                attempt += 1;
+                let delay = policy.delay_before(attempt);
+                log::info!(
+                    "Registering {accelerator} was rejected, retrying in {}ms (attempt {attempt}/{})",
+                    delay.as_millis(),
+                    policy.max_attempts
+                );
+                sleep(delay);
+            }
+            result => return result,
+        }
+    }
+}
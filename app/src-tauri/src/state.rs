@@ -1,6 +1,15 @@
 use serde::{Deserialize, Serialize};
 use std::sync::{Mutex, RwLock};
 
+use crate::connection_state::ConnectionStateCache;
+use crate::disabled_apps::DisabledApps;
+use crate::overlay_auto_hide::OverlayAutoHide;
+use crate::profile_resolver::ProfileSelection;
+use crate::recording_id::RecordingIds;
+use crate::recording_timer::RecordingTimer;
+use crate::settings::HotkeyType;
+use crate::window_watcher::WindowWatcherHandle;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ShortcutState {
     #[default]
@@ -11,13 +20,69 @@ pub enum ShortcutState {
     WaitingForPasteKeyRelease,
 }
 
+/// Why a shortcut failed to register
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutErrorKind {
+    /// The accelerator string could not be parsed into a shortcut
+    ParseFailed,
+    /// The shortcut is already registered (by us or another app)
+    AlreadyRegistered,
+    /// The OS refused the registration for another reason
+    OsRejected,
+}
+
+impl ShortcutErrorKind {
+    /// Classify a registration error reported by the global shortcut backend.
+    /// The plugin only exposes errors as strings, so this matches on the message.
+    pub fn from_backend_message(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        if lower.contains("already registered") || lower.contains("already in use") {
+            Self::AlreadyRegistered
+        } else if lower.contains("parse") || lower.contains("unrecognized") {
+            Self::ParseFailed
+        } else {
+            Self::OsRejected
+        }
+    }
+}
+
+/// A failed shortcut registration, with the accelerator that was attempted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShortcutError {
+    pub accelerator: String,
+    pub kind: ShortcutErrorKind,
+    /// Human-readable message suitable for display in the settings UI
+    pub message: String,
+}
+
+impl ShortcutError {
+    pub fn new(accelerator: impl Into<String>, kind: ShortcutErrorKind) -> Self {
+        let accelerator = accelerator.into();
+        let message = match kind {
+            ShortcutErrorKind::ParseFailed => format!("{accelerator} is not a valid shortcut"),
+            ShortcutErrorKind::AlreadyRegistered => {
+                format!("{accelerator} is already in use by another shortcut")
+            }
+            ShortcutErrorKind::OsRejected => {
+                format!("{accelerator} was rejected by the operating system")
+            }
+        };
+        Self {
+            accelerator,
+            kind,
+            message,
+        }
+    }
+}
+
 #[allow(clippy::struct_field_names)]
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ShortcutErrors {
-    pub toggle_error: Option<String>,
-    pub hold_error: Option<String>,
-    pub paste_last_error: Option<String>,
-    pub translation_error: Option<String>,
+    pub toggle_error: Option<ShortcutError>,
+    pub hold_error: Option<ShortcutError>,
+    pub paste_last_error: Option<ShortcutError>,
+    pub translation_error: Option<ShortcutError>,
 }
 
 impl ShortcutErrors {
@@ -27,6 +92,17 @@ impl ShortcutErrors {
             || self.paste_last_error.is_some()
             || self.translation_error.is_some()
     }
+
+    /// Replace the error of one hotkey
+    pub fn set(&mut self, hotkey_type: HotkeyType, error: Option<ShortcutError>) {
+        let slot = match hotkey_type {
+            HotkeyType::Toggle => &mut self.toggle_error,
+            HotkeyType::Hold => &mut self.hold_error,
+            HotkeyType::PasteLast => &mut self.paste_last_error,
+            HotkeyType::Translation => &mut self.translation_error,
+        };
+        *slot = error;
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,8 +114,38 @@ pub struct ShortcutRegistrationResult {
     pub errors: ShortcutErrors,
 }
 
+/// A shortcut that is currently registered with the OS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredShortcut {
+    pub hotkey_type: HotkeyType,
+    /// Accelerator as configured by the user (e.g. "cmd+shift+Space")
+    pub raw: String,
+    /// Accelerator as registered with the backend (e.g. "shift+super+Space")
+    pub normalized: String,
+}
+
 #[derive(Default)]
 pub struct AppState {
     pub shortcut_state: Mutex<ShortcutState>,
     pub shortcut_errors: RwLock<ShortcutErrors>,
+    pub registered_shortcuts: RwLock<Vec<RegisteredShortcut>>,
+    /// Held while shortcuts are (re)registered, so startup registration and a
+    /// settings change can't interleave
+    pub shortcut_registration: Mutex<()>,
+    /// Running foreground window watcher, if any
+    pub window_watcher: Mutex<Option<WindowWatcherHandle>>,
+    /// Compiled profile rules and the resolver that picks a recording's profile
+    pub profile_selection: ProfileSelection,
+    /// Compiled `disabled_apps` patterns
+    pub disabled_apps: RwLock<DisabledApps>,
+    /// Whether the overlay was hidden for the current recording and must be shown on stop
+    pub overlay_hidden_for_recording: Mutex<bool>,
+    /// Pending hide of the overlay after a transcription
+    pub overlay_auto_hide: Mutex<OverlayAutoHide>,
+    /// Correlation ID of the current (or last) recording
+    pub recording_ids: Mutex<RecordingIds>,
+    /// Duration of the current (or last) hotkey recording
+    pub recording_timer: Mutex<RecordingTimer>,
+    /// Last emitted server connection state, for windows opened after it
+    pub connection_state: ConnectionStateCache,
 }
@@ -0,0 +1,161 @@
+use crate::accelerator::{normalize_accelerator_for, AcceleratorError, AcceleratorPlatform};
+
+use AcceleratorPlatform::{Linux, MacOs, Windows};
+
+/// Inputs whose normalized form is the same on every platform
+const PLATFORM_INDEPENDENT_CASES: &[(&str, &str)] = &[
+    ("ctrl+alt+Space", "control+alt+Space"),
+    ("Control+Alt+space", "control+alt+Space"),
+    ("CTRL+ALT+SPACE", "control+alt+Space"),
+    ("Option+Space", "alt+Space"),
+    ("opt+Space", "alt+Space"),
+    ("Cmd+Shift+Space", "shift+super+Space"),
+    ("Command+Shift+Space", "shift+super+Space"),
+    ("Super+Shift+Space", "shift+super+Space"),
+    ("Meta+Shift+Space", "shift+super+Space"),
+    ("Win+Shift+Space", "shift+super+Space"),
+    ("alt+ctrl+shift+a", "shift+control+alt+KeyA"),
+    ("ctrl+alt+KeyT", "control+alt+KeyT"),
+    ("ctrl+alt+keyt", "control+alt+KeyT"),
+    ("ctrl+1", "control+Digit1"),
+    ("ctrl+Digit9", "control+Digit9"),
+    ("ctrl+alt+Backquote", "control+alt+Backquote"),
+    ("ctrl+alt+`", "control+alt+Backquote"),
+    ("ctrl+alt+Period", "control+alt+Period"),
+    ("ctrl+alt+.", "control+alt+Period"),
+    ("ctrl+Return", "control+Enter"),
+    ("ctrl+Esc", "control+Escape"),
+    ("ctrl+up", "control+ArrowUp"),
+    ("F5", "F5"),
+    ("shift+f12", "shift+F12"),
+    (" ctrl + alt + Space ", "control+alt+Space"),
+    ("ctrl+ctrl+Space", "control+Space"),
+    ("ctrl+Numpad0", "control+Numpad0"),
+    ("ctrl+alt+numpad7", "control+alt+Numpad7"),
+    ("ctrl+NumpadAdd", "control+NumpadAdd"),
+    ("shift+NumpadSubtract", "shift+NumpadSubtract"),
+    ("ctrl+NumpadMultiply", "control+NumpadMultiply"),
+    ("ctrl+NumpadDivide", "control+NumpadDivide"),
+    ("alt+NumpadDecimal", "alt+NumpadDecimal"),
+    ("ctrl+numpadenter", "control+NumpadEnter"),
+];
+
+/// Inputs whose normalized form depends on the platform: (input, macOS, Windows, Linux)
+const PLATFORM_DEPENDENT_CASES: &[(&str, &str, &str, &str)] = &[
+    (
+        "CommandOrControl+Space",
+        "super+Space",
+        "control+Space",
+        "control+Space",
+    ),
+    (
+        "CmdOrCtrl+Shift+KeyV",
+        "shift+super+KeyV",
+        "shift+control+KeyV",
+        "shift+control+KeyV",
+    ),
+    (
+        "cmdorcontrol+alt+Space",
+        "alt+super+Space",
+        "control+alt+Space",
+        "control+alt+Space",
+    ),
+    // As recorded by the settings UI's hotkey input
+    (
+        "mod+shift+Numpad1",
+        "shift+super+Numpad1",
+        "shift+control+Numpad1",
+        "shift+control+Numpad1",
+    ),
+];
+
+#[test]
+fn test_normalize_platform_independent_aliases() {
+    for platform in [MacOs, Windows, Linux] {
+        for (input, expected) in PLATFORM_INDEPENDENT_CASES {
+            assert_eq!(
+                normalize_accelerator_for(input, platform).as_deref(),
+                Ok(*expected),
+                "input {input:?} on {platform:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_normalize_platform_dependent_aliases() {
+    for (input, mac, windows, linux) in PLATFORM_DEPENDENT_CASES {
+        for (platform, expected) in [(MacOs, mac), (Windows, windows), (Linux, linux)] {
+            assert_eq!(
+                normalize_accelerator_for(input, platform).as_deref(),
+                Ok(*expected),
+                "input {input:?} on {platform:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_normalize_rejects_invalid_accelerators() {
+    let cases = [
+        ("", AcceleratorError::Empty),
+        ("   ", AcceleratorError::Empty),
+        ("ctrl++Space", AcceleratorError::EmptyToken),
+        ("ctrl+", AcceleratorError::EmptyToken),
+        (
+            "hyper+Space",
+            AcceleratorError::UnknownModifier("hyper".to_string()),
+        ),
+        (
+            "ctrl+Banana",
+            AcceleratorError::InvalidKey("Banana".to_string()),
+        ),
+        ("ctrl+F25", AcceleratorError::InvalidKey("F25".to_string())),
+        (
+            "ctrl+Numpad10",
+            AcceleratorError::InvalidKey("Numpad10".to_string()),
+        ),
+        ("ctrl+shift", AcceleratorError::MissingKey),
+        ("ctrl+A+B", AcceleratorError::MultipleKeys("A".to_string())),
+    ];
+
+    for platform in [MacOs, Windows, Linux] {
+        for (input, expected) in &cases {
+            assert_eq!(
+                normalize_accelerator_for(input, platform).as_ref(),
+                Err(expected),
+                "input {input:?} on {platform:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_normalize_is_idempotent() {
+    for (input, _) in PLATFORM_INDEPENDENT_CASES {
+        let once = normalize_accelerator_for(input, Linux).unwrap();
+        let twice = normalize_accelerator_for(&once, Linux).unwrap();
+        assert_eq!(once, twice);
+    }
+}
+
+/// Invalid hotkeys fall back to these, so they must always normalize
+#[test]
+fn test_default_hotkeys_are_valid_on_every_platform() {
+    use crate::settings::HotkeyType;
+
+    for hotkey_type in [
+        HotkeyType::Toggle,
+        HotkeyType::Hold,
+        HotkeyType::PasteLast,
+        HotkeyType::Translation,
+    ] {
+        let accelerator = hotkey_type.default_hotkey().to_shortcut_string();
+        for platform in [MacOs, Windows, Linux] {
+            assert!(
+                normalize_accelerator_for(&accelerator, platform).is_ok(),
+                "default {accelerator} on {platform:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,819 @@
+use std::cell::RefCell;
+
+use super::window;
+use crate::active_window::app_name::derive_app_name;
+use crate::active_window::linux::{WaylandBackend, WaylandProbe, WAYLAND_BACKENDS};
+use crate::active_window::{
+    atspi, get_active_window_through, linux, macos, set_detection_enabled, windows,
+    with_derived_app_name, ActiveWindowInfo, AddressBarLookup, ForegroundHistory,
+    MockCommandRunner, OwnWindows,
+};
+
+// =============================================================================
+// macOS
+// =============================================================================
+
+#[test]
+fn test_macos_parses_front_window_output() {
+    let info =
+        macos::parse_front_window_output("Slack|||com.tinyspeck.slackmacgap||||||general|||\n")
+            .unwrap();
+    assert_eq!(info.app_name, "Slack");
+    assert_eq!(info.bundle_id.as_deref(), Some("com.tinyspeck.slackmacgap"));
+    assert_eq!(info.window_title, "general");
+    assert_eq!(info.url, None);
+    assert!(!info.is_private);
+}
+
+#[test]
+fn test_macos_parses_browser_url() {
+    let info = macos::parse_front_window_output(
+        "Google Chrome|||com.google.Chrome|||normal|||Docs|||https://docs.rs/serde\n",
+    )
+    .unwrap();
+    assert_eq!(info.app_name, "Google Chrome");
+    assert_eq!(info.window_title, "Docs");
+    assert_eq!(info.url.as_deref(), Some("https://docs.rs/serde"));
+    assert!(!info.is_private);
+}
+
+#[test]
+fn test_macos_private_window_suppresses_url() {
+    let info = macos::parse_front_window_output(
+        "Google Chrome|||com.google.Chrome|||incognito|||Docs|||https://docs.rs/serde",
+    )
+    .unwrap();
+    assert!(info.is_private);
+    assert_eq!(info.url, None);
+    assert_eq!(info.window_title, "Docs");
+}
+
+#[test]
+fn test_macos_unknown_window_mode_is_private() {
+    // Detection failed: assume private
+    let info = macos::parse_front_window_output(
+        "Safari|||com.apple.Safari|||unknown|||Docs|||https://docs.rs/serde",
+    )
+    .unwrap();
+    assert_eq!(info.url, None);
+    assert!(info.is_private);
+}
+
+#[test]
+fn test_macos_safari_windows() {
+    let normal = macos::parse_front_window_output(
+        "Safari|||com.apple.Safari|||normal|||Docs|||https://docs.rs/serde",
+    )
+    .unwrap();
+    assert_eq!(normal.url.as_deref(), Some("https://docs.rs/serde"));
+    assert!(!normal.is_private);
+
+    let private = macos::parse_front_window_output(
+        "Safari|||com.apple.Safari|||private|||Docs|||https://docs.rs/serde",
+    )
+    .unwrap();
+    assert_eq!(private.url, None);
+    assert!(private.is_private);
+}
+
+#[test]
+fn test_macos_window_modes() {
+    assert_eq!(macos::WindowMode::parse(""), macos::WindowMode::NotBrowser);
+    assert_eq!(
+        macos::WindowMode::parse("normal"),
+        macos::WindowMode::Normal
+    );
+    assert_eq!(
+        macos::WindowMode::parse("incognito"),
+        macos::WindowMode::Private
+    );
+    assert_eq!(
+        macos::WindowMode::parse("unknown"),
+        macos::WindowMode::Unknown
+    );
+    assert_eq!(
+        macos::WindowMode::parse("missing value"),
+        macos::WindowMode::Unknown
+    );
+}
+
+#[test]
+fn test_macos_keeps_separator_inside_window_title() {
+    let info = macos::parse_front_window_output("Notes|||com.apple.Notes||||||a|||b|||").unwrap();
+    assert_eq!(info.window_title, "a|||b");
+    assert_eq!(info.url, None);
+
+    let info = macos::parse_front_window_output(
+        "Google Chrome|||com.google.Chrome|||normal|||a|||b|||https://a.b",
+    )
+    .unwrap();
+    assert_eq!(info.window_title, "a|||b");
+    assert_eq!(info.url.as_deref(), Some("https://a.b"));
+}
+
+#[test]
+fn test_macos_empty_window_title() {
+    let info = macos::parse_front_window_output("Finder|||com.apple.finder|||||||||").unwrap();
+    assert_eq!(info.app_name, "Finder");
+    assert_eq!(info.window_title, "");
+    assert_eq!(info.url, None);
+}
+
+#[test]
+fn test_macos_rejects_malformed_output() {
+    assert!(macos::parse_front_window_output("").is_none());
+    assert!(macos::parse_front_window_output("Slack|||com.tinyspeck.slackmacgap").is_none());
+    assert!(
+        macos::parse_front_window_output("Slack|||com.tinyspeck.slackmacgap||||||general")
+            .is_none()
+    );
+}
+
+#[test]
+fn test_macos_fetches_url_in_the_same_script() {
+    let runner = MockCommandRunner::default().respond(
+        "osascript",
+        "System Events",
+        "Google Chrome|||com.google.Chrome|||normal|||Docs|||https://docs.rs",
+    );
+
+    let info = macos::get_active_window(&runner).unwrap();
+    assert_eq!(info.app_name, "Google Chrome");
+    assert_eq!(info.url.as_deref(), Some("https://docs.rs"));
+    assert_eq!(runner.calls().len(), 1);
+}
+
+#[test]
+fn test_macos_script_failure_returns_none() {
+    let runner = MockCommandRunner::default().fail("osascript", "System Events");
+    assert!(macos::get_active_window(&runner).is_none());
+}
+
+// =============================================================================
+// Linux
+// =============================================================================
+
+const HYPRCTL_SAMPLE: &str = r#"{
+    "address": "0x55d0c1a2b3c0",
+    "mapped": true,
+    "workspace": { "id": 2, "name": "2" },
+    "class": "firefox",
+    "title": "Rust Playground — Mozilla Firefox",
+    "pid": 4242
+}"#;
+
+#[test]
+fn test_linux_parses_hyprctl_output() {
+    let info = linux::parse_hyprctl_output(HYPRCTL_SAMPLE).unwrap();
+    assert_eq!(info.app_name, "firefox");
+    assert_eq!(info.window_title, "Rust Playground — Mozilla Firefox");
+}
+
+#[test]
+fn test_linux_hyprctl_missing_fields_default_to_empty() {
+    let info = linux::parse_hyprctl_output("{}").unwrap();
+    assert_eq!(info.app_name, "");
+    assert_eq!(info.window_title, "");
+}
+
+#[test]
+fn test_linux_rejects_invalid_hyprctl_output() {
+    assert!(linux::parse_hyprctl_output("Invalid dispatcher").is_none());
+}
+
+#[test]
+fn test_linux_x11_reads_title_from_xdotool() {
+    let runner = MockCommandRunner::default()
+        .respond("xdotool", "getwindowname", "main.rs - Visual Studio Code\n")
+        .respond("xdotool", "getwindowpid", "");
+
+    let info = linux::get_active_window_x11(&runner, AddressBarLookup::Find).unwrap();
+    assert_eq!(info.window_title, "main.rs - Visual Studio Code");
+    assert_eq!(info.process_name, None);
+}
+
+/// Firefox's accessibility tree with two windows, trimmed to the interesting
+/// parts. The page in the front window has a URL-like entry of its own.
+const ATSPI_FIREFOX_SAMPLE: &str = r#"{
+    "role": "application",
+    "name": "Firefox",
+    "children": [
+        {
+            "role": "frame",
+            "name": "Inbox — Mozilla Firefox",
+            "children": [
+                {"role": "entry", "name": "Search or enter address", "text": "https://mail.example.com/inbox"}
+            ]
+        },
+        {
+            "role": "frame",
+            "name": "Rust Playground — Mozilla Firefox",
+            "active": true,
+            "children": [
+                {
+                    "role": "panel",
+                    "children": [
+                        {
+                            "role": "document web",
+                            "name": "Rust Playground",
+                            "children": [
+                                {"role": "entry", "name": "Share link", "text": "https://play.rust-lang.org/?gist=1"}
+                            ]
+                        }
+                    ]
+                },
+                {
+                    "role": "tool bar",
+                    "name": "Navigation",
+                    "children": [
+                        {"role": "push button", "name": "Back"},
+                        {"role": "entry", "name": "Search or enter address", "text": "https://play.rust-lang.org/"}
+                    ]
+                }
+            ]
+        }
+    ]
+}"#;
+
+fn accessibility_tree(json: &str) -> atspi::AccessibleNode {
+    serde_json::from_str(json).unwrap()
+}
+
+fn browser_window(entry_name: &str, text: &str, active: bool) -> atspi::AccessibleNode {
+    atspi::AccessibleNode {
+        role: "frame".to_string(),
+        active,
+        children: vec![atspi::AccessibleNode {
+            role: "entry".to_string(),
+            name: entry_name.to_string(),
+            text: Some(text.to_string()),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+fn browser(windows: Vec<atspi::AccessibleNode>) -> atspi::AccessibleNode {
+    atspi::AccessibleNode {
+        role: "application".to_string(),
+        children: windows,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_atspi_reads_address_bar_of_active_window() {
+    let tree = accessibility_tree(ATSPI_FIREFOX_SAMPLE);
+    assert_eq!(
+        atspi::find_address_bar_url(&tree).as_deref(),
+        Some("https://play.rust-lang.org/"),
+        "skips the inactive window and entries inside the page"
+    );
+}
+
+#[test]
+fn test_atspi_finds_address_bar_node_to_read_again() {
+    let mut window = browser_window("Location", "https://docs.rs/", true);
+    window.children[0].id = 7;
+    let tree = browser(vec![window]);
+    let (entry, url) = atspi::find_address_bar(&tree).unwrap();
+    assert_eq!(entry.id, 7);
+    assert_eq!(url, "https://docs.rs/");
+}
+
+#[test]
+fn test_atspi_adds_scheme_hidden_by_browser() {
+    let tree = browser(vec![browser_window(
+        "Address and search bar",
+        "github.com/rust-lang/rust",
+        true,
+    )]);
+    assert_eq!(
+        atspi::find_address_bar_url(&tree).as_deref(),
+        Some("https://github.com/rust-lang/rust")
+    );
+}
+
+#[test]
+fn test_atspi_falls_back_to_entry_holding_a_url() {
+    // Localized builds name the address bar differently
+    let tree = browser(vec![browser_window(
+        "Adresse eingeben",
+        "https://example.de/",
+        false,
+    )]);
+    assert_eq!(
+        atspi::find_address_bar_url(&tree).as_deref(),
+        Some("https://example.de/"),
+        "a single window is the front window even when not marked active"
+    );
+}
+
+#[test]
+fn test_atspi_without_url_returns_none() {
+    // A search being typed
+    let typing = browser(vec![browser_window(
+        "Search or enter address",
+        "rust borrow checker",
+        true,
+    )]);
+    assert_eq!(atspi::find_address_bar_url(&typing), None);
+
+    // Several windows and none of them active
+    let ambiguous = browser(vec![
+        browser_window("Search or enter address", "https://a.example/", false),
+        browser_window("Search or enter address", "https://b.example/", false),
+    ]);
+    assert_eq!(atspi::find_address_bar_url(&ambiguous), None);
+
+    assert_eq!(atspi::find_address_bar_url(&browser(Vec::new())), None);
+}
+
+#[test]
+fn test_atspi_address_bar_text() {
+    assert_eq!(
+        atspi::url_from_address_bar("localhost:8080/api").as_deref(),
+        Some("https://localhost:8080/api")
+    );
+    assert_eq!(
+        atspi::url_from_address_bar("file:///home/me/notes.html").as_deref(),
+        Some("file:///home/me/notes.html")
+    );
+    assert_eq!(atspi::url_from_address_bar("about:blank"), None);
+    assert_eq!(atspi::url_from_address_bar("weather"), None);
+    assert_eq!(atspi::url_from_address_bar(""), None);
+}
+
+#[test]
+fn test_atspi_browser_and_private_window_detection() {
+    assert!(atspi::is_browser("firefox"));
+    assert!(atspi::is_browser("Google-chrome"));
+    assert!(!atspi::is_browser("code"));
+
+    assert!(atspi::title_looks_private(
+        "Rust Playground — Mozilla Firefox Private Browsing"
+    ));
+    assert!(atspi::title_looks_private("New Tab - Chromium (Incognito)"));
+    assert!(!atspi::title_looks_private(
+        "Rust Playground — Mozilla Firefox"
+    ));
+}
+
+#[test]
+fn test_linux_wayland_prefers_kdotool() {
+    let runner = MockCommandRunner::default()
+        .respond("kdotool", "getactivewindow", "{6c1a5f4e}\n")
+        .respond("kdotool", "getwindowname {6c1a5f4e}", "Konsole\n");
+
+    let info = linux::get_active_window_wayland(&runner).unwrap();
+    assert_eq!(info.window_title, "Konsole");
+    assert_eq!(info.app_name, "Konsole");
+    assert!(!runner.calls().iter().any(|c| c.starts_with("hyprctl")));
+}
+
+#[test]
+fn test_linux_wayland_falls_back_to_hyprctl() {
+    let runner = MockCommandRunner::default().respond("hyprctl", "activewindow -j", HYPRCTL_SAMPLE);
+
+    let info = linux::get_active_window_wayland(&runner).unwrap();
+    assert_eq!(info.app_name, "firefox");
+    assert_eq!(
+        runner.calls(),
+        vec!["kdotool getactivewindow", "hyprctl activewindow -j"]
+    );
+}
+
+#[test]
+fn test_linux_wayland_without_tools_returns_none() {
+    let runner = MockCommandRunner::default();
+    assert!(linux::get_active_window_wayland(&runner).is_none());
+}
+
+/// Wayland probe with canned availability and results that records what it queried
+#[derive(Default)]
+struct MockWaylandProbe {
+    available: Vec<WaylandBackend>,
+    windows: Vec<(WaylandBackend, &'static str)>,
+    queried: RefCell<Vec<WaylandBackend>>,
+}
+
+impl WaylandProbe for MockWaylandProbe {
+    fn is_available(&self, backend: WaylandBackend) -> bool {
+        self.available.contains(&backend)
+    }
+
+    fn active_window(&self, backend: WaylandBackend) -> Option<ActiveWindowInfo> {
+        self.queried.borrow_mut().push(backend);
+        self.windows
+            .iter()
+            .find(|(b, _)| *b == backend)
+            .map(|(_, app_name)| ActiveWindowInfo {
+                app_name: (*app_name).to_string(),
+                ..Default::default()
+            })
+    }
+}
+
+#[test]
+fn test_wayland_tries_compositor_tools_before_dbus() {
+    assert_eq!(
+        WAYLAND_BACKENDS,
+        [
+            WaylandBackend::Kdotool,
+            WaylandBackend::Hyprctl,
+            WaylandBackend::DBusIntrospect
+        ]
+    );
+
+    let probe = MockWaylandProbe {
+        available: WAYLAND_BACKENDS.to_vec(),
+        windows: vec![
+            (WaylandBackend::Hyprctl, "firefox"),
+            (WaylandBackend::DBusIntrospect, "Firefox"),
+        ],
+        ..Default::default()
+    };
+
+    let (backend, info) = linux::select_wayland_backend(&WAYLAND_BACKENDS, &probe).unwrap();
+    assert_eq!(backend, WaylandBackend::Hyprctl);
+    assert_eq!(info.app_name, "firefox");
+    assert_eq!(
+        *probe.queried.borrow(),
+        vec![WaylandBackend::Kdotool, WaylandBackend::Hyprctl]
+    );
+}
+
+#[test]
+fn test_wayland_falls_back_to_dbus() {
+    let probe = MockWaylandProbe {
+        available: WAYLAND_BACKENDS.to_vec(),
+        windows: vec![(WaylandBackend::DBusIntrospect, "org.gnome.Nautilus")],
+        ..Default::default()
+    };
+
+    let (backend, _) = linux::select_wayland_backend(&WAYLAND_BACKENDS, &probe).unwrap();
+    assert_eq!(backend, WaylandBackend::DBusIntrospect);
+    assert_eq!(probe.queried.borrow().len(), 3);
+}
+
+#[test]
+fn test_wayland_skips_unavailable_backends() {
+    let probe = MockWaylandProbe {
+        available: vec![WaylandBackend::Hyprctl],
+        windows: vec![(WaylandBackend::Kdotool, "Konsole")],
+        ..Default::default()
+    };
+
+    assert!(linux::select_wayland_backend(&WAYLAND_BACKENDS, &probe).is_none());
+    assert_eq!(*probe.queried.borrow(), vec![WaylandBackend::Hyprctl]);
+}
+
+#[test]
+fn test_wayland_without_available_backends_returns_none() {
+    let probe = MockWaylandProbe::default();
+    assert!(linux::select_wayland_backend(&WAYLAND_BACKENDS, &probe).is_none());
+    assert!(probe.queried.borrow().is_empty());
+}
+
+#[test]
+fn test_linux_introspection_prefers_wm_class() {
+    let info = linux::window_from_introspection(
+        "org.gnome.Nautilus.desktop",
+        "org.gnome.Nautilus",
+        "Downloads".to_string(),
+    );
+    assert_eq!(info.app_name, "org.gnome.Nautilus");
+    assert_eq!(info.window_title, "Downloads");
+
+    let info = linux::window_from_introspection("foot.desktop", "", String::new());
+    assert_eq!(info.app_name, "foot");
+}
+
+// =============================================================================
+// Windows
+// =============================================================================
+
+#[test]
+fn test_windows_decodes_title_up_to_length() {
+    let mut buffer = [0u16; 16];
+    let title: Vec<u16> = "Inbox".encode_utf16().collect();
+    buffer[..title.len()].copy_from_slice(&title);
+
+    assert_eq!(windows::decode_window_title(&buffer, 5), "Inbox");
+    assert_eq!(windows::decode_window_title(&buffer, 2), "In");
+}
+
+#[test]
+fn test_windows_title_length_is_clamped() {
+    let buffer: Vec<u16> = "Inbox".encode_utf16().collect();
+    assert_eq!(windows::decode_window_title(&buffer, 100), "Inbox");
+    assert_eq!(windows::decode_window_title(&buffer, -1), "");
+}
+
+#[test]
+fn test_windows_title_keeps_surrogate_pairs_whole() {
+    // "Re: 😀" - the emoji is a surrogate pair at the end of the buffer
+    let buffer: Vec<u16> = "Re: 😀".encode_utf16().collect();
+    assert_eq!(buffer.len(), 6);
+
+    assert_eq!(windows::decode_window_title(&buffer, 6), "Re: 😀");
+    // Cut between the high and low surrogate: drop the half pair, no U+FFFD
+    assert_eq!(windows::decode_window_title(&buffer, 5), "Re: ");
+}
+
+#[test]
+fn test_windows_title_with_zero_length_is_empty() {
+    assert_eq!(windows::decode_window_title(&[0u16; 4], 0), "");
+    assert_eq!(windows::decode_window_title(&[], 0), "");
+}
+
+#[test]
+fn test_windows_app_name_from_exe_path() {
+    assert_eq!(
+        windows::app_name_from_exe_path(r"C:\Program Files\Microsoft VS Code\Code.exe"),
+        "Code"
+    );
+    assert_eq!(windows::app_name_from_exe_path("notepad.exe"), "notepad");
+    assert_eq!(windows::app_name_from_exe_path("explorer"), "explorer");
+}
+
+/// Encode a version info node the way resource compilers lay it out
+fn version_node(key: &str, value: &[u8], is_text: bool, children: &[Vec<u8>]) -> Vec<u8> {
+    fn pad(data: &mut Vec<u8>) {
+        data.resize(data.len().next_multiple_of(4), 0);
+    }
+    let mut data = vec![0u8; 6];
+    data.extend(key.encode_utf16().chain([0]).flat_map(u16::to_le_bytes));
+    pad(&mut data);
+    data.extend(value);
+    for child in children {
+        pad(&mut data);
+        data.extend(child);
+    }
+    let length = u16::try_from(data.len()).unwrap();
+    let value_length = if is_text {
+        value.len() / 2
+    } else {
+        value.len()
+    };
+    data[0..2].copy_from_slice(&length.to_le_bytes());
+    data[2..4].copy_from_slice(&u16::try_from(value_length).unwrap().to_le_bytes());
+    data[4..6].copy_from_slice(&u16::from(is_text).to_le_bytes());
+    data
+}
+
+fn version_string(key: &str, text: &str) -> Vec<u8> {
+    let value: Vec<u8> = text
+        .encode_utf16()
+        .chain([0])
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    version_node(key, &value, true, &[])
+}
+
+/// A `VS_VERSIONINFO` block with one string table per `(language, strings)`
+fn version_info(tables: &[(&str, &[(&str, &str)])]) -> Vec<u8> {
+    let tables: Vec<Vec<u8>> = tables
+        .iter()
+        .map(|(language, strings)| {
+            let strings: Vec<Vec<u8>> = strings
+                .iter()
+                .map(|(key, text)| version_string(key, text))
+                .collect();
+            version_node(language, &[], true, &strings)
+        })
+        .collect();
+    let translation = version_node("Translation", &[0x09, 0x04, 0xB0, 0x04], false, &[]);
+    version_node(
+        "VS_VERSION_INFO",
+        &[0u8; 52], // VS_FIXEDFILEINFO
+        false,
+        &[
+            version_node("StringFileInfo", &[], true, &tables),
+            version_node("VarFileInfo", &[], true, &[translation]),
+        ],
+    )
+}
+
+#[test]
+fn test_windows_reads_file_description() {
+    let data = version_info(&[(
+        "040904B0",
+        &[
+            ("CompanyName", "Microsoft Corporation"),
+            ("FileDescription", "Microsoft Edge"),
+            ("ProductName", "Microsoft Edge"),
+        ],
+    )]);
+    assert_eq!(
+        windows::file_description_from_version_info(&data).as_deref(),
+        Some("Microsoft Edge")
+    );
+}
+
+#[test]
+fn test_windows_file_description_prefers_english_table() {
+    let data = version_info(&[
+        ("040704B0", &[("FileDescription", "Editor")]),
+        ("040904B0", &[("FileDescription", "Notepad")]),
+    ]);
+    assert_eq!(
+        windows::file_description_from_version_info(&data).as_deref(),
+        Some("Notepad")
+    );
+
+    let data = version_info(&[("040704B0", &[("FileDescription", "Editor")])]);
+    assert_eq!(
+        windows::file_description_from_version_info(&data).as_deref(),
+        Some("Editor")
+    );
+}
+
+#[test]
+fn test_windows_missing_file_description() {
+    let blank = version_info(&[("040904B0", &[("FileDescription", "  ")])]);
+    assert_eq!(windows::file_description_from_version_info(&blank), None);
+
+    let without = version_info(&[("040904B0", &[("ProductName", "Tool")])]);
+    assert_eq!(windows::file_description_from_version_info(&without), None);
+}
+
+#[test]
+fn test_windows_malformed_version_info() {
+    let data = version_info(&[("040904B0", &[("FileDescription", "Microsoft Edge")])]);
+    for len in [0, 5, 40, data.len() / 2] {
+        assert_eq!(
+            windows::file_description_from_version_info(&data[..len]),
+            None
+        );
+    }
+}
+
+// =============================================================================
+// Turning detection off
+// =============================================================================
+
+#[test]
+fn test_disabled_detection_runs_no_commands() {
+    let runner = MockCommandRunner::default()
+        .respond("xdotool", "getwindowname", "main.rs - Visual Studio Code\n")
+        .respond(
+            "osascript",
+            "",
+            "Code|||com.microsoft.VSCode||||||main.rs|||",
+        )
+        .respond("kdotool", "", "Konsole\n")
+        .respond("hyprctl", "", HYPRCTL_SAMPLE);
+
+    set_detection_enabled(false);
+    let info = get_active_window_through(&runner);
+    set_detection_enabled(true);
+
+    assert!(info.is_none());
+    assert!(runner.calls().is_empty());
+}
+
+// =============================================================================
+// App names from titles
+// =============================================================================
+
+/// A window as reported by a backend that only knows the title (kdotool)
+fn title_only(title: &str) -> ActiveWindowInfo {
+    ActiveWindowInfo {
+        window_title: title.to_string(),
+        app_name: title.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_app_name_from_title_suffix() {
+    for (title, expected) in [
+        ("Rust Playground — Mozilla Firefox", "Mozilla Firefox"),
+        ("Inbox – Thunderbird", "Thunderbird"),
+        (
+            "main.rs - tambourine - Visual Studio Code",
+            "Visual Studio Code",
+        ),
+        ("~/src/tambourine : zsh — Konsole", "Konsole"),
+        ("● lib.rs - Visual Studio Code", "Visual Studio Code"),
+    ] {
+        assert_eq!(derive_app_name(&title_only(title)), expected, "{title}");
+    }
+}
+
+#[test]
+fn test_app_name_from_title_prefix_when_suffix_is_a_document() {
+    for (title, expected) in [
+        ("GIMP - untitled.xcf", "GIMP"),
+        ("Terminal - vim notes.md", "Terminal"),
+        ("Files | /home/me/Downloads", "Files"),
+    ] {
+        assert_eq!(derive_app_name(&title_only(title)), expected, "{title}");
+    }
+}
+
+#[test]
+fn test_app_name_from_title_without_separator() {
+    assert_eq!(derive_app_name(&title_only("Konsole")), "Konsole");
+    assert_eq!(
+        derive_app_name(&title_only("  Spotify Premium ")),
+        "Spotify Premium"
+    );
+    // Dashes inside words aren't separators
+    assert_eq!(
+        derive_app_name(&title_only("gnome-terminal")),
+        "gnome-terminal"
+    );
+    assert_eq!(derive_app_name(&title_only("")), "");
+}
+
+#[test]
+fn test_app_name_falls_back_to_title_when_no_part_fits() {
+    let title = "Quarterly planning notes for the whole team - ~/docs/plan.odt";
+    assert_eq!(derive_app_name(&title_only(title)), title);
+}
+
+#[test]
+fn test_app_name_from_empty_class_uses_title() {
+    let info = ActiveWindowInfo {
+        window_title: "Rust Playground — Mozilla Firefox".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(derive_app_name(&info), "Mozilla Firefox");
+}
+
+#[test]
+fn test_detected_app_name_is_kept() {
+    let info = ActiveWindowInfo {
+        window_title: "Rust Playground — Mozilla Firefox".to_string(),
+        app_name: "firefox".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(derive_app_name(&info), "firefox");
+}
+
+#[test]
+fn test_derived_app_name_keeps_raw_title() {
+    let info = with_derived_app_name(title_only("notes.txt - Kate"));
+    assert_eq!(info.app_name, "Kate");
+    assert_eq!(info.window_title, "notes.txt - Kate");
+}
+
+// =============================================================================
+// Own windows
+// =============================================================================
+
+fn own_windows() -> OwnWindows {
+    OwnWindows {
+        bundle_id: Some("com.kingstonkuan.tambourine".to_string()),
+        process_name: Some("tambourine".to_string()),
+        titles: vec!["Tambourine".to_string(), "Voice Overlay".to_string()],
+    }
+}
+
+#[test]
+fn test_own_windows_recognized_by_bundle_process_or_title() {
+    let own = own_windows();
+
+    let mut by_bundle = window("Tambourine", "");
+    by_bundle.bundle_id = Some("com.kingstonkuan.tambourine".to_string());
+    assert!(own.contains(&by_bundle));
+
+    let mut by_process = window("Tambourine", "");
+    by_process.process_name = Some(r"C:\Program Files\Tambourine\Tambourine.exe".to_string());
+    assert!(own.contains(&by_process));
+
+    assert!(own.contains(&window("", "Voice Overlay")));
+
+    let mut other = window("Slack", "general");
+    other.bundle_id = Some("com.tinyspeck.slackmacgap".to_string());
+    other.process_name = Some("slack".to_string());
+    assert!(!own.contains(&other));
+}
+
+#[test]
+fn test_focus_on_own_window_returns_previous_external_window() {
+    let mut history = ForegroundHistory::new(own_windows());
+
+    let slack = window("Slack", "general");
+    let code = window("Code", "main.rs");
+    assert_eq!(history.resolve(Some(slack)).unwrap().app_name, "Slack");
+    assert_eq!(history.resolve(Some(code)).unwrap().app_name, "Code");
+
+    // The user clicks the overlay, then the settings window
+    let info = history.resolve(Some(window("", "Voice Overlay"))).unwrap();
+    assert_eq!(info.app_name, "Code");
+    assert_eq!(info.window_title, "main.rs");
+    let info = history
+        .resolve(Some(window("Tambourine", "Tambourine")))
+        .unwrap();
+    assert_eq!(info.app_name, "Code");
+}
+
+#[test]
+fn test_own_window_without_previous_external_window() {
+    let mut history = ForegroundHistory::new(own_windows());
+    assert!(history.resolve(Some(window("", "Voice Overlay"))).is_none());
+    assert!(history.resolve(None).is_none());
+}
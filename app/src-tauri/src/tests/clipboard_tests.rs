@@ -0,0 +1,81 @@
+use crate::clipboard::{ClipboardBackend, ClipboardGuard};
+
+/// In-memory clipboard. `None` stands for non-text content such as an image.
+#[derive(Default)]
+struct MockClipboard {
+    content: Option<String>,
+    writes: Vec<String>,
+}
+
+impl MockClipboard {
+    fn with_text(text: &str) -> Self {
+        Self {
+            content: Some(text.to_string()),
+            writes: Vec::new(),
+        }
+    }
+}
+
+impl ClipboardBackend for MockClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.content.clone()
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        self.content = Some(text.to_string());
+        self.writes.push(text.to_string());
+        Ok(())
+    }
+}
+
+/// Simulates clipboard-based insertion whose paste step fails
+fn paste_that_fails(clipboard: &mut MockClipboard, text: &str) -> Result<(), String> {
+    let mut guard = ClipboardGuard::new(clipboard);
+    guard.set_text(text)?;
+    Err("Failed to simulate paste keystroke".to_string())
+}
+
+#[test]
+fn test_failed_paste_restores_previous_text() {
+    let mut clipboard = MockClipboard::with_text("user's text");
+
+    let result = paste_that_fails(&mut clipboard, "transcription");
+
+    assert!(result.is_err());
+    assert_eq!(clipboard.content.as_deref(), Some("user's text"));
+    assert_eq!(clipboard.writes, vec!["transcription", "user's text"]);
+}
+
+#[test]
+fn test_commit_keeps_current_content() {
+    let mut clipboard = MockClipboard::with_text("user's text");
+
+    let mut guard = ClipboardGuard::new(&mut clipboard);
+    guard.set_text("transcription").unwrap();
+    guard.commit();
+
+    assert_eq!(clipboard.content.as_deref(), Some("transcription"));
+    assert_eq!(clipboard.writes, vec!["transcription"]);
+}
+
+#[test]
+fn test_drop_without_commit_restores() {
+    let mut clipboard = MockClipboard::with_text("user's text");
+    {
+        let mut guard = ClipboardGuard::new(&mut clipboard);
+        guard.set_text("transcription").unwrap();
+        assert_eq!(guard.get_text().as_deref(), Some("transcription"));
+    }
+    assert_eq!(clipboard.content.as_deref(), Some("user's text"));
+}
+
+#[test]
+fn test_non_text_clipboard_is_not_overwritten_on_restore() {
+    let mut clipboard = MockClipboard::default();
+
+    let result = paste_that_fails(&mut clipboard, "transcription");
+
+    assert!(result.is_err());
+    // Nothing was snapshotted, so no empty string is written back
+    assert_eq!(clipboard.writes, vec!["transcription"]);
+}
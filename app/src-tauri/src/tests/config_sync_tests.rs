@@ -0,0 +1,1229 @@
+use serde_json::json;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::Duration;
+
+use tauri_plugin_http::reqwest::{Client, Response};
+
+use crate::config_sync::{
+    endpoint_url, normalize_api_base_path, retry_sync, sync_error_response, BulkSettingResult,
+    BulkSyncOutcome, ConfigSnapshot, ConfigSyncState, ConfigTransport, DryRunRequest,
+    IdempotencyKey, RetryPolicy, SyncError, SyncMetrics, SyncStatus, TransportFuture,
+    TransportResponse, CLIENT_UUID_HEADER, IDEMPOTENCY_KEY_HEADER,
+};
+use crate::events::ConfigSetting;
+use crate::settings::{AppSettings, CleanupPromptSections, OperatingMode};
+
+fn response(status: u16, body: &str) -> Response {
+    Response::from(
+        tauri::http::Response::builder()
+            .status(status)
+            .body(body.to_string())
+            .unwrap(),
+    )
+}
+
+fn full_snapshot() -> ConfigSnapshot {
+    ConfigSnapshot {
+        prompt_sections: Some(CleanupPromptSections::default()),
+        stt_timeout_seconds: Some(1.5),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_snapshot_from_default_settings_is_empty() {
+    let snapshot = ConfigSnapshot::from_settings(&AppSettings::default());
+    assert!(snapshot.is_empty());
+    assert_eq!(serde_json::to_value(&snapshot).unwrap(), json!({}));
+}
+
+#[test]
+fn test_snapshot_serializes_every_setting() {
+    let json = serde_json::to_value(full_snapshot()).unwrap();
+    assert_eq!(json["stt_timeout_seconds"], json!(1.5));
+    assert!(json["prompt_sections"]["main"].is_object());
+}
+
+#[test]
+fn test_bulk_not_found_falls_back_to_individual_sync() {
+    assert_eq!(
+        BulkSyncOutcome::from_response(404, "Not Found"),
+        Ok(BulkSyncOutcome::Unsupported)
+    );
+}
+
+#[test]
+fn test_bulk_server_error_is_not_a_fallback() {
+    assert_eq!(
+        BulkSyncOutcome::from_response(500, "boom"),
+        Err(SyncError::Http {
+            status: 500,
+            body: "boom".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_bulk_invalid_body_is_an_error() {
+    let result = BulkSyncOutcome::from_response(200, "not json");
+    assert!(matches!(result, Err(SyncError::Serialization(_))));
+}
+
+#[test]
+fn test_bulk_mixed_response_emits_updated_and_error_events() {
+    let body = r#"{"results": [
+        {"setting": "prompt-sections", "accepted": true},
+        {"setting": "stt-timeout", "accepted": false, "error": "Timeout out of range"}
+    ]}"#;
+    let Ok(BulkSyncOutcome::Applied(results)) = BulkSyncOutcome::from_response(200, body) else {
+        panic!("expected applied outcome");
+    };
+
+    let responses: Vec<_> = full_snapshot()
+        .responses_for(&results)
+        .iter()
+        .map(|r| serde_json::to_value(r).unwrap())
+        .collect();
+
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["type"], "config-updated");
+    assert_eq!(responses[0]["setting"], "prompt-sections");
+    assert!(responses[0]["value"]["main"].is_object());
+    assert_eq!(
+        responses[1],
+        json!({
+            "type": "config-error",
+            "setting": "stt-timeout",
+            "error": "Timeout out of range",
+        })
+    );
+}
+
+#[test]
+fn test_bulk_response_missing_setting_is_reported_as_error() {
+    let results = vec![BulkSettingResult {
+        setting: "stt-timeout".to_string(),
+        accepted: true,
+        error: None,
+    }];
+
+    let responses: Vec<_> = full_snapshot()
+        .responses_for(&results)
+        .iter()
+        .map(|r| serde_json::to_value(r).unwrap())
+        .collect();
+
+    assert_eq!(responses[0]["type"], "config-error");
+    assert_eq!(responses[0]["setting"], "prompt-sections");
+    assert_eq!(
+        responses[1],
+        json!({ "type": "config-updated", "setting": "stt-timeout", "value": 1.5 })
+    );
+}
+
+#[test]
+fn test_error_responses_cover_every_synced_setting() {
+    let snapshot = ConfigSnapshot {
+        stt_timeout_seconds: Some(2.0),
+        ..Default::default()
+    };
+    let responses: Vec<_> = snapshot
+        .error_responses("connection refused")
+        .iter()
+        .map(|r| serde_json::to_value(r).unwrap())
+        .collect();
+    assert_eq!(
+        responses,
+        vec![json!({
+            "type": "config-error",
+            "setting": "stt-timeout",
+            "error": "connection refused",
+        })]
+    );
+}
+
+#[test]
+fn test_sync_error_maps_auth_statuses_to_unauthorized() {
+    assert_eq!(SyncError::from_status(401, "nope"), SyncError::Unauthorized);
+    assert_eq!(SyncError::from_status(403, ""), SyncError::Unauthorized);
+    assert_eq!(
+        SyncError::from_status(422, "bad timeout"),
+        SyncError::Http {
+            status: 422,
+            body: "bad timeout".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_sync_error_display() {
+    assert_eq!(
+        SyncError::NotConnected.to_string(),
+        "Not connected to a server"
+    );
+    assert_eq!(
+        SyncError::from_status(500, "").to_string(),
+        "Server returned HTTP 500"
+    );
+    assert_eq!(
+        SyncError::from_status(500, "boom").to_string(),
+        "Server returned HTTP 500: boom"
+    );
+}
+
+#[test]
+fn test_reqwest_status_error_maps_to_status_variant() {
+    let unauthorized = response(401, "").error_for_status().unwrap_err();
+    assert_eq!(SyncError::from(unauthorized), SyncError::Unauthorized);
+
+    let server_error = response(503, "").error_for_status().unwrap_err();
+    assert!(matches!(
+        SyncError::from(server_error),
+        SyncError::Http { status: 503, .. }
+    ));
+}
+
+#[test]
+fn test_reqwest_request_error_maps_to_network() {
+    let error = Client::new().get("not a url").build().unwrap_err();
+    assert!(matches!(SyncError::from(error), SyncError::Network(_)));
+}
+
+#[test]
+fn test_reqwest_decode_error_maps_to_serialization() {
+    let error =
+        tauri::async_runtime::block_on(response(200, "not json").json::<serde_json::Value>())
+            .unwrap_err();
+    assert!(matches!(
+        SyncError::from(error),
+        SyncError::Serialization(_)
+    ));
+}
+
+// =============================================================================
+// Retries and idempotency keys
+// =============================================================================
+
+fn no_delay(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        delay: Duration::ZERO,
+    }
+}
+
+/// Run one sync intent whose attempts fail with `failures` before succeeding,
+/// returning the keys sent with each attempt
+fn keys_sent(
+    policy: RetryPolicy,
+    failures: Vec<SyncError>,
+) -> (Result<(), SyncError>, Vec<String>) {
+    let sent = RefCell::new(Vec::new());
+    let failures = RefCell::new(failures.into_iter());
+    let key = IdempotencyKey::new();
+    let (result, attempts) = tauri::async_runtime::block_on(retry_sync(policy, &key, |key| {
+        sent.borrow_mut().push(key.as_str().to_string());
+        let next = failures.borrow_mut().next();
+        async move { next.map_or(Ok(()), Err) }
+    }));
+    let sent = sent.into_inner();
+    assert_eq!(attempts as usize, sent.len());
+    (result, sent)
+}
+
+#[test]
+fn test_retries_reuse_the_intent_key() {
+    let (result, keys) = keys_sent(
+        no_delay(3),
+        vec![
+            SyncError::Network("timed out".to_string()),
+            SyncError::from_status(503, ""),
+        ],
+    );
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(keys.len(), 3);
+    assert!(keys.iter().all(|k| k == &keys[0]));
+}
+
+#[test]
+fn test_distinct_intents_get_distinct_keys() {
+    let (_, first) = keys_sent(no_delay(3), Vec::new());
+    let (_, second) = keys_sent(no_delay(3), Vec::new());
+    assert_ne!(first[0], second[0]);
+}
+
+#[test]
+fn test_non_retryable_errors_are_not_retried() {
+    let (result, keys) = keys_sent(no_delay(3), vec![SyncError::Unauthorized]);
+    assert_eq!(result, Err(SyncError::Unauthorized));
+    assert_eq!(keys.len(), 1);
+
+    let (_, keys) = keys_sent(no_delay(3), vec![SyncError::from_status(422, "bad")]);
+    assert_eq!(keys.len(), 1);
+}
+
+#[test]
+fn test_retries_stop_at_max_attempts() {
+    let network = || SyncError::Network("refused".to_string());
+    let (result, keys) = keys_sent(no_delay(2), vec![network(), network(), network()]);
+    assert_eq!(result, Err(network()));
+    assert_eq!(keys.len(), 2);
+}
+
+#[test]
+fn test_metrics_record_key_of_last_failure() {
+    let mut metrics = SyncMetrics::default();
+    let ok_key = IdempotencyKey::new();
+    metrics.record(&ok_key, 2, &Ok(()));
+    assert_eq!(metrics.last_error, None);
+
+    let failed_key = IdempotencyKey::new();
+    metrics.record(
+        &failed_key,
+        3,
+        &Err(SyncError::Network("refused".to_string())),
+    );
+
+    assert_eq!(metrics.succeeded, 1);
+    assert_eq!(metrics.failed, 1);
+    assert_eq!(metrics.retries, 3);
+    let last_error = metrics.last_error.unwrap();
+    assert_eq!(last_error.idempotency_key, failed_key.as_str());
+    assert_eq!(last_error.attempts, 3);
+    assert_eq!(last_error.error, "Network error: refused");
+}
+
+// =============================================================================
+// Syncing through a mock transport
+// =============================================================================
+
+#[derive(Debug, Clone)]
+struct RecordedRequest {
+    method: &'static str,
+    url: String,
+    headers: Vec<(&'static str, String)>,
+    body: serde_json::Value,
+}
+
+impl RecordedRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Records requests and replies with scripted responses (200 once the script runs out)
+#[derive(Default)]
+struct MockTransport {
+    responses: Mutex<VecDeque<Result<TransportResponse, SyncError>>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl MockTransport {
+    fn replying(responses: Vec<Result<TransportResponse, SyncError>>) -> Arc<Self> {
+        Arc::new(Self {
+            responses: Mutex::new(responses.into()),
+            requests: Mutex::default(),
+        })
+    }
+
+    fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn record(
+        &self,
+        method: &'static str,
+        url: String,
+        headers: Vec<(&'static str, String)>,
+        body: serde_json::Value,
+    ) -> TransportFuture<'_> {
+        self.requests.lock().unwrap().push(RecordedRequest {
+            method,
+            url,
+            headers,
+            body,
+        });
+        let response = self
+            .responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| Ok(reply(200, "")));
+        Box::pin(async move { response })
+    }
+}
+
+impl ConfigTransport for MockTransport {
+    fn put_json(
+        &self,
+        url: String,
+        headers: Vec<(&'static str, String)>,
+        body: serde_json::Value,
+    ) -> TransportFuture<'_> {
+        self.record("PUT", url, headers, body)
+    }
+
+    fn post_json(
+        &self,
+        url: String,
+        headers: Vec<(&'static str, String)>,
+        body: serde_json::Value,
+    ) -> TransportFuture<'_> {
+        self.record("POST", url, headers, body)
+    }
+}
+
+fn reply(status: u16, body: &str) -> TransportResponse {
+    TransportResponse {
+        status,
+        body: body.to_string(),
+    }
+}
+
+fn connected(transport: &Arc<MockTransport>) -> ConfigSyncState {
+    let mut sync = ConfigSyncState::with_transport(transport.clone());
+    sync.set_connected("http://127.0.0.1:8765".to_string(), "client-1".to_string());
+    sync
+}
+
+#[test]
+fn test_disconnected_sync_sends_nothing() {
+    let transport = MockTransport::replying(Vec::new());
+    let sync = ConfigSyncState::with_transport(transport.clone());
+
+    let result = tauri::async_runtime::block_on(sync.sync_stt_timeout(1.5));
+    assert_eq!(result, Err(SyncError::NotConnected));
+    let result = tauri::async_runtime::block_on(sync.sync_all(&full_snapshot()));
+    assert_eq!(result, Err(SyncError::NotConnected));
+    assert!(transport.requests().is_empty());
+}
+
+#[test]
+fn test_connected_sync_puts_setting_with_client_headers() {
+    let transport = MockTransport::replying(Vec::new());
+    let sync = connected(&transport);
+
+    tauri::async_runtime::block_on(sync.sync_stt_timeout(1.5)).unwrap();
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "PUT");
+    assert_eq!(
+        requests[0].url,
+        "http://127.0.0.1:8765/api/config/stt-timeout"
+    );
+    assert_eq!(requests[0].body, json!({ "timeout_seconds": 1.5 }));
+    assert_eq!(requests[0].header("X-Client-UUID"), Some("client-1"));
+    assert!(requests[0].header(IDEMPOTENCY_KEY_HEADER).is_some());
+    assert_eq!(sync.metrics().succeeded, 1);
+}
+
+#[test]
+fn test_connected_sync_reports_rejection_without_retrying() {
+    let transport = MockTransport::replying(vec![Ok(reply(422, "bad sections"))]);
+    let sync = connected(&transport);
+
+    let result = tauri::async_runtime::block_on(
+        sync.sync_prompt_sections(&CleanupPromptSections::default()),
+    );
+
+    assert_eq!(
+        result,
+        Err(SyncError::Http {
+            status: 422,
+            body: "bad sections".to_string()
+        })
+    );
+    assert_eq!(transport.requests().len(), 1);
+    assert_eq!(
+        transport.requests()[0].url,
+        "http://127.0.0.1:8765/api/config/prompts"
+    );
+    let last_error = sync.metrics().last_error.unwrap();
+    assert_eq!(
+        Some(last_error.idempotency_key.as_str()),
+        transport.requests()[0].header(IDEMPOTENCY_KEY_HEADER)
+    );
+}
+
+#[test]
+fn test_profile_prompts_on_older_server_are_unsupported() {
+    let transport = MockTransport::replying(vec![Ok(reply(404, "Not Found"))]);
+    let sync = connected(&transport);
+
+    let result =
+        tauri::async_runtime::block_on(sync.sync_profile_prompt_overrides(&HashMap::new()));
+
+    assert_eq!(result, Err(SyncError::Unsupported));
+    assert_eq!(
+        transport.requests()[0].url,
+        "http://127.0.0.1:8765/api/config/prompts/profiles"
+    );
+    assert!(sync_error_response(ConfigSetting::ProfilePrompts, &SyncError::Unsupported).is_none());
+    assert_eq!(
+        sync.sync_status().get(&ConfigSetting::ProfilePrompts),
+        Some(&SyncStatus::Failed {
+            error: "Server doesn't support this setting".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_connected_sync_retries_with_the_same_key() {
+    let transport = MockTransport::replying(vec![Err(SyncError::Network("timed out".to_string()))]);
+    let sync = connected(&transport);
+
+    tauri::async_runtime::block_on(sync.sync_stt_timeout(2.0)).unwrap();
+
+    let keys: Vec<_> = transport
+        .requests()
+        .iter()
+        .map(|r| r.header(IDEMPOTENCY_KEY_HEADER).unwrap().to_string())
+        .collect();
+    assert_eq!(keys.len(), 2);
+    assert_eq!(keys[0], keys[1]);
+    assert_eq!(sync.metrics().retries, 1);
+}
+
+#[test]
+fn test_connected_bulk_sync_posts_snapshot() {
+    let transport = MockTransport::replying(vec![Ok(reply(
+        200,
+        r#"{"results": [{"setting": "stt-timeout", "accepted": true}]}"#,
+    ))]);
+    let sync = connected(&transport);
+
+    let outcome = tauri::async_runtime::block_on(sync.sync_all(&full_snapshot())).unwrap();
+
+    assert_eq!(
+        outcome,
+        BulkSyncOutcome::Applied(vec![BulkSettingResult {
+            setting: "stt-timeout".to_string(),
+            accepted: true,
+            error: None,
+        }])
+    );
+    let requests = transport.requests();
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].url, "http://127.0.0.1:8765/api/config/bulk");
+    assert_eq!(requests[0].body["stt_timeout_seconds"], json!(1.5));
+}
+
+#[test]
+fn test_connected_bulk_sync_on_older_server_is_unsupported() {
+    let transport = MockTransport::replying(vec![Ok(reply(404, "Not Found"))]);
+    let sync = connected(&transport);
+
+    let outcome = tauri::async_runtime::block_on(sync.sync_all(&full_snapshot()));
+    assert_eq!(outcome, Ok(BulkSyncOutcome::Unsupported));
+}
+
+#[test]
+fn test_local_only_sync_never_calls_transport() {
+    let transport = MockTransport::replying(Vec::new());
+    let mut sync = ConfigSyncState::with_transport(transport.clone());
+    sync.set_mode(OperatingMode::LocalOnly);
+    sync.set_connected("http://127.0.0.1:8765".to_string(), "client-1".to_string());
+    assert!(!sync.is_connected());
+
+    let sections = CleanupPromptSections::default();
+    assert_eq!(
+        tauri::async_runtime::block_on(sync.sync_prompt_sections(&sections)),
+        Ok(())
+    );
+    assert_eq!(
+        tauri::async_runtime::block_on(sync.sync_stt_timeout(1.5)),
+        Ok(())
+    );
+    assert_eq!(
+        tauri::async_runtime::block_on(sync.sync_all(&full_snapshot())),
+        Ok(BulkSyncOutcome::Skipped)
+    );
+    assert!(transport.requests().is_empty());
+    assert_eq!(sync.metrics(), SyncMetrics::default());
+}
+
+#[test]
+fn test_switching_to_local_only_drops_connection() {
+    let transport = MockTransport::replying(Vec::new());
+    let mut sync = connected(&transport);
+    assert!(sync.is_connected());
+
+    sync.set_mode(OperatingMode::LocalOnly);
+    assert!(!sync.is_connected());
+    tauri::async_runtime::block_on(sync.sync_stt_timeout(1.5)).unwrap();
+    assert!(transport.requests().is_empty());
+
+    // Back in connected mode, syncing waits for the overlay to reconnect
+    sync.set_mode(OperatingMode::Connected);
+    assert_eq!(
+        tauri::async_runtime::block_on(sync.sync_stt_timeout(1.5)),
+        Err(SyncError::NotConnected)
+    );
+}
+
+#[test]
+fn test_dry_run_builds_requests_without_sending() {
+    let transport = MockTransport::replying(Vec::new());
+    let mut sync = connected(&transport);
+    sync.set_dry_run(true);
+
+    let sections = CleanupPromptSections::default();
+    tauri::async_runtime::block_on(sync.sync_prompt_sections(&sections)).unwrap();
+    let dry_run = sync.metrics().last_dry_run.unwrap();
+
+    assert_eq!(
+        tauri::async_runtime::block_on(sync.sync_all(&full_snapshot())),
+        Ok(BulkSyncOutcome::Skipped)
+    );
+    assert!(transport.requests().is_empty());
+    let metrics = sync.metrics();
+    assert_eq!(metrics.dry_runs, 2);
+    assert_eq!(metrics.succeeded, 0);
+    assert_eq!(
+        metrics.last_dry_run.unwrap().url,
+        "http://127.0.0.1:8765/api/config/bulk"
+    );
+
+    // The captured request matches what is sent once dry run is off
+    sync.set_dry_run(false);
+    tauri::async_runtime::block_on(sync.sync_prompt_sections(&sections)).unwrap();
+    let sent = &transport.requests()[0];
+    assert_eq!(dry_run.method, sent.method);
+    assert_eq!(dry_run.url, sent.url);
+    assert_eq!(dry_run.body, serde_json::to_string(&sent.body).unwrap());
+    assert_eq!(
+        dry_run.body,
+        serde_json::to_value(&sections).unwrap().to_string()
+    );
+}
+
+#[test]
+fn test_dry_run_redacts_client_credentials() {
+    let request = DryRunRequest::new(
+        "PUT",
+        "http://127.0.0.1:8765/api/config/stt-timeout".to_string(),
+        &[
+            (CLIENT_UUID_HEADER, "client-1".to_string()),
+            ("authorization", "Bearer secret".to_string()),
+            (IDEMPOTENCY_KEY_HEADER, "key-1".to_string()),
+        ],
+        &json!({ "timeout_seconds": 1.5 }),
+    );
+
+    let curl = request.to_curl();
+    assert!(!curl.contains("client-1"));
+    assert!(!curl.contains("secret"));
+    assert_eq!(
+        curl,
+        "curl -X PUT 'http://127.0.0.1:8765/api/config/stt-timeout' \
+         -H 'X-Client-UUID: <redacted>' -H 'authorization: <redacted>' \
+         -H 'Idempotency-Key: key-1' -H 'Content-Type: application/json' \
+         -d '{\"timeout_seconds\":1.5}'"
+    );
+}
+
+#[test]
+fn test_dry_run_curl_quotes_single_quotes_in_body() {
+    let request = DryRunRequest::new(
+        "PUT",
+        "http://127.0.0.1:8765/api/config/prompts".to_string(),
+        &[],
+        &json!({ "content": "don't" }),
+    );
+    assert!(request
+        .to_curl()
+        .ends_with(r#"-d '{"content":"don'\''t"}'"#));
+}
+
+#[test]
+fn test_api_base_path_is_normalized() {
+    assert_eq!(normalize_api_base_path(""), "");
+    assert_eq!(normalize_api_base_path("/"), "");
+    assert_eq!(normalize_api_base_path("/api"), "/api");
+    assert_eq!(normalize_api_base_path("api"), "/api");
+    assert_eq!(normalize_api_base_path("/stt/api/"), "/stt/api");
+    assert_eq!(normalize_api_base_path("//stt//api//"), "/stt/api");
+}
+
+#[test]
+fn test_endpoints_are_built_from_base_path() {
+    let server = "http://127.0.0.1:8765";
+    for (base_path, expected) in [
+        ("", "http://127.0.0.1:8765/config/prompts"),
+        ("/", "http://127.0.0.1:8765/config/prompts"),
+        ("/api", "http://127.0.0.1:8765/api/config/prompts"),
+        ("/stt/api/", "http://127.0.0.1:8765/stt/api/config/prompts"),
+    ] {
+        let base_path = normalize_api_base_path(base_path);
+        assert_eq!(
+            endpoint_url(server, &base_path, "/config/prompts"),
+            expected
+        );
+    }
+    assert_eq!(
+        endpoint_url("http://proxy.local/", "/stt/api", "/config/bulk"),
+        "http://proxy.local/stt/api/config/bulk"
+    );
+}
+
+#[test]
+fn test_sync_uses_configured_base_path() {
+    let transport = MockTransport::replying(vec![Ok(reply(200, "")), Ok(reply(404, "Not Found"))]);
+    let mut sync = connected(&transport);
+    assert_eq!(sync.api_base_path(), "/api");
+    sync.set_api_base_path("/stt/api/");
+
+    tauri::async_runtime::block_on(sync.sync_stt_timeout(1.5)).unwrap();
+    tauri::async_runtime::block_on(sync.sync_all(&full_snapshot())).unwrap();
+
+    let urls: Vec<_> = transport.requests().into_iter().map(|r| r.url).collect();
+    assert_eq!(
+        urls,
+        [
+            "http://127.0.0.1:8765/stt/api/config/stt-timeout",
+            "http://127.0.0.1:8765/stt/api/config/bulk",
+        ]
+    );
+}
+
+// =============================================================================
+// Per-setting sync status
+// =============================================================================
+
+/// Replies like `MockTransport`, but only after yielding once, so a sync can
+/// be observed while its request is in flight
+struct YieldingTransport(Arc<MockTransport>);
+
+impl ConfigTransport for YieldingTransport {
+    fn put_json(
+        &self,
+        url: String,
+        headers: Vec<(&'static str, String)>,
+        body: serde_json::Value,
+    ) -> TransportFuture<'_> {
+        let response = self.0.put_json(url, headers, body);
+        Box::pin(async move {
+            tokio::task::yield_now().await;
+            response.await
+        })
+    }
+
+    fn post_json(
+        &self,
+        url: String,
+        headers: Vec<(&'static str, String)>,
+        body: serde_json::Value,
+    ) -> TransportFuture<'_> {
+        let response = self.0.post_json(url, headers, body);
+        Box::pin(async move {
+            tokio::task::yield_now().await;
+            response.await
+        })
+    }
+}
+
+fn yielding(responses: Vec<Result<TransportResponse, SyncError>>) -> ConfigSyncState {
+    let mut sync = ConfigSyncState::with_transport(Arc::new(YieldingTransport(
+        MockTransport::replying(responses),
+    )));
+    sync.set_connected("http://127.0.0.1:8765".to_string(), "client-1".to_string());
+    sync
+}
+
+/// Run `sync` to completion, returning the status of `setting` while its
+/// request was in flight
+fn status_in_flight<T>(
+    state: &ConfigSyncState,
+    setting: ConfigSetting,
+    sync: impl Future<Output = Result<T, SyncError>>,
+) -> (Option<SyncStatus>, Result<T, SyncError>) {
+    tauri::async_runtime::block_on(async {
+        let mut sync = std::pin::pin!(sync);
+        let first_poll = std::future::poll_fn(|cx| Poll::Ready(sync.as_mut().poll(cx))).await;
+        assert!(first_poll.is_pending(), "request should still be in flight");
+
+        let in_flight = state.sync_status().get(&setting).cloned();
+        (in_flight, sync.await)
+    })
+}
+
+#[test]
+fn test_sync_status_goes_from_pending_to_synced() {
+    let sync = yielding(Vec::new());
+    assert!(sync.sync_status().is_empty());
+
+    let (in_flight, result) =
+        status_in_flight(&sync, ConfigSetting::SttTimeout, sync.sync_stt_timeout(1.5));
+    assert_eq!(in_flight, Some(SyncStatus::Pending));
+    result.unwrap();
+
+    let statuses = sync.sync_status();
+    assert!(matches!(
+        statuses.get(&ConfigSetting::SttTimeout),
+        Some(SyncStatus::Synced { .. })
+    ));
+    assert_eq!(statuses.len(), 1, "only the synced setting has a status");
+}
+
+#[test]
+fn test_sync_status_goes_from_pending_to_failed() {
+    let sync = yielding(vec![Ok(reply(422, "bad sections"))]);
+
+    let (in_flight, result) = status_in_flight(
+        &sync,
+        ConfigSetting::PromptSections,
+        sync.sync_prompt_sections(&CleanupPromptSections::default()),
+    );
+    assert_eq!(in_flight, Some(SyncStatus::Pending));
+    assert!(result.is_err());
+
+    assert_eq!(
+        sync.sync_status().get(&ConfigSetting::PromptSections),
+        Some(&SyncStatus::Failed {
+            error: "Server returned HTTP 422: bad sections".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_sync_status_without_connection_stays_pending_until_bulk_sync() {
+    let transport = MockTransport::replying(vec![Ok(reply(
+        200,
+        r#"{"results": [{"setting": "stt-timeout", "accepted": true}]}"#,
+    ))]);
+    let mut sync = ConfigSyncState::with_transport(transport.clone());
+    let result = tauri::async_runtime::block_on(sync.sync_stt_timeout(1.5));
+    assert_eq!(result, Err(SyncError::NotConnected));
+    assert_eq!(
+        sync.sync_status().get(&ConfigSetting::SttTimeout),
+        Some(&SyncStatus::Pending)
+    );
+
+    // Connecting syncs every setting in one request, settling the offline change
+    sync.set_connected("http://127.0.0.1:8765".to_string(), "client-1".to_string());
+    tauri::async_runtime::block_on(sync.sync_all(&full_snapshot())).unwrap();
+    assert!(matches!(
+        sync.sync_status().get(&ConfigSetting::SttTimeout),
+        Some(SyncStatus::Synced { .. })
+    ));
+}
+
+#[test]
+fn test_bulk_sync_status_is_tracked_per_setting() {
+    let body = r#"{"results": [
+        {"setting": "prompt-sections", "accepted": true},
+        {"setting": "stt-timeout", "accepted": false, "error": "out of range"}
+    ]}"#;
+    let sync = yielding(vec![Ok(reply(200, body))]);
+
+    let (in_flight, result) = status_in_flight(
+        &sync,
+        ConfigSetting::PromptSections,
+        sync.sync_all(&full_snapshot()),
+    );
+    assert_eq!(in_flight, Some(SyncStatus::Pending));
+    result.unwrap();
+
+    let statuses = sync.sync_status();
+    assert!(matches!(
+        statuses.get(&ConfigSetting::PromptSections),
+        Some(SyncStatus::Synced { .. })
+    ));
+    assert_eq!(
+        statuses.get(&ConfigSetting::SttTimeout),
+        Some(&SyncStatus::Failed {
+            error: "out of range".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_sync_status_serializes_by_setting_name() {
+    let sync = ConfigSyncState::with_transport(MockTransport::replying(Vec::new()));
+    let _ = tauri::async_runtime::block_on(sync.sync_stt_timeout(1.5));
+
+    assert_eq!(
+        serde_json::to_value(sync.sync_status()).unwrap(),
+        json!({
+            "stt-timeout": { "status": "pending" }
+        })
+    );
+    let synced = serde_json::to_value(SyncStatus::Synced {
+        at: std::time::Instant::now(),
+    })
+    .unwrap();
+    assert_eq!(synced["status"], "synced");
+    assert!(synced["synced_ms_ago"].is_u64());
+}
+
+// =============================================================================
+// Cancelling syncs in flight
+// =============================================================================
+
+/// A server that accepts requests but never answers
+#[derive(Default)]
+struct HangingTransport {
+    requests: AtomicUsize,
+}
+
+impl HangingTransport {
+    fn hang(&self) -> TransportFuture<'_> {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        Box::pin(std::future::pending())
+    }
+}
+
+impl ConfigTransport for HangingTransport {
+    fn put_json(
+        &self,
+        _url: String,
+        _headers: Vec<(&'static str, String)>,
+        _body: serde_json::Value,
+    ) -> TransportFuture<'_> {
+        self.hang()
+    }
+
+    fn post_json(
+        &self,
+        _url: String,
+        _headers: Vec<(&'static str, String)>,
+        _body: serde_json::Value,
+    ) -> TransportFuture<'_> {
+        self.hang()
+    }
+}
+
+fn hanging() -> (Arc<HangingTransport>, ConfigSyncState) {
+    let transport = Arc::new(HangingTransport::default());
+    let mut sync = ConfigSyncState::with_transport(transport.clone());
+    sync.set_connected("http://127.0.0.1:8765".to_string(), "client-1".to_string());
+    (transport, sync)
+}
+
+/// Start `sync`, cancel it once its request is in flight, and return how it resolved
+fn cancel_mid_flight<T>(
+    state: &ConfigSyncState,
+    sync: impl Future<Output = Result<T, SyncError>>,
+) -> Result<T, SyncError> {
+    tauri::async_runtime::block_on(async {
+        let mut sync = std::pin::pin!(sync);
+        let first_poll = std::future::poll_fn(|cx| Poll::Ready(sync.as_mut().poll(cx))).await;
+        assert!(first_poll.is_pending(), "request should still be in flight");
+
+        state.cancel_in_flight();
+        sync.await
+    })
+}
+
+#[test]
+fn test_cancelled_sync_returns_cancelled_without_error_event() {
+    let (transport, sync) = hanging();
+
+    let result = cancel_mid_flight(&sync, sync.sync_stt_timeout(1.5));
+    assert_eq!(result, Err(SyncError::Cancelled));
+    assert_eq!(transport.requests.load(Ordering::SeqCst), 1);
+
+    let error = result.unwrap_err();
+    assert!(!error.is_retryable());
+    assert!(sync_error_response(ConfigSetting::SttTimeout, &error).is_none());
+    assert_eq!(sync.metrics().failed, 0);
+    assert_eq!(
+        sync.sync_status().get(&ConfigSetting::SttTimeout),
+        Some(&SyncStatus::Pending),
+        "resynced on the next connect"
+    );
+}
+
+#[test]
+fn test_cancelled_bulk_sync_returns_cancelled() {
+    let (_, sync) = hanging();
+    let result = cancel_mid_flight(&sync, sync.sync_all(&full_snapshot()));
+    assert_eq!(result, Err(SyncError::Cancelled));
+}
+
+#[test]
+fn test_failed_sync_still_gets_error_event() {
+    let response = sync_error_response(ConfigSetting::SttTimeout, &SyncError::Unauthorized);
+    assert_eq!(
+        serde_json::to_value(response.unwrap()).unwrap(),
+        json!({
+            "type": "config-error",
+            "setting": "stt-timeout",
+            "error": "Server rejected this client's credentials",
+        })
+    );
+}
+
+#[test]
+fn test_disconnect_only_cancels_syncs_already_started() {
+    let transport = MockTransport::replying(Vec::new());
+    let mut sync = connected(&transport);
+    sync.set_disconnected();
+    sync.set_connected("http://127.0.0.1:8765".to_string(), "client-1".to_string());
+
+    tauri::async_runtime::block_on(sync.sync_stt_timeout(1.5)).unwrap();
+    assert_eq!(transport.requests().len(), 1);
+}
+
+// =============================================================================
+// Concurrent syncs of the same setting
+// =============================================================================
+
+/// Run `syncs` concurrently, started in order, and collect their results
+fn run_together<'a, T>(syncs: Vec<std::pin::Pin<Box<dyn Future<Output = T> + 'a>>>) -> Vec<T> {
+    tauri::async_runtime::block_on(async {
+        let mut syncs: Vec<_> = syncs.into_iter().map(Some).collect();
+        let mut results: Vec<Option<T>> = syncs.iter().map(|_| None).collect();
+        std::future::poll_fn(|cx| {
+            for (sync, result) in syncs.iter_mut().zip(&mut results) {
+                if let Some(future) = sync {
+                    if let Poll::Ready(output) = future.as_mut().poll(cx) {
+                        *result = Some(output);
+                        *sync = None;
+                    }
+                }
+            }
+            if syncs.iter().all(Option::is_none) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+        results.into_iter().map(Option::unwrap).collect()
+    })
+}
+
+fn sent_timeouts(transport: &MockTransport) -> Vec<serde_json::Value> {
+    transport
+        .requests()
+        .into_iter()
+        .map(|request| request.body["timeout_seconds"].clone())
+        .collect()
+}
+
+#[test]
+fn test_stale_sync_of_same_setting_is_dropped() {
+    let transport = MockTransport::replying(Vec::new());
+    let mut sync = ConfigSyncState::with_transport(Arc::new(YieldingTransport(transport.clone())));
+    sync.set_connected("http://127.0.0.1:8765".to_string(), "client-1".to_string());
+
+    let results = run_together(vec![
+        Box::pin(sync.sync_stt_timeout(0.5)),
+        Box::pin(sync.sync_stt_timeout(1.0)),
+        Box::pin(sync.sync_stt_timeout(2.0)),
+    ]);
+
+    assert_eq!(results, vec![Ok(()), Err(SyncError::Superseded), Ok(())]);
+    assert_eq!(
+        sent_timeouts(&transport),
+        vec![json!(0.5), json!(2.0)],
+        "one request at a time, and the newest value lands last"
+    );
+    assert!(matches!(
+        sync.sync_status().get(&ConfigSetting::SttTimeout),
+        Some(SyncStatus::Synced { .. })
+    ));
+}
+
+#[test]
+fn test_syncs_of_different_settings_run_concurrently() {
+    let transport = MockTransport::replying(Vec::new());
+    let mut sync = ConfigSyncState::with_transport(Arc::new(YieldingTransport(transport.clone())));
+    sync.set_connected("http://127.0.0.1:8765".to_string(), "client-1".to_string());
+
+    let sections = CleanupPromptSections::default();
+    tauri::async_runtime::block_on(async {
+        let mut timeout_sync = std::pin::pin!(sync.sync_stt_timeout(1.5));
+        let mut sections_sync = std::pin::pin!(sync.sync_prompt_sections(&sections));
+        std::future::poll_fn(|cx| {
+            assert!(timeout_sync.as_mut().poll(cx).is_pending());
+            assert!(sections_sync.as_mut().poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+        assert_eq!(transport.requests().len(), 2, "both requests in flight");
+        timeout_sync.await.unwrap();
+        sections_sync.await.unwrap();
+    });
+}
+
+#[test]
+fn test_bulk_sync_waits_for_individual_sync_of_same_setting() {
+    let transport = MockTransport::replying(vec![
+        Ok(reply(200, "")),
+        Ok(reply(200, r#"{"results": []}"#)),
+    ]);
+    let mut sync = ConfigSyncState::with_transport(Arc::new(YieldingTransport(transport.clone())));
+    sync.set_connected("http://127.0.0.1:8765".to_string(), "client-1".to_string());
+
+    let snapshot = full_snapshot();
+    let results = run_together(vec![
+        Box::pin(async { sync.sync_stt_timeout(1.0).await.map(|()| None) }),
+        Box::pin(async { sync.sync_all(&snapshot).await.map(Some) }),
+    ]);
+    assert!(results.iter().all(Result::is_ok));
+
+    let methods: Vec<_> = transport.requests().iter().map(|r| r.method).collect();
+    assert_eq!(methods, vec!["PUT", "POST"]);
+}
+
+#[test]
+fn test_cancel_reaches_sync_waiting_for_its_turn() {
+    let transport = MockTransport::replying(Vec::new());
+    let mut sync = ConfigSyncState::with_transport(Arc::new(YieldingTransport(transport.clone())));
+    sync.set_connected("http://127.0.0.1:8765".to_string(), "client-1".to_string());
+
+    let waiting_result = tauri::async_runtime::block_on(async {
+        let mut in_flight = std::pin::pin!(sync.sync_stt_timeout(0.5));
+        let mut waiting = std::pin::pin!(sync.sync_stt_timeout(2.0));
+        std::future::poll_fn(|cx| {
+            assert!(in_flight.as_mut().poll(cx).is_pending());
+            assert!(waiting.as_mut().poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+
+        sync.cancel_in_flight();
+        let _ = in_flight.await;
+        waiting.await
+    });
+
+    assert_eq!(waiting_result, Err(SyncError::Cancelled));
+    assert_eq!(
+        sent_timeouts(&transport),
+        vec![json!(0.5)],
+        "the queued sync must not go out on a fresh token"
+    );
+    assert_eq!(
+        sync.sync_status().get(&ConfigSetting::SttTimeout),
+        Some(&SyncStatus::Pending),
+        "the unsent value is resynced on the next connect"
+    );
+}
+
+#[test]
+fn test_superseded_sync_gets_no_error_event() {
+    let error = SyncError::Superseded;
+    assert!(!error.is_retryable());
+    assert!(sync_error_response(ConfigSetting::SttTimeout, &error).is_none());
+    assert_eq!(SyncStatus::from_error(&error), SyncStatus::Pending);
+}
+
+// =============================================================================
+// Shutdown flush
+// =============================================================================
+
+#[test]
+fn test_shutdown_flush_sends_change_made_just_before() {
+    let transport = MockTransport::replying(Vec::new());
+    let mut sync = ConfigSyncState::with_transport(Arc::new(YieldingTransport(transport.clone())));
+    sync.set_connected("http://127.0.0.1:8765".to_string(), "client-1".to_string());
+
+    // The last change waits behind the one in flight when quit is requested
+    let sent_when_flushed = RefCell::new(Vec::new());
+    let unflushed = RefCell::new(None);
+    run_together(vec![
+        Box::pin(async { sync.sync_stt_timeout(0.5).await.unwrap() }),
+        Box::pin(async { sync.sync_stt_timeout(2.0).await.unwrap() }),
+        Box::pin(async {
+            let pending = sync.shutdown_flush(Duration::from_secs(5)).await;
+            *unflushed.borrow_mut() = Some(pending);
+            *sent_when_flushed.borrow_mut() = sent_timeouts(&transport);
+        }),
+    ]);
+
+    assert_eq!(*sent_when_flushed.borrow(), vec![json!(0.5), json!(2.0)]);
+    assert_eq!(unflushed.into_inner(), Some(Vec::new()));
+}
+
+#[test]
+fn test_change_queued_behind_failed_sync_stays_pending() {
+    let sync = yielding(vec![Ok(reply(422, "bad timeout"))]);
+
+    let status_after_first = tauri::async_runtime::block_on(async {
+        let mut first = std::pin::pin!(sync.sync_stt_timeout(0.5));
+        let mut queued = std::pin::pin!(sync.sync_stt_timeout(2.0));
+        std::future::poll_fn(|cx| {
+            assert!(first.as_mut().poll(cx).is_pending());
+            assert!(queued.as_mut().poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+
+        assert!(first.await.is_err());
+        let status = sync.sync_status().get(&ConfigSetting::SttTimeout).cloned();
+        queued.await.unwrap();
+        status
+    });
+
+    assert_eq!(
+        status_after_first,
+        Some(SyncStatus::Pending),
+        "the queued change hasn't been sent yet"
+    );
+    assert!(matches!(
+        sync.sync_status().get(&ConfigSetting::SttTimeout),
+        Some(SyncStatus::Synced { .. })
+    ));
+}
+
+#[test]
+fn test_stale_unsupported_reply_keeps_newer_status() {
+    let sync = yielding(vec![Ok(reply(404, "Not Found"))]);
+    let overrides = HashMap::new();
+
+    let status_after_first = tauri::async_runtime::block_on(async {
+        let mut first = std::pin::pin!(sync.sync_profile_prompt_overrides(&overrides));
+        let mut queued = std::pin::pin!(sync.sync_profile_prompt_overrides(&overrides));
+        std::future::poll_fn(|cx| {
+            assert!(first.as_mut().poll(cx).is_pending());
+            assert!(queued.as_mut().poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+
+        assert_eq!(first.await, Err(SyncError::Unsupported));
+        let status = sync
+            .sync_status()
+            .get(&ConfigSetting::ProfilePrompts)
+            .cloned();
+        queued.await.unwrap();
+        status
+    });
+
+    assert_eq!(status_after_first, Some(SyncStatus::Pending));
+    assert!(matches!(
+        sync.sync_status().get(&ConfigSetting::ProfilePrompts),
+        Some(SyncStatus::Synced { .. })
+    ));
+}
+
+#[test]
+fn test_shutdown_flush_gives_up_at_deadline() {
+    let (_, sync) = hanging();
+    let unflushed = tauri::async_runtime::block_on(async {
+        let mut stuck = std::pin::pin!(sync.sync_stt_timeout(1.5));
+        let first_poll = std::future::poll_fn(|cx| Poll::Ready(stuck.as_mut().poll(cx))).await;
+        assert!(first_poll.is_pending(), "request should still be in flight");
+
+        sync.shutdown_flush(Duration::from_millis(20)).await
+    });
+    assert_eq!(unflushed, vec![ConfigSetting::SttTimeout]);
+}
+
+#[test]
+fn test_shutdown_flush_without_syncs_returns_immediately() {
+    let sync = ConfigSyncState::with_transport(MockTransport::replying(Vec::new()));
+    let unflushed = tauri::async_runtime::block_on(sync.shutdown_flush(Duration::from_secs(5)));
+    assert!(unflushed.is_empty());
+}
@@ -0,0 +1,109 @@
+use std::cell::RefCell;
+
+use crate::connection_state::ConnectionStateCache;
+use crate::events::{ConnectionStatePayload, DisconnectReason};
+
+const SERVER_URL: &str = "http://127.0.0.1:8765";
+
+/// Report `state` as the connection machine would, recording every payload emitted
+fn transition(
+    cache: &ConnectionStateCache,
+    emitted: &RefCell<Vec<ConnectionStatePayload>>,
+    state: &str,
+    reason: Option<DisconnectReason>,
+) {
+    cache.transition(state, reason, |p| emitted.borrow_mut().push(p.clone()));
+}
+
+#[test]
+fn test_starts_disconnected() {
+    let cache = ConnectionStateCache::default();
+    assert_eq!(cache.current(), ConnectionStatePayload::disconnected(None));
+}
+
+#[test]
+fn test_stored_state_matches_last_emitted_payload() {
+    let cache = ConnectionStateCache::default();
+    let emitted = RefCell::new(Vec::new());
+
+    transition(&cache, &emitted, "idle", None);
+    cache.set_server_url(SERVER_URL.to_string(), |p| {
+        emitted.borrow_mut().push(p.clone());
+    });
+    transition(
+        &cache,
+        &emitted,
+        "reconnecting",
+        Some(DisconnectReason::Timeout),
+    );
+
+    let emitted = emitted.into_inner();
+    assert_eq!(emitted.len(), 3);
+    assert_eq!(&cache.current(), emitted.last().unwrap());
+}
+
+#[test]
+fn test_transition_carries_disconnect_reason() {
+    let cache = ConnectionStateCache::default();
+    let emitted = RefCell::new(Vec::new());
+    transition(
+        &cache,
+        &emitted,
+        "reconnecting",
+        Some(DisconnectReason::ServerClosed),
+    );
+
+    let current = cache.current();
+    assert_eq!(current.state, "reconnecting");
+    assert_eq!(current.reason.as_deref(), Some("server-closed"));
+}
+
+#[test]
+fn test_transition_keeps_server_url_while_connected() {
+    let cache = ConnectionStateCache::default();
+    cache.transition("idle", None, |_| {});
+    cache.set_server_url(SERVER_URL.to_string(), |_| {});
+    assert_eq!(
+        cache.current(),
+        ConnectionStatePayload::connected(SERVER_URL)
+    );
+
+    cache.transition("recording", None, |_| {});
+    let current = cache.current();
+    assert_eq!(current.state, "recording");
+    assert_eq!(current.server_url.as_deref(), Some(SERVER_URL));
+
+    cache.transition("reconnecting", None, |_| {});
+    assert_eq!(cache.current().server_url, None);
+}
+
+#[test]
+fn test_server_url_ignored_once_machine_reports_disconnect() {
+    let cache = ConnectionStateCache::default();
+    let emitted = RefCell::new(Vec::new());
+    transition(
+        &cache,
+        &emitted,
+        "reconnecting",
+        Some(DisconnectReason::ServerClosed),
+    );
+
+    // A late report from the previous connection must not overwrite "reconnecting"
+    cache.set_server_url(SERVER_URL.to_string(), |p| {
+        emitted.borrow_mut().push(p.clone());
+    });
+    assert_eq!(emitted.borrow().len(), 1);
+    assert_eq!(cache.current().state, "reconnecting");
+    assert_eq!(cache.current().server_url, None);
+}
+
+#[test]
+fn test_reemit_sends_current_state() {
+    let cache = ConnectionStateCache::default();
+    cache.transition("idle", None, |_| {});
+    cache.set_server_url(SERVER_URL.to_string(), |_| {});
+
+    let mut reemitted = None;
+    cache.reemit(|p| reemitted = Some(p.clone()));
+    assert_eq!(reemitted, Some(cache.current()));
+}
@@ -0,0 +1,91 @@
+use crate::active_window::ActiveWindowInfo;
+use crate::disabled_apps::{DisabledApps, RecordingGate, SuppressionScope};
+use crate::profile_matcher::ProfileRuleError;
+
+fn disabled(patterns: &[&str]) -> DisabledApps {
+    let patterns: Vec<String> = patterns.iter().map(ToString::to_string).collect();
+    DisabledApps::compile(&patterns).unwrap()
+}
+
+fn window(app_name: &str, bundle_id: Option<&str>) -> ActiveWindowInfo {
+    ActiveWindowInfo {
+        app_name: app_name.to_string(),
+        bundle_id: bundle_id.map(ToString::to_string),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_disabled_app_hides_overlay_by_default() {
+    let apps = disabled(&["^steam$"]);
+    assert_eq!(
+        apps.gate(Some(&window("Steam", None)), false),
+        RecordingGate::HideOverlay
+    );
+}
+
+#[test]
+fn test_disabled_app_suppresses_recording_when_configured() {
+    let apps = disabled(&["^steam$"]);
+    assert_eq!(
+        apps.gate(Some(&window("Steam", None)), true),
+        RecordingGate::Suppress
+    );
+}
+
+#[test]
+fn test_other_apps_record_normally() {
+    let apps = disabled(&["^steam$"]);
+    assert_eq!(
+        apps.gate(Some(&window("Steam Helper", None)), true),
+        RecordingGate::Record
+    );
+    assert_eq!(
+        apps.gate(Some(&window("Slack", None)), true),
+        RecordingGate::Record
+    );
+}
+
+#[test]
+fn test_unknown_window_records_normally() {
+    let apps = disabled(&[".*"]);
+    assert_eq!(apps.gate(None, true), RecordingGate::Record);
+}
+
+#[test]
+fn test_disabled_app_matches_bundle_id() {
+    let apps = disabled(&[r"^com\.agilebits\.onepassword"]);
+    let info = window("1Password", Some("com.agilebits.onepassword7"));
+    assert!(apps.contains(&info));
+    assert_eq!(apps.gate(Some(&info), true), RecordingGate::Suppress);
+}
+
+#[test]
+fn test_no_disabled_apps_never_suppresses() {
+    let apps = DisabledApps::default();
+    assert_eq!(
+        apps.gate(Some(&window("Steam", None)), true),
+        RecordingGate::Record
+    );
+}
+
+#[test]
+fn test_invalid_disabled_app_pattern_is_rejected() {
+    let result = DisabledApps::compile(&["steam".to_string(), "[".to_string()]);
+    assert!(matches!(
+        result,
+        Err(ProfileRuleError::InvalidPattern { ref pattern, .. }) if pattern == "["
+    ));
+}
+
+#[test]
+fn test_suppression_scope_serializes_for_event_payload() {
+    assert_eq!(
+        serde_json::to_value(SuppressionScope::Overlay).unwrap(),
+        "overlay"
+    );
+    assert_eq!(
+        serde_json::to_value(SuppressionScope::Recording).unwrap(),
+        "recording"
+    );
+}
@@ -0,0 +1,45 @@
+use crate::events::{ConnectionStatePayload, DisconnectReason};
+
+#[test]
+fn test_connection_state_omits_absent_fields() {
+    let payload = ConnectionStatePayload::disconnected(None);
+    let json = serde_json::to_value(&payload).unwrap();
+    assert_eq!(json, serde_json::json!({ "state": "disconnected" }));
+}
+
+#[test]
+fn test_connection_state_includes_reason_when_present() {
+    let payload = ConnectionStatePayload::disconnected(Some(DisconnectReason::AuthFailed));
+    let json = serde_json::to_value(&payload).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "state": "disconnected", "reason": "auth-failed" })
+    );
+}
+
+#[test]
+fn test_connection_state_includes_server_url_when_connected() {
+    let payload = ConnectionStatePayload::connected("http://127.0.0.1:8765");
+    let json = serde_json::to_value(&payload).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "state": "idle", "server_url": "http://127.0.0.1:8765" })
+    );
+}
+
+#[test]
+fn test_disconnect_reason_deserializes_from_kebab_case() {
+    let reason: DisconnectReason = serde_json::from_str("\"user-requested\"").unwrap();
+    assert_eq!(reason, DisconnectReason::UserRequested);
+    assert_eq!(reason.as_str(), "user-requested");
+}
+
+#[test]
+fn test_unknown_disconnect_reason_is_kept() {
+    let reason: DisconnectReason = serde_json::from_str("\"server-upgrading\"").unwrap();
+    assert_eq!(
+        reason,
+        DisconnectReason::Unknown("server-upgrading".to_string())
+    );
+    assert_eq!(reason.as_str(), "server-upgrading");
+}
@@ -0,0 +1,298 @@
+use chrono::{Duration, Utc};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::events::HistoryChangedPayload;
+use crate::history::{HistoryEntry, HistoryImportStrategy, HistoryStorage};
+
+/// Create a history storage in a fresh temporary directory
+fn temp_storage() -> (HistoryStorage, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("tambourine-history-{}", uuid::Uuid::new_v4()));
+    (HistoryStorage::new(dir.clone()), dir)
+}
+
+fn entry_at(text: &str, minutes_ago: i64) -> HistoryEntry {
+    let mut entry = HistoryEntry::new(text.to_string(), text.to_string());
+    entry.timestamp = Utc::now() - Duration::minutes(minutes_ago);
+    entry
+}
+
+#[test]
+fn test_latest_empty_history() {
+    let (storage, dir) = temp_storage();
+    assert!(storage.latest().unwrap().is_none());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_latest_returns_most_recent_added_entry() {
+    let (storage, dir) = temp_storage();
+    storage
+        .add_entry("first".to_string(), "first".to_string(), None, None)
+        .unwrap();
+    storage
+        .add_entry("second".to_string(), "second".to_string(), Some(1500), None)
+        .unwrap();
+
+    let latest = storage.latest().unwrap().unwrap();
+    assert_eq!(latest.text, "second");
+    assert_eq!(latest.duration_ms, Some(1500));
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_latest_returns_most_recent_seeded_entry() {
+    let (storage, dir) = temp_storage();
+    // Seed out of order: the most recent entry is not first in the list
+    let entries = vec![
+        entry_at("older", 30),
+        entry_at("newest", 1),
+        entry_at("oldest", 60),
+    ];
+    storage
+        .import_entries(entries, HistoryImportStrategy::MergeAppend)
+        .unwrap();
+
+    assert_eq!(storage.latest().unwrap().unwrap().text, "newest");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+const DEDUP_WINDOW: Option<Duration> = Some(Duration::seconds(30));
+
+fn add(storage: &HistoryStorage, text: &str) -> HistoryEntry {
+    storage
+        .add_entry(text.to_string(), text.to_string(), None, DEDUP_WINDOW)
+        .unwrap()
+}
+
+#[test]
+fn test_dedup_refreshes_repeat_within_window() {
+    let (storage, dir) = temp_storage();
+    let first = add(&storage, "Send the report today");
+    let repeat = add(&storage, "  Send the  report\ttoday ");
+
+    let entries = storage.get_all(None).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(repeat.id, first.id);
+    assert!(entries[0].timestamp >= first.timestamp);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_dedup_inserts_repeat_outside_window() {
+    let (storage, dir) = temp_storage();
+    storage
+        .import_entries(
+            vec![entry_at("hello", 5)],
+            HistoryImportStrategy::MergeAppend,
+        )
+        .unwrap();
+    add(&storage, "hello");
+
+    assert_eq!(storage.get_all(None).unwrap().len(), 2);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_dedup_always_inserts_different_text() {
+    let (storage, dir) = temp_storage();
+    add(&storage, "hello");
+    add(&storage, "hello world");
+    add(&storage, "hello");
+
+    let texts: Vec<String> = storage
+        .get_all(None)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.text)
+        .collect();
+    assert_eq!(texts, vec!["hello", "hello world", "hello"]);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_dedup_disabled_inserts_repeats() {
+    let (storage, dir) = temp_storage();
+    for _ in 0..2 {
+        storage
+            .add_entry("hello".to_string(), "hello".to_string(), None, None)
+            .unwrap();
+    }
+
+    assert_eq!(storage.get_all(None).unwrap().len(), 2);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+/// History storage that records the serialized change payloads it reports
+fn recording_storage() -> (HistoryStorage, Arc<Mutex<Vec<serde_json::Value>>>, PathBuf) {
+    let (storage, dir) = temp_storage();
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let recorded = changes.clone();
+    let storage = storage.with_change_listener(move |change: &HistoryChangedPayload| {
+        recorded
+            .lock()
+            .unwrap()
+            .push(serde_json::to_value(change).unwrap());
+    });
+    (storage, changes, dir)
+}
+
+fn change_types(changes: &Mutex<Vec<serde_json::Value>>) -> Vec<String> {
+    changes
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|c| c["type"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_add_reports_added_entry() {
+    let (storage, changes, dir) = recording_storage();
+    let entry = add(&storage, "hello");
+
+    let changes = changes.lock().unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0]["type"], "added");
+    assert_eq!(changes[0]["entry"]["id"], entry.id.as_str());
+    assert_eq!(changes[0]["entry"]["text"], "hello");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_deduplicated_add_reports_updated_entry() {
+    let (storage, changes, dir) = recording_storage();
+    let entry = add(&storage, "hello");
+    add(&storage, "hello");
+
+    assert_eq!(change_types(&changes), vec!["added", "updated"]);
+    assert_eq!(changes.lock().unwrap()[1]["entry"]["id"], entry.id.as_str());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_delete_reports_deleted_id() {
+    let (storage, changes, dir) = recording_storage();
+    let entry = add(&storage, "hello");
+    assert!(storage.delete(&entry.id).unwrap());
+    // Deleting an unknown ID changes nothing, so reports nothing
+    assert!(!storage.delete("missing").unwrap());
+
+    let changes = changes.lock().unwrap();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(
+        changes[1],
+        serde_json::json!({ "type": "deleted", "ids": [entry.id] })
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_clear_reports_cleared() {
+    let (storage, changes, dir) = recording_storage();
+    storage.clear().unwrap();
+
+    assert_eq!(
+        *changes.lock().unwrap(),
+        vec![serde_json::json!({ "type": "cleared" })]
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_import_reports_one_reload() {
+    let (storage, changes, dir) = recording_storage();
+    let entries = vec![entry_at("one", 3), entry_at("two", 2), entry_at("three", 1)];
+    storage
+        .import_entries(entries, HistoryImportStrategy::MergeAppend)
+        .unwrap();
+
+    assert_eq!(
+        *changes.lock().unwrap(),
+        vec![serde_json::json!({ "type": "reloaded" })]
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_prune_before_keeps_entry_at_cutoff() {
+    let (storage, changes, dir) = recording_storage();
+    let at_cutoff = entry_at("at cutoff", 60);
+    let cutoff = at_cutoff.timestamp;
+    let mut just_before = entry_at("just before", 60);
+    just_before.timestamp = cutoff - Duration::milliseconds(1);
+    let old = entry_at("old", 120);
+    let pruned_ids = vec![just_before.id.clone(), old.id.clone()];
+    let entries = vec![entry_at("recent", 1), at_cutoff, just_before, old];
+    storage
+        .import_entries(entries, HistoryImportStrategy::Replace)
+        .unwrap();
+
+    let removed = storage.prune_before(cutoff).unwrap();
+
+    let texts: Vec<String> = storage
+        .get_all(None)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.text)
+        .collect();
+    assert_eq!(texts, vec!["recent", "at cutoff"]);
+    assert_eq!(removed, 2);
+    // One event for the whole batch, so windows don't refetch the list
+    assert_eq!(change_types(&changes), vec!["reloaded", "deleted"]);
+    assert_eq!(
+        changes.lock().unwrap()[1],
+        serde_json::json!({ "type": "deleted", "ids": pruned_ids })
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_prune_count_matches_deleted_entries() {
+    let (storage, dir) = temp_storage();
+    let day = 24 * 60;
+    let entries = vec![
+        entry_at("today", 5),
+        entry_at("last week", 7 * day),
+        entry_at("last month", 30 * day),
+        entry_at("last year", 365 * day),
+    ];
+    storage
+        .import_entries(entries, HistoryImportStrategy::Replace)
+        .unwrap();
+
+    let before = storage.get_all(None).unwrap().len();
+    let removed = storage.prune(3, false).unwrap();
+    let after = storage.get_all(None).unwrap().len();
+    assert_eq!(removed, 3);
+    assert_eq!(before - after, removed);
+
+    // Nothing left that old
+    assert_eq!(storage.prune(3, false).unwrap(), 0);
+    assert_eq!(storage.prune(u32::MAX, false).unwrap(), 0);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_prune_zero_days_needs_delete_all() {
+    let (storage, changes, dir) = recording_storage();
+    add(&storage, "one");
+    add(&storage, "two");
+
+    assert!(storage.prune(0, false).is_err());
+    assert_eq!(storage.get_all(None).unwrap().len(), 2);
+
+    assert_eq!(storage.prune(0, true).unwrap(), 2);
+    assert!(storage.get_all(None).unwrap().is_empty());
+    assert_eq!(change_types(&changes), vec!["added", "added", "cleared"]);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_prune_without_matches_reports_nothing() {
+    let (storage, changes, dir) = recording_storage();
+    add(&storage, "hello");
+    assert_eq!(storage.prune(30, false).unwrap(), 0);
+    assert_eq!(change_types(&changes), vec!["added"]);
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,115 @@
+use serde_json::json;
+use std::fs;
+
+use crate::migrations::{
+    backup_path, migrate, migrate_file, migrate_value, schema_version, MigrationError,
+    CURRENT_SCHEMA_VERSION,
+};
+use crate::settings::AppSettings;
+
+/// A settings file from before versioning: no `schema_version`, no newer settings
+fn v0_settings() -> serde_json::Value {
+    json!({
+        "toggle_hotkey": { "modifiers": ["ctrl", "shift"], "key": "Space", "enabled": true },
+        "sound_enabled": false,
+        "server_url": "http://192.168.1.20:8765",
+        "formatting_profiles": [{ "id": "custom" }]
+    })
+}
+
+#[test]
+fn test_missing_version_is_version_zero() {
+    assert_eq!(schema_version(&v0_settings()), Ok(0));
+}
+
+#[test]
+fn test_v0_migrates_to_current_with_defaults() {
+    let settings = migrate(v0_settings()).unwrap();
+    let defaults = AppSettings::default();
+
+    // Stored values are kept
+    assert_eq!(settings.toggle_hotkey.modifiers, vec!["ctrl", "shift"]);
+    assert!(!settings.sound_enabled);
+    assert_eq!(settings.server_url, "http://192.168.1.20:8765");
+
+    // Missing settings get their defaults
+    assert_eq!(settings.hold_hotkey, defaults.hold_hotkey);
+    assert_eq!(
+        settings.translation_default_language,
+        defaults.translation_default_language
+    );
+    assert!(settings.profile_rules.is_empty());
+    assert!(settings.disabled_apps.is_empty());
+    assert!(!settings.suppress_recording_in_disabled_apps);
+    assert!(
+        settings.active_window_detection_enabled,
+        "detection stays on for settings from before the switch"
+    );
+    assert_eq!(settings.api_base_path, "/api");
+}
+
+#[test]
+fn test_migrated_value_keeps_unmodelled_keys_and_records_version() {
+    let migrated = migrate_value(v0_settings()).unwrap();
+    assert_eq!(migrated["schema_version"], json!(CURRENT_SCHEMA_VERSION));
+    assert_eq!(migrated["formatting_profiles"], json!([{ "id": "custom" }]));
+    assert_eq!(migrated["profile_rules"], json!([]));
+}
+
+#[test]
+fn test_current_version_is_unchanged() {
+    let mut current = v0_settings();
+    current["schema_version"] = json!(CURRENT_SCHEMA_VERSION);
+    current["disabled_apps"] = json!(["^steam$"]);
+
+    assert_eq!(migrate_value(current.clone()), Ok(current));
+}
+
+#[test]
+fn test_future_version_errors_clearly() {
+    let mut future = v0_settings();
+    future["schema_version"] = json!(CURRENT_SCHEMA_VERSION + 1);
+
+    let error = migrate(future).unwrap_err();
+    assert_eq!(
+        error,
+        MigrationError::UnsupportedVersion {
+            found: CURRENT_SCHEMA_VERSION + 1,
+            supported: CURRENT_SCHEMA_VERSION,
+        }
+    );
+    assert!(error.to_string().contains("newer than this app supports"));
+}
+
+#[test]
+fn test_invalid_version_is_rejected() {
+    assert!(matches!(
+        migrate(json!({ "schema_version": "two" })),
+        Err(MigrationError::InvalidVersion(_))
+    ));
+    assert_eq!(migrate(json!([])).unwrap_err(), MigrationError::NotAnObject);
+}
+
+#[test]
+fn test_migrate_file_backs_up_original() {
+    let dir = std::env::temp_dir().join(format!("tambourine-migrations-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("settings.json");
+    let original = v0_settings().to_string();
+    fs::write(&path, &original).unwrap();
+
+    assert_eq!(migrate_file(&path), Ok(true));
+
+    let backup = backup_path(&path, 0);
+    assert_eq!(backup.file_name().unwrap(), "settings.json.v0.bak");
+    assert_eq!(fs::read_to_string(&backup).unwrap(), original);
+    let migrated: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(migrated["schema_version"], json!(CURRENT_SCHEMA_VERSION));
+
+    // Already current: nothing to do
+    assert_eq!(migrate_file(&path), Ok(false));
+    assert_eq!(migrate_file(&dir.join("missing.json")), Ok(false));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,3 +1,72 @@
+mod accelerator_tests;
+mod active_window_tests;
+mod clipboard_tests;
+mod config_sync_tests;
+mod connection_state_tests;
+mod disabled_apps_tests;
+mod events_tests;
+mod history_tests;
 mod hotkey_config_tests;
+mod migrations_tests;
+mod overlay_auto_hide_tests;
+mod profile_matcher_tests;
+mod profile_resolver_tests;
+mod reconnect_tests;
+mod recording_id_tests;
+mod recording_timer_tests;
 mod settings_commands_tests;
+mod shortcut_errors_tests;
+mod shortcut_registrar_tests;
 mod shortcut_tests;
+mod window_watcher_tests;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::active_window::ActiveWindowInfo;
+use crate::clock::Clock;
+use crate::profile_matcher::{MatcherKind, ProfileRule};
+
+/// Clock that only moves when the test advances it
+#[derive(Clone)]
+struct FakeClock {
+    now: Rc<Cell<Instant>>,
+}
+
+impl FakeClock {
+    fn new() -> Self {
+        Self {
+            now: Rc::new(Cell::new(Instant::now())),
+        }
+    }
+
+    fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+}
+
+/// A window of `app_name` titled `window_title`, with nothing else detected
+fn window(app_name: &str, window_title: &str) -> ActiveWindowInfo {
+    ActiveWindowInfo {
+        app_name: app_name.to_string(),
+        window_title: window_title.to_string(),
+        ..Default::default()
+    }
+}
+
+/// A rule giving `profile_id` to apps whose name matches `pattern`
+fn app_rule(profile_id: &str, pattern: &str) -> ProfileRule {
+    ProfileRule {
+        profile_id: profile_id.to_string(),
+        matcher: MatcherKind::App {
+            pattern: pattern.to_string(),
+        },
+    }
+}
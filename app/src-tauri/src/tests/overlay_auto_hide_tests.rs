@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use super::FakeClock;
+use crate::clock::Clock;
+use crate::overlay_auto_hide::OverlayAutoHide;
+
+const TIMEOUT: Duration = Duration::from_secs(3);
+
+fn auto_hide() -> (OverlayAutoHide<FakeClock>, FakeClock) {
+    let clock = FakeClock::new();
+    (OverlayAutoHide::new(clock.clone()), clock)
+}
+
+#[test]
+fn test_hides_once_the_timeout_has_passed() {
+    let (mut auto_hide, clock) = auto_hide();
+    let ticket = auto_hide.schedule(TIMEOUT).unwrap();
+    assert_eq!(ticket.deadline, clock.now() + TIMEOUT);
+
+    clock.advance(TIMEOUT.checked_sub(Duration::from_millis(1)).unwrap());
+    assert!(!auto_hide.fire(ticket));
+    assert!(auto_hide.is_pending());
+
+    clock.advance(Duration::from_millis(1));
+    assert!(auto_hide.fire(ticket));
+    assert!(auto_hide.is_hidden());
+    assert!(!auto_hide.is_pending());
+
+    // A ticket only hides the overlay once
+    assert!(!auto_hide.fire(ticket));
+}
+
+#[test]
+fn test_zero_timeout_stays_until_next_action() {
+    let (mut auto_hide, _clock) = auto_hide();
+    assert!(auto_hide.schedule(Duration::ZERO).is_none());
+    assert!(!auto_hide.is_pending());
+}
+
+#[test]
+fn test_new_recording_cancels_pending_hide() {
+    let (mut auto_hide, clock) = auto_hide();
+    let ticket = auto_hide.schedule(TIMEOUT).unwrap();
+
+    clock.advance(Duration::from_secs(1));
+    // Overlay was never hidden, so there's nothing to show again
+    assert!(!auto_hide.recording_started());
+    assert!(!auto_hide.is_pending());
+
+    // The timer thread wakes up during the new recording: no flicker
+    clock.advance(TIMEOUT);
+    assert!(!auto_hide.fire(ticket));
+    assert!(!auto_hide.is_hidden());
+}
+
+#[test]
+fn test_recording_after_hide_shows_overlay_again() {
+    let (mut auto_hide, clock) = auto_hide();
+    let ticket = auto_hide.schedule(TIMEOUT).unwrap();
+    clock.advance(TIMEOUT);
+    assert!(auto_hide.fire(ticket));
+
+    assert!(auto_hide.recording_started());
+    assert!(!auto_hide.is_hidden());
+    assert!(!auto_hide.recording_started());
+}
+
+#[test]
+fn test_cancel_before_recording_starts_stops_timer() {
+    // Toggle press cancels before the release starts the recording
+    let (mut auto_hide, clock) = auto_hide();
+    let ticket = auto_hide.schedule(TIMEOUT).unwrap();
+
+    auto_hide.cancel();
+    clock.advance(TIMEOUT);
+    assert!(!auto_hide.fire(ticket));
+}
+
+#[test]
+fn test_rescheduling_replaces_pending_hide() {
+    let (mut auto_hide, clock) = auto_hide();
+    let first = auto_hide.schedule(TIMEOUT).unwrap();
+
+    clock.advance(Duration::from_secs(2));
+    let second = auto_hide.schedule(TIMEOUT).unwrap();
+
+    clock.advance(Duration::from_secs(1));
+    assert!(!auto_hide.fire(first));
+    assert!(!auto_hide.is_hidden());
+
+    clock.advance(Duration::from_secs(2));
+    assert!(auto_hide.fire(second));
+}
@@ -0,0 +1,198 @@
+use std::sync::RwLock;
+
+use super::{app_rule, window};
+use crate::profile_matcher::{
+    replace_rules, MatcherKind, ProfileRule, ProfileRuleError, ProfileRuleSet, UrlMatcher,
+};
+
+fn title_rule(profile_id: &str, pattern: &str) -> ProfileRule {
+    ProfileRule {
+        profile_id: profile_id.to_string(),
+        matcher: MatcherKind::Title {
+            pattern: pattern.to_string(),
+        },
+    }
+}
+
+fn url_rule(profile_id: &str, host: Option<&str>, path_prefix: Option<&str>) -> ProfileRule {
+    ProfileRule {
+        profile_id: profile_id.to_string(),
+        matcher: MatcherKind::Url {
+            host: host.map(str::to_string),
+            path_prefix: path_prefix.map(str::to_string),
+        },
+    }
+}
+
+fn url_matcher(host: Option<&str>, path_prefix: Option<&str>) -> UrlMatcher {
+    UrlMatcher::new(host, path_prefix).unwrap()
+}
+
+#[test]
+fn test_first_matching_rule_wins() {
+    let rules = ProfileRuleSet::compile(vec![
+        title_rule("email-pro", r"\bInbox\b"),
+        app_rule("chat", "^slack$"),
+        app_rule("notes", "slack|notion"),
+    ])
+    .unwrap();
+
+    assert_eq!(
+        rules.match_profile(&window("Slack", "general")),
+        Some("chat")
+    );
+    assert_eq!(
+        rules.match_profile(&window("Slack", "Inbox - Slack")),
+        Some("email-pro")
+    );
+    assert_eq!(
+        rules.match_profile(&window("Notion", "Roadmap")),
+        Some("notes")
+    );
+    assert_eq!(rules.match_profile(&window("Finder", "Downloads")), None);
+}
+
+#[test]
+fn test_app_rule_matches_bundle_id_and_process_name() {
+    let rules =
+        ProfileRuleSet::compile(vec![app_rule("code", r"^com\.microsoft\.VSCode$")]).unwrap();
+    let mut info = window("Code", "main.rs");
+    info.bundle_id = Some("com.microsoft.VSCode".to_string());
+    assert_eq!(rules.match_profile(&info), Some("code"));
+
+    let rules = ProfileRuleSet::compile(vec![app_rule("terminal", "^alacritty$")]).unwrap();
+    let mut info = window("", "~");
+    info.process_name = Some("alacritty".to_string());
+    assert_eq!(rules.match_profile(&info), Some("terminal"));
+}
+
+#[test]
+fn test_invalid_pattern_is_rejected_with_pattern_named() {
+    let error = ProfileRuleSet::compile(vec![app_rule("chat", "slack"), app_rule("code", "(vim")])
+        .unwrap_err();
+
+    let ProfileRuleError::InvalidPattern { ref pattern, .. } = error;
+    assert_eq!(pattern, "(vim");
+    assert!(error.to_string().starts_with("Invalid pattern '(vim'"));
+}
+
+#[test]
+fn test_bad_update_keeps_previous_rules_active() {
+    let rule_set = RwLock::new(ProfileRuleSet::default());
+    replace_rules(&rule_set, vec![app_rule("chat", "slack")]).unwrap();
+
+    let result = replace_rules(
+        &rule_set,
+        vec![app_rule("code", "code"), title_rule("notes", "[unclosed")],
+    );
+    assert!(result.is_err());
+
+    let rules = rule_set.read().unwrap();
+    assert_eq!(
+        rules.match_profile(&window("Slack", "general")),
+        Some("chat")
+    );
+    assert_eq!(rules.match_profile(&window("Code", "main.rs")), None);
+}
+
+#[test]
+fn test_good_update_swaps_rules() {
+    let rule_set = RwLock::new(ProfileRuleSet::default());
+    replace_rules(&rule_set, vec![app_rule("chat", "slack")]).unwrap();
+    replace_rules(&rule_set, vec![app_rule("code", "code")]).unwrap();
+
+    let rules = rule_set.read().unwrap();
+    assert_eq!(rules.match_profile(&window("Slack", "general")), None);
+    assert_eq!(
+        rules.match_profile(&window("Code", "main.rs")),
+        Some("code")
+    );
+}
+
+#[test]
+fn test_rules_deserialize_from_settings_json() {
+    let json = r#"[
+        { "profile_id": "chat", "matcher": { "type": "app", "pattern": "slack" } },
+        { "profile_id": "email-pro", "matcher": { "type": "title", "pattern": "Inbox" } }
+    ]"#;
+    let rules: Vec<ProfileRule> = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        rules,
+        vec![app_rule("chat", "slack"), title_rule("email-pro", "Inbox")]
+    );
+}
+
+#[test]
+fn test_url_host_wildcard_matches_subdomains() {
+    let matcher = url_matcher(Some("*.slack.com"), None);
+    assert!(matcher.matches("https://app.slack.com/client/T01/C02"));
+    assert!(matcher.matches("https://acme.enterprise.slack.com/"));
+    assert!(matcher.matches("https://slack.com/"));
+    assert!(!matcher.matches("https://notslack.com/"));
+    assert!(!matcher.matches("https://slack.com.evil.example/"));
+}
+
+#[test]
+fn test_url_exact_host_is_normalized() {
+    let matcher = url_matcher(Some("Mail.Google.com"), None);
+    assert!(matcher.matches("https://MAIL.google.com./mail/u/0"));
+    assert!(!matcher.matches("https://calendar.google.com/"));
+    // Some browsers report the URL without a scheme
+    assert!(matcher.matches("mail.google.com/mail/u/0"));
+}
+
+#[test]
+fn test_url_path_prefix_matches_whole_segments() {
+    let matcher = url_matcher(Some("github.com"), Some("/rust-lang/"));
+    assert!(matcher.matches("https://github.com/rust-lang"));
+    assert!(matcher.matches("https://github.com/rust-lang/rust/pulls"));
+    assert!(!matcher.matches("https://github.com/rust-lang-nursery"));
+    assert!(!matcher.matches("https://gitlab.com/rust-lang/rust"));
+
+    let matcher = url_matcher(None, Some("docs"));
+    assert!(matcher.matches("https://example.com/docs/intro"));
+}
+
+#[test]
+fn test_invalid_url_does_not_match() {
+    let matcher = url_matcher(Some("example.com"), None);
+    assert!(!matcher.matches(""));
+    assert!(!matcher.matches("http://[::1"));
+    assert!(!matcher.matches("not a url"));
+
+    let rules = ProfileRuleSet::compile(vec![url_rule("email", Some("gmail.com"), None)]).unwrap();
+    let mut info = window("Safari", "Inbox");
+    info.url = Some("https://exa mple.com".to_string());
+    assert_eq!(rules.match_profile(&info), None);
+    info.url = None;
+    assert_eq!(rules.match_profile(&info), None);
+}
+
+#[test]
+fn test_url_rule_matches_browser_tab() {
+    let rules = ProfileRuleSet::compile(vec![
+        url_rule("chat", Some("*.slack.com"), None),
+        url_rule("email", Some("mail.google.com"), Some("/mail")),
+    ])
+    .unwrap();
+    let mut info = window("Google Chrome", "Inbox (3)");
+    info.url = Some("https://mail.google.com/mail/u/0/#inbox".to_string());
+    assert_eq!(rules.match_profile(&info), Some("email"));
+
+    info.url = Some("https://app.slack.com/client".to_string());
+    assert_eq!(rules.match_profile(&info), Some("chat"));
+}
+
+#[test]
+fn test_invalid_url_host_pattern_is_rejected() {
+    assert!(UrlMatcher::new(Some("*.*.example.com"), None).is_err());
+    assert!(UrlMatcher::new(Some("https://example.com"), None).is_err());
+    assert!(UrlMatcher::new(Some("*."), None).is_err());
+}
+
+#[test]
+fn test_url_rule_deserializes_from_settings_json() {
+    let json = r#"{ "profile_id": "chat", "matcher": { "type": "url", "host": "*.slack.com" } }"#;
+    let rule: ProfileRule = serde_json::from_str(json).unwrap();
+    assert_eq!(rule, url_rule("chat", Some("*.slack.com"), None));
+}
@@ -0,0 +1,144 @@
+use super::{app_rule, window};
+use crate::active_window::ActiveWindowInfo;
+use crate::profile_matcher::replace_rules;
+use crate::profile_resolver::{ChainResolver, FixedResolver, ProfileResolver, ProfileSelection};
+
+/// Picks nothing, so a chain moves on to the next resolver
+struct NoProfile;
+
+impl ProfileResolver for NoProfile {
+    fn resolve(&self, _info: &ActiveWindowInfo) -> Option<String> {
+        None
+    }
+}
+
+#[test]
+fn test_fixed_resolver_ignores_window() {
+    let resolver = FixedResolver::new("email");
+    assert_eq!(
+        resolver.resolve(&window("Slack", "")).as_deref(),
+        Some("email")
+    );
+    assert_eq!(
+        resolver.resolve(&window("Code", "")).as_deref(),
+        Some("email")
+    );
+}
+
+#[test]
+fn test_chain_uses_first_resolver_that_picks_a_profile() {
+    let chain = ChainResolver::new(vec![
+        Box::new(NoProfile),
+        Box::new(FixedResolver::new("chat")),
+        Box::new(FixedResolver::new("code")),
+    ]);
+    assert_eq!(chain.resolve(&window("Slack", "")).as_deref(), Some("chat"));
+
+    let empty = ChainResolver::new(vec![Box::new(NoProfile)]);
+    assert_eq!(empty.resolve(&window("Slack", "")), None);
+    assert_eq!(
+        ChainResolver::new(Vec::new()).resolve(&window("Slack", "")),
+        None
+    );
+}
+
+#[test]
+fn test_chain_falls_back_when_no_rule_matches() {
+    let selection = ProfileSelection::default();
+    replace_rules(selection.rules(), vec![app_rule("chat", "slack")]).unwrap();
+    let chain = ChainResolver::new(vec![
+        Box::new(selection.rule_based()),
+        Box::new(FixedResolver::new("default-fallback")),
+    ]);
+
+    assert_eq!(chain.resolve(&window("Slack", "")).as_deref(), Some("chat"));
+    assert_eq!(
+        chain.resolve(&window("Code", "")).as_deref(),
+        Some("default-fallback")
+    );
+}
+
+#[test]
+fn test_selection_resolves_with_rules_by_default() {
+    let selection = ProfileSelection::default();
+    assert_eq!(selection.resolve(&window("Slack", "")), None);
+
+    // Rule updates apply without replacing the resolver
+    replace_rules(selection.rules(), vec![app_rule("chat", "slack")]).unwrap();
+    assert_eq!(
+        selection.resolve(&window("Slack", "")).as_deref(),
+        Some("chat")
+    );
+}
+
+#[test]
+fn test_swapping_resolver_at_runtime() {
+    let selection = ProfileSelection::default();
+    replace_rules(selection.rules(), vec![app_rule("chat", "slack")]).unwrap();
+
+    selection.set_resolver(Box::new(FixedResolver::new("notes")));
+    assert_eq!(
+        selection.resolve(&window("Slack", "")).as_deref(),
+        Some("notes")
+    );
+
+    selection.set_resolver(Box::new(selection.rule_based()));
+    assert_eq!(
+        selection.resolve(&window("Slack", "")).as_deref(),
+        Some("chat")
+    );
+    assert_eq!(selection.resolve(&window("Code", "")), None);
+}
+
+#[test]
+fn test_explain_reports_matching_rule() {
+    let selection = ProfileSelection::default();
+    replace_rules(
+        selection.rules(),
+        vec![app_rule("chat", "slack"), app_rule("code", "code|slack")],
+    )
+    .unwrap();
+
+    let result = selection.explain(window("Slack", ""));
+    assert_eq!(result.window.app_name, "Slack");
+    assert_eq!(result.matched.as_deref(), Some("chat"));
+    assert_eq!(
+        result.matched_rule_index,
+        Some(0),
+        "first matching rule wins"
+    );
+
+    let result = selection.explain(window("Code", ""));
+    assert_eq!(result.matched.as_deref(), Some("code"));
+    assert_eq!(result.matched_rule_index, Some(1));
+}
+
+#[test]
+fn test_explain_without_matching_rule() {
+    let selection = ProfileSelection::default();
+    replace_rules(selection.rules(), vec![app_rule("chat", "slack")]).unwrap();
+
+    let result = selection.explain(window("Terminal", ""));
+    assert_eq!(result.matched, None);
+    assert_eq!(result.matched_rule_index, None);
+
+    // A fallback profile is reported without blaming a rule for it
+    selection.set_resolver(Box::new(ChainResolver::new(vec![
+        Box::new(selection.rule_based()),
+        Box::new(FixedResolver::new("default-fallback")),
+    ])));
+    let result = selection.explain(window("Terminal", ""));
+    assert_eq!(result.matched.as_deref(), Some("default-fallback"));
+    assert_eq!(result.matched_rule_index, None);
+}
+
+#[test]
+fn test_explain_ignores_rule_overridden_by_resolver() {
+    let selection = ProfileSelection::default();
+    replace_rules(selection.rules(), vec![app_rule("chat", "slack")]).unwrap();
+    selection.set_resolver(Box::new(FixedResolver::new("notes")));
+
+    let result = selection.explain(window("Slack", ""));
+    assert_eq!(result.matched.as_deref(), Some("notes"));
+    assert_eq!(result.matched_rule_index, None);
+}
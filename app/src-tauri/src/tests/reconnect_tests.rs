@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use super::FakeClock;
+use crate::reconnect::{ReconnectController, ReconnectStatus};
+
+fn controller() -> (ReconnectController<FakeClock>, FakeClock) {
+    let clock = FakeClock::new();
+    (ReconnectController::new(clock.clone()), clock)
+}
+
+#[test]
+fn test_controller_is_idle_until_retry_scheduled() {
+    let (controller, _clock) = controller();
+    assert!(!controller.is_active());
+    assert_eq!(
+        controller.status(),
+        ReconnectStatus {
+            active: false,
+            attempt: 0,
+            retry_in_ms: None,
+        }
+    );
+}
+
+#[test]
+fn test_status_counts_down_to_scheduled_attempt() {
+    let (mut controller, clock) = controller();
+    controller.retry_scheduled(4, Duration::from_secs(8));
+
+    clock.advance(Duration::from_secs(3));
+    assert_eq!(
+        controller.status(),
+        ReconnectStatus {
+            active: true,
+            attempt: 4,
+            retry_in_ms: Some(5000),
+        }
+    );
+
+    // Once the delay has elapsed the attempt is in flight
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(controller.status().retry_in_ms, None);
+    assert!(controller.status().active);
+    assert_eq!(controller.status().attempt, 4);
+}
+
+#[test]
+fn test_latest_schedule_replaces_previous_one() {
+    let (mut controller, clock) = controller();
+    controller.retry_scheduled(5, Duration::from_secs(30));
+    clock.advance(Duration::from_secs(10));
+
+    // A manual reconnect in the overlay restarts its count
+    controller.retry_scheduled(1, Duration::from_secs(2));
+    let status = controller.status();
+    assert_eq!(status.attempt, 1);
+    assert_eq!(status.retry_in_ms, Some(2000));
+}
+
+#[test]
+fn test_reset_clears_progress() {
+    let (mut controller, _clock) = controller();
+    controller.retry_scheduled(3, Duration::from_secs(8));
+    controller.reset();
+    assert!(!controller.is_active());
+    assert_eq!(controller.status().attempt, 0);
+}
@@ -0,0 +1,55 @@
+use crate::recording_id::{with_recording_id, RecordingIds};
+
+#[test]
+fn test_id_is_stable_across_start_and_stop() {
+    let mut ids = RecordingIds::default();
+    assert_eq!(ids.current(), None);
+
+    let started = ids.start();
+    // Stop (and the transcription after it) reads the current ID
+    assert_eq!(ids.current(), Some(started.as_str()));
+}
+
+#[test]
+fn test_toggle_recording_keeps_id_from_prepare() {
+    let mut ids = RecordingIds::default();
+    let prepared = ids.prepare();
+    assert_eq!(ids.start(), prepared);
+    assert_eq!(ids.current(), Some(prepared.as_str()));
+}
+
+#[test]
+fn test_each_recording_gets_a_new_id() {
+    let mut ids = RecordingIds::default();
+    let first = ids.start();
+    let second = ids.start();
+    assert_ne!(first, second);
+
+    let prepared = ids.prepare();
+    assert_ne!(prepared, second);
+    assert_eq!(ids.start(), prepared);
+    // The prepared ID is only used for the recording it was prepared for
+    assert_ne!(ids.start(), prepared);
+}
+
+#[test]
+fn test_discarded_id_is_not_current_or_reused() {
+    let mut ids = RecordingIds::default();
+    let prepared = ids.prepare();
+    ids.start();
+    ids.discard();
+    assert_eq!(ids.current(), None);
+    assert_ne!(ids.start(), prepared);
+}
+
+#[test]
+fn test_log_lines_are_prefixed_with_id() {
+    assert_eq!(
+        with_recording_id(Some("abc"), format_args!("Hold: {}", "starting recording")),
+        "[recording abc] Hold: starting recording"
+    );
+    assert_eq!(
+        with_recording_id(None, "Hold: starting recording"),
+        "Hold: starting recording"
+    );
+}
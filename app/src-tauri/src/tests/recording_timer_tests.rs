@@ -0,0 +1,115 @@
+use std::time::{Duration, Instant};
+
+use super::FakeClock;
+use crate::recording_timer::{duration_ms, RecordingTimer, StoppedRecording};
+
+fn timer() -> (RecordingTimer<FakeClock>, FakeClock) {
+    let clock = FakeClock::new();
+    (RecordingTimer::new(clock.clone()), clock)
+}
+
+#[test]
+fn test_start_stop_pair_yields_plausible_duration() {
+    let mut timer = RecordingTimer::default();
+    let before = Instant::now();
+    timer.start();
+    assert!(timer.is_running());
+
+    let duration = timer.stop().unwrap();
+    assert!(!timer.is_running());
+    assert!(duration <= before.elapsed());
+}
+
+#[test]
+fn test_duration_covers_time_between_start_and_stop() {
+    let (mut timer, clock) = timer();
+    timer.start();
+    clock.advance(Duration::from_millis(2350));
+
+    assert_eq!(timer.stop(), Some(Duration::from_millis(2350)));
+    assert_eq!(
+        timer.take_last_duration().map(duration_ms),
+        Some(2350),
+        "duration is kept for the history entry"
+    );
+}
+
+#[test]
+fn test_stop_without_start_has_no_duration() {
+    let (mut timer, _) = timer();
+    assert_eq!(timer.stop(), None);
+    assert_eq!(timer.take_last_duration(), None);
+}
+
+#[test]
+fn test_last_duration_is_taken_once() {
+    let (mut timer, clock) = timer();
+    timer.start();
+    clock.advance(Duration::from_secs(1));
+    timer.stop();
+
+    assert_eq!(timer.take_last_duration(), Some(Duration::from_secs(1)));
+    assert_eq!(timer.take_last_duration(), None);
+}
+
+#[test]
+fn test_new_recording_discards_untaken_duration() {
+    let (mut timer, clock) = timer();
+    timer.start();
+    clock.advance(Duration::from_secs(3));
+    timer.stop();
+
+    // The next recording starts before the previous one produced a history entry
+    timer.start();
+    assert_eq!(timer.take_last_duration(), None);
+    clock.advance(Duration::from_millis(500));
+    assert_eq!(timer.stop(), Some(Duration::from_millis(500)));
+}
+
+#[test]
+fn test_recording_below_min_length_is_discarded() {
+    let (mut timer, clock) = timer();
+    timer.start();
+    clock.advance(Duration::from_millis(120));
+
+    assert_eq!(
+        timer.stop_with_min_length(Duration::from_millis(300)),
+        Some(StoppedRecording::TooShort(Duration::from_millis(120)))
+    );
+    assert!(!timer.is_running());
+    assert_eq!(
+        timer.take_last_duration(),
+        None,
+        "a discarded recording leaves nothing for a history entry"
+    );
+}
+
+#[test]
+fn test_recording_at_or_above_min_length_is_kept() {
+    let (mut timer, clock) = timer();
+    timer.start();
+    clock.advance(Duration::from_millis(300));
+
+    assert_eq!(
+        timer.stop_with_min_length(Duration::from_millis(300)),
+        Some(StoppedRecording::Kept(Duration::from_millis(300)))
+    );
+    assert_eq!(timer.take_last_duration().map(duration_ms), Some(300));
+}
+
+#[test]
+fn test_zero_min_length_keeps_every_recording() {
+    let (mut timer, _clock) = timer();
+    timer.start();
+
+    assert_eq!(
+        timer.stop_with_min_length(Duration::ZERO),
+        Some(StoppedRecording::Kept(Duration::ZERO))
+    );
+}
+
+#[test]
+fn test_stop_with_min_length_without_start_is_none() {
+    let (mut timer, _clock) = timer();
+    assert_eq!(timer.stop_with_min_length(Duration::from_millis(300)), None);
+}
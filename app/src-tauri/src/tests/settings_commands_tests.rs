@@ -1,4 +1,7 @@
-use crate::settings::HotkeyConfig;
+use crate::settings::{
+    resolve_prompt_sections, AppSettings, CleanupPromptSections, HotkeyConfig, PromptMode,
+    PromptSection, PromptSectionOverrides,
+};
 
 // Tests for HotkeyConfig
 #[test]
@@ -45,3 +48,99 @@ fn test_default_hotkeys_are_enabled() {
     assert!(HotkeyConfig::default_hold().enabled);
     assert!(HotkeyConfig::default_paste_last().enabled);
 }
+
+fn manual(content: &str) -> PromptSection {
+    PromptSection {
+        enabled: true,
+        prompt_mode: PromptMode::Manual {
+            content: content.to_string(),
+        },
+    }
+}
+
+/// Settings with a custom main prompt and overrides for "chat" and "email"
+fn settings_with_overrides() -> AppSettings {
+    let mut settings = AppSettings {
+        cleanup_prompt_sections: Some(CleanupPromptSections {
+            main: manual("Clean up the dictation."),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    settings.profile_prompt_overrides.insert(
+        "chat".to_string(),
+        PromptSectionOverrides {
+            main: Some(manual("Keep it short and casual.")),
+            ..Default::default()
+        },
+    );
+    settings.profile_prompt_overrides.insert(
+        "email".to_string(),
+        PromptSectionOverrides {
+            main: Some(manual("Write in a formal tone.")),
+            dictionary: Some(PromptSection {
+                enabled: false,
+                prompt_mode: PromptMode::Auto,
+            }),
+            ..Default::default()
+        },
+    );
+    settings
+}
+
+#[test]
+fn test_profile_overrides_replace_only_their_sections() {
+    let settings = settings_with_overrides();
+    let base = settings.cleanup_prompt_sections.clone().unwrap();
+
+    let chat = resolve_prompt_sections(Some("chat"), &settings);
+    assert_eq!(chat.main, manual("Keep it short and casual."));
+    assert_eq!(chat.advanced, base.advanced);
+    assert_eq!(chat.dictionary, base.dictionary);
+
+    let email = resolve_prompt_sections(Some("email"), &settings);
+    assert_eq!(email.main, manual("Write in a formal tone."));
+    assert_eq!(email.advanced, base.advanced);
+    assert!(!email.dictionary.enabled);
+}
+
+#[test]
+fn test_resolve_falls_back_to_base_sections() {
+    let settings = settings_with_overrides();
+    let base = settings.cleanup_prompt_sections.clone().unwrap();
+
+    assert_eq!(resolve_prompt_sections(None, &settings), base);
+    assert_eq!(resolve_prompt_sections(Some("code"), &settings), base);
+}
+
+#[test]
+fn test_resolve_without_base_sections_uses_defaults() {
+    let mut settings = settings_with_overrides();
+    settings.cleanup_prompt_sections = None;
+
+    assert_eq!(
+        resolve_prompt_sections(None, &settings),
+        CleanupPromptSections::default()
+    );
+    let chat = resolve_prompt_sections(Some("chat"), &settings);
+    assert_eq!(chat.main, manual("Keep it short and casual."));
+    assert_eq!(chat.advanced, CleanupPromptSections::default().advanced);
+}
+
+#[test]
+fn test_full_sections_are_a_valid_override() {
+    let full = serde_json::to_value(CleanupPromptSections::default()).unwrap();
+    let overrides: PromptSectionOverrides = serde_json::from_value(full).unwrap();
+    assert!(overrides.main.is_some() && overrides.advanced.is_some());
+    assert!(overrides.dictionary.is_some());
+
+    let partial: PromptSectionOverrides = serde_json::from_value(
+        serde_json::json!({ "main": { "enabled": false, "mode": { "mode": "auto" } } }),
+    )
+    .unwrap();
+    assert!(partial.advanced.is_none());
+    assert_eq!(
+        serde_json::to_value(&partial).unwrap(),
+        serde_json::json!({ "main": { "enabled": false, "mode": { "mode": "auto" } } })
+    );
+}
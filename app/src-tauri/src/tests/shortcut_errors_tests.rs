@@ -0,0 +1,109 @@
+use crate::settings::HotkeyType;
+use crate::state::{ShortcutError, ShortcutErrorKind, ShortcutErrors};
+
+// Tests for ShortcutErrorKind::from_backend_message()
+#[test]
+fn test_error_kind_already_registered() {
+    assert_eq!(
+        ShortcutErrorKind::from_backend_message(
+            "HotKey { mods: CONTROL, key: Space } already registered"
+        ),
+        ShortcutErrorKind::AlreadyRegistered
+    );
+}
+
+#[test]
+fn test_error_kind_parse_failed() {
+    assert_eq!(
+        ShortcutErrorKind::from_backend_message(
+            "Couldn't recognize \"Foo\" as a valid key for hotkey, failed to parse"
+        ),
+        ShortcutErrorKind::ParseFailed
+    );
+}
+
+#[test]
+fn test_error_kind_os_rejected() {
+    assert_eq!(
+        ShortcutErrorKind::from_backend_message("Failed to register hotkey: OS error 1409"),
+        ShortcutErrorKind::OsRejected
+    );
+}
+
+// Tests for ShortcutError construction
+#[test]
+fn test_shortcut_error_parse_failed() {
+    let error = ShortcutError::new("ctrl+shift+Foo", ShortcutErrorKind::ParseFailed);
+    assert_eq!(error.accelerator, "ctrl+shift+Foo");
+    assert_eq!(error.kind, ShortcutErrorKind::ParseFailed);
+    assert!(error.message.contains("not a valid shortcut"));
+}
+
+#[test]
+fn test_shortcut_error_already_registered() {
+    let error = ShortcutError::new("ctrl+shift+Space", ShortcutErrorKind::AlreadyRegistered);
+    assert_eq!(error.kind, ShortcutErrorKind::AlreadyRegistered);
+    assert_eq!(
+        error.message,
+        "ctrl+shift+Space is already in use by another shortcut"
+    );
+}
+
+#[test]
+fn test_shortcut_error_os_rejected() {
+    let error = ShortcutError::new("ctrl+alt+Space", ShortcutErrorKind::OsRejected);
+    assert_eq!(error.accelerator, "ctrl+alt+Space");
+    assert_eq!(error.kind, ShortcutErrorKind::OsRejected);
+}
+
+#[test]
+fn test_shortcut_error_serialization() {
+    let error = ShortcutError::new("ctrl+alt+Space", ShortcutErrorKind::AlreadyRegistered);
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(json["accelerator"], "ctrl+alt+Space");
+    assert_eq!(json["kind"], "already_registered");
+}
+
+// Tests for ShortcutErrors::has_any_error()
+#[test]
+fn test_has_any_error_empty() {
+    assert!(!ShortcutErrors::default().has_any_error());
+}
+
+#[test]
+fn test_has_any_error_with_each_kind() {
+    for kind in [
+        ShortcutErrorKind::ParseFailed,
+        ShortcutErrorKind::AlreadyRegistered,
+        ShortcutErrorKind::OsRejected,
+    ] {
+        let errors = ShortcutErrors {
+            hold_error: Some(ShortcutError::new("ctrl+alt+Backquote", kind)),
+            ..Default::default()
+        };
+        assert!(errors.has_any_error());
+    }
+}
+
+#[test]
+fn test_set_replaces_only_that_hotkeys_error() {
+    let mut errors = ShortcutErrors {
+        toggle_error: Some(ShortcutError::new(
+            "ctrl+Space",
+            ShortcutErrorKind::OsRejected,
+        )),
+        ..Default::default()
+    };
+    errors.set(
+        HotkeyType::Hold,
+        Some(ShortcutError::new(
+            "ctrl+alt+Space",
+            ShortcutErrorKind::OsRejected,
+        )),
+    );
+    errors.set(HotkeyType::Toggle, None);
+
+    assert!(errors.toggle_error.is_none());
+    assert_eq!(errors.hold_error.unwrap().accelerator, "ctrl+alt+Space");
+    assert!(errors.paste_last_error.is_none());
+}
@@ -0,0 +1,126 @@
+use std::cell::{Cell, RefCell};
+use std::time::Duration;
+
+use crate::shortcut_registrar::{register_with_retry, RetryPolicy, ShortcutRegistrar};
+use crate::state::{ShortcutError, ShortcutErrorKind};
+
+/// Registrar that fails with the given kind for the first `failures` calls, then succeeds
+struct MockRegistrar {
+    failures: u32,
+    kind: ShortcutErrorKind,
+    calls: Cell<u32>,
+}
+
+impl MockRegistrar {
+    fn failing(failures: u32, kind: ShortcutErrorKind) -> Self {
+        Self {
+            failures,
+            kind,
+            calls: Cell::new(0),
+        }
+    }
+}
+
+impl ShortcutRegistrar for MockRegistrar {
+    fn register(&self, accelerator: &str) -> Result<(), ShortcutError> {
+        self.calls.set(self.calls.get() + 1);
+        if self.calls.get() <= self.failures {
+            Err(ShortcutError::new(accelerator, self.kind))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn no_sleep(_: Duration) {}
+
+#[test]
+fn test_retry_succeeds_after_one_os_rejection() {
+    let registrar = MockRegistrar::failing(1, ShortcutErrorKind::OsRejected);
+    let attempts = RefCell::new(Vec::new());
+
+    let result = register_with_retry(
+        &registrar,
+        "ctrl+alt+Space",
+        &RetryPolicy::default(),
+        no_sleep,
+        |attempt, result| attempts.borrow_mut().push((attempt, result.is_ok())),
+    );
+
+    assert!(result.is_ok());
+    assert_eq!(registrar.calls.get(), 2);
+    assert_eq!(*attempts.borrow(), vec![(1, false), (2, true)]);
+}
+
+#[test]
+fn test_retry_gives_up_after_max_attempts() {
+    let registrar = MockRegistrar::failing(u32::MAX, ShortcutErrorKind::OsRejected);
+
+    let result = register_with_retry(
+        &registrar,
+        "ctrl+alt+Space",
+        &RetryPolicy::default(),
+        no_sleep,
+        |_, _| {},
+    );
+
+    let error = result.unwrap_err();
+    assert_eq!(error.kind, ShortcutErrorKind::OsRejected);
+    assert_eq!(error.accelerator, "ctrl+alt+Space");
+    assert_eq!(registrar.calls.get(), 3);
+}
+
+#[test]
+fn test_retry_does_not_retry_parse_failures() {
+    let registrar = MockRegistrar::failing(1, ShortcutErrorKind::ParseFailed);
+
+    let result = register_with_retry(
+        &registrar,
+        "ctrl+alt+Nope",
+        &RetryPolicy::default(),
+        no_sleep,
+        |_, _| {},
+    );
+
+    assert_eq!(result.unwrap_err().kind, ShortcutErrorKind::ParseFailed);
+    assert_eq!(registrar.calls.get(), 1);
+}
+
+#[test]
+fn test_retry_does_not_retry_already_registered() {
+    let registrar = MockRegistrar::failing(1, ShortcutErrorKind::AlreadyRegistered);
+
+    let result = register_with_retry(
+        &registrar,
+        "ctrl+alt+Space",
+        &RetryPolicy::default(),
+        no_sleep,
+        |_, _| {},
+    );
+
+    assert!(result.is_err());
+    assert_eq!(registrar.calls.get(), 1);
+}
+
+#[test]
+fn test_retry_sleeps_with_increasing_delay() {
+    let registrar = MockRegistrar::failing(u32::MAX, ShortcutErrorKind::OsRejected);
+    let delays = RefCell::new(Vec::new());
+    let policy = RetryPolicy {
+        max_attempts: 3,
+        base_delay: Duration::from_millis(100),
+    };
+
+    let _ = register_with_retry(
+        &registrar,
+        "ctrl+alt+Space",
+        &policy,
+        |d| delays.borrow_mut().push(d),
+        |_, _| {},
+    );
+
+    assert_eq!(
+        *delays.borrow(),
+        vec![Duration::from_millis(100), Duration::from_millis(200)]
+    );
+}
@@ -0,0 +1,184 @@
+use std::sync::mpsc;
+use std::time::Duration;
+
+use super::{window, FakeClock};
+use crate::active_window::ActiveWindowInfo;
+use crate::window_watcher::{watch_with_source, TitleChanges, WindowChangeDetector};
+
+/// Title changes reported as soon as they are seen
+const IMMEDIATE_TITLES: TitleChanges = TitleChanges {
+    enabled: true,
+    debounce: Duration::ZERO,
+};
+
+/// Feed a sequence of windows through a detector, returning the reported ones
+fn reported(debounce_polls: u32, sequence: &[ActiveWindowInfo]) -> Vec<String> {
+    let mut detector =
+        WindowChangeDetector::new(debounce_polls).with_title_changes(IMMEDIATE_TITLES);
+    sequence
+        .iter()
+        .filter(|info| detector.observe(info))
+        .map(|info| format!("{}: {}", info.app_name, info.window_title))
+        .collect()
+}
+
+#[test]
+fn test_detector_reports_only_changes() {
+    let sequence = [
+        window("Slack", "general"),
+        window("Slack", "general"),
+        window("Code", "main.rs"),
+        window("Code", "main.rs"),
+        window("Code", "lib.rs"),
+        window("Slack", "general"),
+    ];
+    assert_eq!(
+        reported(1, &sequence),
+        vec![
+            "Slack: general",
+            "Code: main.rs",
+            "Code: lib.rs",
+            "Slack: general"
+        ]
+    );
+}
+
+#[test]
+fn test_detector_ignores_windows_that_flash_by() {
+    let sequence = [
+        window("Slack", "general"),
+        window("Slack", "general"),
+        // Alt-tab passes over Finder before settling on Code
+        window("Finder", "Downloads"),
+        window("Code", "main.rs"),
+        window("Code", "main.rs"),
+        // Briefly switching away and back is not a change
+        window("Mail", "Inbox"),
+        window("Code", "main.rs"),
+    ];
+    assert_eq!(
+        reported(2, &sequence),
+        vec!["Slack: general", "Code: main.rs"]
+    );
+}
+
+#[test]
+fn test_detector_treats_zero_debounce_as_one() {
+    let sequence = [window("Slack", "general")];
+    assert_eq!(reported(0, &sequence), vec!["Slack: general"]);
+}
+
+/// Poll `sequence` once per second through a detector on a fake clock,
+/// returning the reported windows
+fn reported_over_time(title_changes: TitleChanges, sequence: &[ActiveWindowInfo]) -> Vec<String> {
+    let clock = FakeClock::new();
+    let mut detector =
+        WindowChangeDetector::with_clock(1, clock.clone()).with_title_changes(title_changes);
+    sequence
+        .iter()
+        .filter(|info| {
+            let report = detector.observe(info);
+            clock.advance(Duration::from_secs(1));
+            report
+        })
+        .map(|info| format!("{}: {}", info.app_name, info.window_title))
+        .collect()
+}
+
+/// A browser downloading a file (title updates every second), then a chat app
+fn noisy_title_stream() -> Vec<ActiveWindowInfo> {
+    let mut sequence: Vec<_> = (0..=10)
+        .map(|percent| {
+            window(
+                "Firefox",
+                &format!("Downloading {}% - Firefox", percent * 10),
+            )
+        })
+        .collect();
+    sequence.extend([
+        window("Firefox", "Download complete - Firefox"),
+        window("Firefox", "Download complete - Firefox"),
+        window("Firefox", "Download complete - Firefox"),
+        window("Slack", "general"),
+    ]);
+    sequence
+}
+
+#[test]
+fn test_detector_coalesces_title_churn() {
+    let sequence = noisy_title_stream();
+    assert_eq!(
+        reported_over_time(IMMEDIATE_TITLES, &sequence).len(),
+        sequence.len() - 2
+    );
+
+    let title_changes = TitleChanges {
+        enabled: true,
+        debounce: Duration::from_secs(2),
+    };
+    assert_eq!(
+        reported_over_time(title_changes, &sequence),
+        vec![
+            "Firefox: Downloading 0% - Firefox",
+            "Firefox: Download complete - Firefox",
+            "Slack: general"
+        ]
+    );
+}
+
+#[test]
+fn test_detector_can_ignore_title_changes() {
+    let title_changes = TitleChanges {
+        enabled: false,
+        debounce: Duration::ZERO,
+    };
+    assert_eq!(
+        reported_over_time(title_changes, &noisy_title_stream()),
+        vec!["Firefox: Downloading 0% - Firefox", "Slack: general"]
+    );
+}
+
+#[test]
+fn test_detector_reports_app_switch_without_title_debounce() {
+    let title_changes = TitleChanges {
+        enabled: true,
+        debounce: Duration::from_secs(30),
+    };
+    let sequence = [
+        window("Slack", "general"),
+        window("Code", "main.rs"),
+        window("Slack", "random"),
+    ];
+    assert_eq!(
+        reported_over_time(title_changes, &sequence),
+        vec!["Slack: general", "Code: main.rs", "Slack: random"]
+    );
+}
+
+#[test]
+fn test_watcher_calls_back_on_changes_and_stops() {
+    let mut sequence = vec![
+        window("Slack", "general"),
+        window("Slack", "general"),
+        window("Code", "main.rs"),
+    ]
+    .into_iter();
+    let (tx, rx) = mpsc::channel();
+
+    let handle = watch_with_source(
+        Duration::from_millis(1),
+        WindowChangeDetector::new(1),
+        move || sequence.next(),
+        move |info| {
+            let _ = tx.send(info.app_name);
+        },
+    );
+
+    let timeout = Duration::from_secs(5);
+    assert_eq!(rx.recv_timeout(timeout).unwrap(), "Slack");
+    assert_eq!(rx.recv_timeout(timeout).unwrap(), "Code");
+
+    handle.stop();
+    // The callback (and its sender) are dropped once the thread exits
+    assert!(rx.recv_timeout(timeout).is_err());
+}
@@ -0,0 +1,224 @@
+//! Foreground window change notifications.
+//!
+//! Polls the active window on a dedicated thread and reports only meaningful
+//! changes, so profiles can switch while a session is running instead of only
+//! at record time.
+//!
+//! Switching to another app is reported once it has been seen for a few
+//! polls. Titles churn a lot more (progress bars, clocks, unread counts), so a
+//! title change within the same app is only reported once the new title has
+//! stayed the same for `TitleChanges::debounce`, or not at all.
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::active_window::{get_active_window_cached, ActiveWindowInfo};
+use crate::clock::{Clock, SystemClock};
+
+/// Number of consecutive polls a new app must be seen for before it is reported.
+/// Filters out windows that only flash by (e.g. while alt-tabbing).
+pub const DEFAULT_DEBOUNCE_POLLS: u32 = 2;
+
+/// How long a new window title must stay unchanged before it is reported
+pub const DEFAULT_TITLE_DEBOUNCE: Duration = Duration::from_millis(1500);
+
+/// Whether title changes within the same app are reported, and how long a
+/// new title has to be stable first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TitleChanges {
+    pub enabled: bool,
+    pub debounce: Duration,
+}
+
+impl Default for TitleChanges {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            debounce: DEFAULT_TITLE_DEBOUNCE,
+        }
+    }
+}
+
+/// The parts of a window that identify its app
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AppKey {
+    app_name: String,
+    bundle_id: Option<String>,
+}
+
+impl From<&ActiveWindowInfo> for AppKey {
+    fn from(info: &ActiveWindowInfo) -> Self {
+        Self {
+            app_name: info.app_name.clone(),
+            bundle_id: info.bundle_id.clone(),
+        }
+    }
+}
+
+/// Decides which polled windows are reported as changes
+#[derive(Debug)]
+pub struct WindowChangeDetector<C: Clock = SystemClock> {
+    clock: C,
+    debounce_polls: u32,
+    title_changes: TitleChanges,
+    last_reported: Option<(AppKey, String)>,
+    /// Another app, and for how many consecutive polls it has been seen
+    pending_app: Option<(AppKey, u32)>,
+    /// A new title in the reported app, and when it first appeared
+    pending_title: Option<(String, Instant)>,
+}
+
+impl WindowChangeDetector {
+    pub fn new(debounce_polls: u32) -> Self {
+        Self::with_clock(debounce_polls, SystemClock)
+    }
+}
+
+impl<C: Clock> WindowChangeDetector<C> {
+    pub fn with_clock(debounce_polls: u32, clock: C) -> Self {
+        Self {
+            clock,
+            debounce_polls: debounce_polls.max(1),
+            title_changes: TitleChanges::default(),
+            last_reported: None,
+            pending_app: None,
+            pending_title: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_title_changes(mut self, title_changes: TitleChanges) -> Self {
+        self.title_changes = title_changes;
+        self
+    }
+
+    /// Feed one poll result. Returns true if this window should be reported.
+    pub fn observe(&mut self, info: &ActiveWindowInfo) -> bool {
+        let app = AppKey::from(info);
+        match self.last_reported {
+            Some((ref last_app, ref last_title)) if *last_app == app => {
+                self.pending_app = None;
+                if !self.title_changes.enabled || *last_title == info.window_title {
+                    self.pending_title = None;
+                    return false;
+                }
+                self.observe_title(app, &info.window_title)
+            }
+            _ => self.observe_app(app, &info.window_title),
+        }
+    }
+
+    fn observe_app(&mut self, app: AppKey, title: &str) -> bool {
+        let seen = match self.pending_app {
+            Some((ref pending, count)) if *pending == app => count + 1,
+            _ => 1,
+        };
+        if seen >= self.debounce_polls {
+            self.report(app, title);
+            true
+        } else {
+            self.pending_app = Some((app, seen));
+            false
+        }
+    }
+
+    fn observe_title(&mut self, app: AppKey, title: &str) -> bool {
+        let now = self.clock.now();
+        let since = match self.pending_title {
+            Some((ref pending, since)) if pending == title => since,
+            _ => {
+                self.pending_title = Some((title.to_string(), now));
+                now
+            }
+        };
+        if now.saturating_duration_since(since) >= self.title_changes.debounce {
+            self.report(app, title);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn report(&mut self, app: AppKey, title: &str) {
+        self.last_reported = Some((app, title.to_string()));
+        self.pending_app = None;
+        self.pending_title = None;
+    }
+}
+
+/// Stops the watcher thread when stopped or dropped
+pub struct WindowWatcherHandle {
+    stop: Arc<(Mutex<bool>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl WindowWatcherHandle {
+    /// Stop watching and wait for the thread to exit
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        let (stopped, wake) = &*self.stop;
+        *stopped.lock().unwrap() = true;
+        wake.notify_all();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for WindowWatcherHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Watch the foreground window, calling `callback` whenever it meaningfully changes.
+pub fn watch_active_window(
+    interval: Duration,
+    title_changes: TitleChanges,
+    callback: impl Fn(ActiveWindowInfo) + Send + 'static,
+) -> WindowWatcherHandle {
+    let detector =
+        WindowChangeDetector::new(DEFAULT_DEBOUNCE_POLLS).with_title_changes(title_changes);
+    watch_with_source(interval, detector, get_active_window_cached, callback)
+}
+
+/// Watch windows produced by `source`, polled every `interval` and filtered by `detector`.
+/// Polls where no window could be detected are skipped.
+pub fn watch_with_source(
+    interval: Duration,
+    mut detector: WindowChangeDetector,
+    mut source: impl FnMut() -> Option<ActiveWindowInfo> + Send + 'static,
+    callback: impl Fn(ActiveWindowInfo) + Send + 'static,
+) -> WindowWatcherHandle {
+    let stop = Arc::new((Mutex::new(false), Condvar::new()));
+    let thread_stop = Arc::clone(&stop);
+
+    let thread = std::thread::spawn(move || {
+        let (stopped, wake) = &*thread_stop;
+        loop {
+            if let Some(info) = source() {
+                if detector.observe(&info) {
+                    callback(info);
+                }
+            }
+
+            // Sleep until the next poll, waking early if stopped
+            let guard = stopped.lock().unwrap();
+            let (guard, _) = wake
+                .wait_timeout_while(guard, interval, |stopped| !*stopped)
+                .unwrap();
+            if *guard {
+                break;
+            }
+        }
+    });
+
+    WindowWatcherHandle {
+        stop,
+        thread: Some(thread),
+    }
+}
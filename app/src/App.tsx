@@ -44,6 +44,8 @@ function ConnectionStatusIndicator() {
 			unlisten = await tauriAPI.onConnectionStateChanged((newState) => {
 				setState(newState);
 			});
+			// The overlay may have connected before this window subscribed
+			await tauriAPI.requestConnectionState();
 		};
 
 		setup();
@@ -183,7 +185,7 @@ function InstructionsCard() {
 					<span className="instruction-label">Toggle:</span>
 					<HotkeyDisplay
 						config={toggleHotkey}
-						error={shortcutErrors?.toggle_error}
+						error={shortcutErrors?.toggle_error?.message}
 					/>
 					<span className="instruction-desc">Press to start/stop</span>
 				</div>
@@ -191,7 +193,7 @@ function InstructionsCard() {
 					<span className="instruction-label">Hold:</span>
 					<HotkeyDisplay
 						config={holdHotkey}
-						error={shortcutErrors?.hold_error}
+						error={shortcutErrors?.hold_error?.message}
 					/>
 					<span className="instruction-desc">Hold to record</span>
 				</div>
@@ -199,7 +201,7 @@ function InstructionsCard() {
 					<span className="instruction-label">Paste:</span>
 					<HotkeyDisplay
 						config={pasteLastHotkey}
-						error={shortcutErrors?.paste_last_error}
+						error={shortcutErrors?.paste_last_error?.message}
 					/>
 					<span className="instruction-desc">Paste last result</span>
 				</div>
@@ -258,12 +260,14 @@ type SettingKey =
 	| "stt-provider"
 	| "llm-provider"
 	| "prompt-sections"
+	| "profile-prompts"
 	| "stt-timeout";
 
 const SETTING_DISPLAY_NAMES: Record<SettingKey, string> = {
 	"stt-provider": "STT provider",
 	"llm-provider": "LLM provider",
 	"prompt-sections": "Formatting prompt",
+	"profile-prompts": "Profile prompts",
 	"stt-timeout": "STT timeout",
 };
 
@@ -32,6 +32,7 @@ import {
 	useConnectionClient,
 	useConnectionSend,
 	useConnectionState,
+	useDisconnectReason,
 } from "./contexts/ConnectionContext";
 import { useNativeAudioTrack } from "./hooks/useNativeAudioTrack";
 import { useAddHistoryEntry, useSettings, useTypeText } from "./lib/queries";
@@ -231,6 +232,7 @@ function RecordingControl() {
 	const client = usePipecatClient();
 	const queryClient = useQueryClient();
 	const connectionState = useConnectionState();
+	const disconnectReason = useDisconnectReason();
 	const send = useConnectionSend();
 	const displayState = getDisplayState(connectionState);
 
@@ -245,6 +247,10 @@ function RecordingControl() {
 	// Track the last mic device ID used for capture
 	// undefined = never started, null = system default, string = specific device
 	const lastMicIdRef = useRef<string | null | undefined>(undefined);
+	// start-recording held back until the recording is long enough to keep
+	const pendingServerStartRef = useRef<ReturnType<typeof setTimeout> | null>(
+		null,
+	);
 
 	// Native audio capture for low-latency mic acquisition
 	// Bypasses browser's getUserMedia() which has ~300-400ms latency on macOS
@@ -317,15 +323,25 @@ function RecordingControl() {
 					const serverUrl = await tauriAPI.getServerUrl();
 					const clientUUID = await tauriAPI.getClientUUID();
 					if (serverUrl && clientUUID) {
-						await configAPI.updateAppContext(serverUrl, clientUUID, {
-							app_name: windowInfo.app_name,
-							bundle_id: windowInfo.bundle_id,
-							window_title: windowInfo.window_title,
-							url: windowInfo.url,
-						});
+						await configAPI.updateAppContext(
+							serverUrl,
+							clientUUID,
+							{
+								app_name: windowInfo.app_name,
+								bundle_id: windowInfo.bundle_id,
+								window_title: windowInfo.window_title,
+								url: windowInfo.url,
+								profile_id: payload.profile_id,
+								prompt_sections: payload.prompt_sections,
+							},
+							payload.recording_id,
+						);
 					}
 				} catch (error) {
-					console.warn("[Recording] Failed to set app context:", error);
+					console.warn(
+						`[Recording ${payload.recording_id}] Failed to set app context:`,
+						error,
+					);
 				}
 			}
 
@@ -391,9 +407,24 @@ function RecordingControl() {
 					// Signal server to start turn management
 					// This is required for server-side buffer management and turn detection
 					// Use safe send to detect communication failures and trigger reconnection
-					safeSendClientMessage(client, "start-recording", {}, (error) =>
-						send({ type: "COMMUNICATION_ERROR", error }),
-					);
+					const sendServerStart = () => {
+						pendingServerStartRef.current = null;
+						safeSendClientMessage(client, "start-recording", {}, (error) =>
+							send({ type: "COMMUNICATION_ERROR", error }),
+						);
+					};
+					// Recordings shorter than min_recording_ms are discarded, so the
+					// server only hears about a recording once it's long enough to keep.
+					// Audio sent before then is still transcribed by the server.
+					const minRecordingMs = settings?.min_recording_ms ?? 0;
+					if (minRecordingMs > 0) {
+						pendingServerStartRef.current = setTimeout(
+							sendServerStart,
+							minRecordingMs,
+						);
+					} else {
+						sendServerStart();
+					}
 				}
 			} catch (error) {
 				console.warn("[Recording] Failed to start recording:", error);
@@ -404,6 +435,7 @@ function RecordingControl() {
 		[
 			client,
 			settings?.selected_mic_id,
+			settings?.min_recording_ms,
 			isNativeAudioReady,
 			nativeAudioTrack,
 			startNativeCapture,
@@ -411,7 +443,7 @@ function RecordingControl() {
 		],
 	);
 
-	const onStopRecording = useCallback(() => {
+	const releaseMic = useCallback(() => {
 		// Stop native audio capture and reset state so next recording starts fresh
 		stopNativeCapture();
 		lastMicIdRef.current = undefined;
@@ -453,6 +485,22 @@ function RecordingControl() {
 				console.warn("[Recording] Failed to stop audio track:", error);
 			}
 		}
+	}, [client, stopNativeCapture]);
+
+	// Cancel a held-back start-recording, returning whether one was pending
+	const cancelPendingServerStart = useCallback(() => {
+		if (pendingServerStartRef.current === null) {
+			return false;
+		}
+		clearTimeout(pendingServerStartRef.current);
+		pendingServerStartRef.current = null;
+		return true;
+	}, []);
+
+	useEffect(() => cancelPendingServerStart, [cancelPendingServerStart]);
+
+	const onStopRecording = useCallback(() => {
+		releaseMic();
 
 		// Only do state transitions and server signaling if we were actually recording
 		if (client && displayState === "recording") {
@@ -460,6 +508,14 @@ function RecordingControl() {
 			send({ type: "STOP_RECORDING" });
 			startResponseTimeout();
 
+			// The minimum length was measured from slightly earlier than our timer,
+			// so the held-back start may still be pending
+			if (cancelPendingServerStart()) {
+				safeSendClientMessage(client, "start-recording", {}, (error) =>
+					send({ type: "COMMUNICATION_ERROR", error }),
+				);
+			}
+
 			// Signal server to process the recorded audio
 			// This is required for server-side turn completion
 			// Use safe send to detect communication failures and trigger reconnection
@@ -467,28 +523,60 @@ function RecordingControl() {
 				send({ type: "COMMUNICATION_ERROR", error }),
 			);
 		}
-	}, [client, displayState, stopNativeCapture, send, startResponseTimeout]);
+	}, [
+		client,
+		displayState,
+		releaseMic,
+		send,
+		startResponseTimeout,
+		cancelPendingServerStart,
+	]);
+
+	// A recording shorter than min_recording_ms: release the mic like a normal
+	// stop, but skip transcription. The server was never sent start-recording
+	// for it, so it stays idle and ignores the audio.
+	const onRecordingDiscarded = useCallback(() => {
+		releaseMic();
+		const serverStarted = !cancelPendingServerStart();
+
+		if (displayState === "recording") {
+			send({ type: "DISCARD_RECORDING" });
+
+			// Our timer fired before the discard arrived: end the server's turn so
+			// it doesn't stay recording into the next dictation
+			if (client && serverStarted) {
+				safeSendClientMessage(client, "stop-recording", {}, (error) =>
+					send({ type: "COMMUNICATION_ERROR", error }),
+				);
+			}
+		}
+	}, [client, displayState, releaseMic, send, cancelPendingServerStart]);
 
 	useEffect(() => {
 		let isCancelled = false;
 		let unlistenStart: (() => void) | undefined;
 		let unlistenStop: (() => void) | undefined;
+		let unlistenDiscarded: (() => void) | undefined;
 
 		const setup = async () => {
-			const [startUnlisten, stopUnlisten] = await Promise.all([
-				tauriAPI.onStartRecording(onStartRecording),
-				tauriAPI.onStopRecording(onStopRecording),
-			]);
+			const [startUnlisten, stopUnlisten, discardedUnlisten] =
+				await Promise.all([
+					tauriAPI.onStartRecording(onStartRecording),
+					tauriAPI.onStopRecording(onStopRecording),
+					tauriAPI.onRecordingDiscarded(onRecordingDiscarded),
+				]);
 
 			// If cancelled before setup completed, clean up immediately
 			if (isCancelled) {
 				startUnlisten();
 				stopUnlisten();
+				discardedUnlisten();
 				return;
 			}
 
 			unlistenStart = startUnlisten;
 			unlistenStop = stopUnlisten;
+			unlistenDiscarded = discardedUnlisten;
 		};
 
 		setup();
@@ -497,8 +585,9 @@ function RecordingControl() {
 			isCancelled = true;
 			unlistenStart?.();
 			unlistenStop?.();
+			unlistenDiscarded?.();
 		};
-	}, [onStartRecording, onStopRecording]);
+	}, [onStartRecording, onStopRecording, onRecordingDiscarded]);
 
 	// Listen for prepare-recording event (toggle key press) to pre-warm microphone
 	// This reduces perceived latency by acquiring the mic while user holds the key
@@ -539,6 +628,29 @@ function RecordingControl() {
 		startNativeCapture,
 	]);
 
+	// A suppressed recording never starts, so release a pre-warmed mic
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			unlisten = await tauriAPI.onRecordingSuppressed(async (payload) => {
+				console.log(
+					`[Recording] Suppressed ${payload.scope} in disabled app ${payload.app_name}`,
+				);
+				if (payload.scope === "recording" && micPreparedRef.current) {
+					micPreparedRef.current = false;
+					await stopNativeCapture();
+				}
+			});
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, [stopNativeCapture]);
+
 	// Listen for translation trigger event from Rust
 	useEffect(() => {
 		let unlisten: (() => void) | undefined;
@@ -649,16 +761,10 @@ function RecordingControl() {
 		let unlisten: (() => void) | undefined;
 
 		const setup = async () => {
-			unlisten = await listen("request-disconnect", async () => {
+			unlisten = await listen("request-disconnect", () => {
 				console.log("[Pipecat] Received disconnect request from Rust");
-				if (client) {
-					try {
-						await client.disconnect();
-						console.log("[Pipecat] Disconnected gracefully");
-					} catch (error) {
-						console.error("[Pipecat] Disconnect error:", error);
-					}
-				}
+				// The machine disconnects the client and stops reconnecting
+				send({ type: "DISCONNECT" });
 			});
 		};
 
@@ -667,7 +773,7 @@ function RecordingControl() {
 		return () => {
 			unlisten?.();
 		};
-	}, [client]);
+	}, [send]);
 
 	// Cleanup on window close/beforeunload
 	useEffect(() => {
@@ -734,7 +840,7 @@ function RecordingControl() {
 			) {
 				if (hasInitialSyncRef.current) {
 					hasInitialSyncRef.current = false;
-					tauriAPI.setServerDisconnected();
+					tauriAPI.setServerDisconnected(disconnectReason ?? undefined);
 				}
 			}
 			return;
@@ -769,7 +875,14 @@ function RecordingControl() {
 
 		// Runtime provider changes are now handled via onProviderChangeRequest event
 		// (pessimistic updates - main window sends request, overlay sends to server)
-	}, [client, displayState, settings, buildConfigMessages, send]);
+	}, [
+		client,
+		displayState,
+		disconnectReason,
+		settings,
+		buildConfigMessages,
+		send,
+	]);
 
 	// Listen to native UserTranscript event for raw transcription
 	// RTVIObserver emits these automatically as user speaks
@@ -821,13 +934,20 @@ function RecordingControl() {
 					console.error("[Pipecat] Failed to type text:", error);
 				}
 				addHistoryEntry.mutate({ text, rawText });
+			} else if (settings?.store_empty_recordings) {
+				// Keep a record of the attempt (with its duration) even without text
+				addHistoryEntry.mutate({ text: "", rawText });
 			}
 			send({ type: "RESPONSE_RECEIVED" });
 			resetTranslationMode();
+			tauriAPI.scheduleOverlayAutoHide().catch((error) => {
+				console.warn("[Overlay] Failed to schedule auto-hide:", error);
+			});
 		}, [
 			clearResponseTimeout,
 			typeTextMutation,
 			addHistoryEntry,
+			settings?.store_empty_recordings,
 			send,
 			resetTranslationMode,
 		]),
@@ -11,14 +11,36 @@ import {
 	Trash2,
 } from "lucide-react";
 import { memo, useCallback, useEffect, useMemo, useState } from "react";
+import { match } from "ts-pattern";
 import {
 	useClearHistory,
 	useDeleteHistoryEntry,
 	useHistory,
 } from "../lib/queries";
-import type { HistoryEntry } from "../lib/tauri";
+import type { HistoryChangedPayload, HistoryEntry } from "../lib/tauri";
 import { tauriAPI } from "../lib/tauri";
 
+/**
+ * Apply a history change to a cached history list (newest first).
+ * Returns undefined when the list has to be refetched instead.
+ */
+function applyHistoryChange(
+	entries: HistoryEntry[],
+	change: HistoryChangedPayload,
+	limit: number | undefined,
+): HistoryEntry[] | undefined {
+	return match(change)
+		.with({ type: "added" }, { type: "updated" }, ({ entry }) =>
+			[entry, ...entries.filter((e) => e.id !== entry.id)].slice(0, limit),
+		)
+		.with({ type: "deleted" }, ({ ids }) =>
+			entries.filter((e) => !ids.includes(e.id)),
+		)
+		.with({ type: "cleared" }, () => [])
+		.with({ type: "reloaded" }, () => undefined)
+		.exhaustive();
+}
+
 function formatTime(timestamp: string): string {
 	return format(new Date(timestamp), "h:mm a");
 }
@@ -139,8 +161,20 @@ export function HistoryFeed() {
 		let unlisten: (() => void) | undefined;
 
 		const setup = async () => {
-			unlisten = await tauriAPI.onHistoryChanged(() => {
-				queryClient.invalidateQueries({ queryKey: ["history"] });
+			unlisten = await tauriAPI.onHistoryChanged((change) => {
+				const cached = queryClient.getQueriesData<HistoryEntry[]>({
+					queryKey: ["history"],
+				});
+				for (const [queryKey, entries] of cached) {
+					const limit = queryKey[1] as number | undefined;
+					const updated =
+						entries && applyHistoryChange(entries, change, limit);
+					if (updated) {
+						queryClient.setQueryData(queryKey, updated);
+					} else {
+						queryClient.invalidateQueries({ queryKey });
+					}
+				}
 			});
 		};
 
@@ -5,9 +5,14 @@ import { Check, Copy, RefreshCw, X } from "lucide-react";
 import { useCallback, useEffect, useState } from "react";
 import { match } from "ts-pattern";
 import { joinURL } from "ufo";
-import { useSettings, useUpdateServerUrl } from "../../lib/queries";
+import {
+	useSettings,
+	useUpdateApiBasePath,
+	useUpdateServerUrl,
+} from "../../lib/queries";
 import {
 	type ConnectionState,
+	DEFAULT_API_BASE_PATH,
 	DEFAULT_SERVER_URL,
 	tauriAPI,
 } from "../../lib/tauri";
@@ -28,6 +33,10 @@ export function ConnectionSettings() {
 	const { data: settings, isLoading } = useSettings();
 	const updateServerUrl = useUpdateServerUrl();
 	const [localUrl, setLocalUrl] = useState<string | null>(null);
+	const updateApiBasePath = useUpdateApiBasePath();
+	const [localApiBasePath, setLocalApiBasePath] = useState<string | null>(
+		null,
+	);
 	const [pingStatus, setPingStatus] = useState<PingStatus>("idle");
 	const [clientUUID, setClientUUID] = useState<string | null>(null);
 	const [uuidCopied, setUuidCopied] = useState(false);
@@ -71,7 +80,14 @@ export function ConnectionSettings() {
 
 		const setup = async () => {
 			unlisten = await tauriAPI.onReconnectResult((result) => {
-				if (!result.success) {
+				// Only manual reconnects notify: automatic retries keep going on their own.
+				// Nothing to reconnect to when running local-only.
+				const isManualAttempt = result.attempt === undefined;
+				if (
+					!result.success &&
+					isManualAttempt &&
+					settings?.operating_mode !== "local_only"
+				) {
 					notifications.show({
 						title: "Reconnection Failed",
 						message: result.error || "Could not reconnect to the server",
@@ -87,7 +103,7 @@ export function ConnectionSettings() {
 		return () => {
 			unlisten?.();
 		};
-	}, []);
+	}, [settings?.operating_mode]);
 
 	// Use local state if user is editing, otherwise use saved value
 	const displayUrl = localUrl ?? settings?.server_url ?? DEFAULT_SERVER_URL;
@@ -114,6 +130,18 @@ export function ConnectionSettings() {
 		});
 	};
 
+	const displayApiBasePath =
+		localApiBasePath ?? settings?.api_base_path ?? DEFAULT_API_BASE_PATH;
+	const hasApiBasePathChanges =
+		localApiBasePath !== null &&
+		localApiBasePath !== settings?.api_base_path;
+
+	const handleSaveApiBasePath = (path: string) => {
+		updateApiBasePath.mutate(path, {
+			onSuccess: () => setLocalApiBasePath(null),
+		});
+	};
+
 	const handleKeyDown = (e: React.KeyboardEvent) => {
 		if (e.key === "Enter" && hasChanges) {
 			handleSave();
@@ -314,6 +342,69 @@ export function ConnectionSettings() {
 				</div>
 			</div>
 
+			{/* API Path Row */}
+			<div className="settings-card" style={{ marginTop: 12 }}>
+				<div
+					className="settings-row"
+					style={{ flexDirection: "column", alignItems: "stretch", gap: 8 }}
+				>
+					<div>
+						<div style={{ display: "flex", alignItems: "center", gap: 8 }}>
+							<p className="settings-label" style={{ margin: 0 }}>
+								API Path
+							</p>
+							<StatusIndicator status={updateApiBasePath.status} />
+						</div>
+						<p className="settings-description">
+							The path the server's API is served under, if it is behind a
+							reverse proxy at a sub-path
+						</p>
+					</div>
+					<div style={{ display: "flex", gap: 8, alignItems: "center" }}>
+						<TextInput
+							value={displayApiBasePath}
+							onChange={(e) => setLocalApiBasePath(e.currentTarget.value)}
+							onKeyDown={(e) => {
+								if (e.key === "Enter" && hasApiBasePathChanges) {
+									handleSaveApiBasePath(displayApiBasePath);
+								}
+							}}
+							placeholder={DEFAULT_API_BASE_PATH}
+							disabled={isLoading}
+							style={{ flex: 1 }}
+							styles={{
+								input: {
+									fontFamily: "monospace",
+									fontSize: "13px",
+								},
+							}}
+						/>
+						{hasApiBasePathChanges && (
+							<Button
+								onClick={() => handleSaveApiBasePath(displayApiBasePath)}
+								loading={updateApiBasePath.isPending}
+								size="sm"
+								color="gray"
+							>
+								Save
+							</Button>
+						)}
+						{settings?.api_base_path !== DEFAULT_API_BASE_PATH &&
+							!hasApiBasePathChanges && (
+								<Button
+									onClick={() => handleSaveApiBasePath(DEFAULT_API_BASE_PATH)}
+									loading={updateApiBasePath.isPending}
+									size="sm"
+									variant="subtle"
+									color="gray"
+								>
+									Reset
+								</Button>
+							)}
+					</div>
+				</div>
+			</div>
+
 			{/* Client ID Row */}
 			<div className="settings-card" style={{ marginTop: 12 }}>
 				<div
@@ -86,7 +86,7 @@ export function HotkeySettings() {
 						setHotkeyEnabled.mutate({ hotkeyType: "toggle", enabled })
 					}
 					enabledLoading={setHotkeyEnabled.isPending}
-					registrationError={shortcutErrors?.toggle_error}
+					registrationError={shortcutErrors?.toggle_error?.message}
 					mutationStatus={updateToggleHotkey.status}
 				/>
 
@@ -105,7 +105,7 @@ export function HotkeySettings() {
 							setHotkeyEnabled.mutate({ hotkeyType: "hold", enabled })
 						}
 						enabledLoading={setHotkeyEnabled.isPending}
-						registrationError={shortcutErrors?.hold_error}
+						registrationError={shortcutErrors?.hold_error?.message}
 						mutationStatus={updateHoldHotkey.status}
 					/>
 				</div>
@@ -125,7 +125,7 @@ export function HotkeySettings() {
 							setHotkeyEnabled.mutate({ hotkeyType: "paste_last", enabled })
 						}
 						enabledLoading={setHotkeyEnabled.isPending}
-						registrationError={shortcutErrors?.paste_last_error}
+						registrationError={shortcutErrors?.paste_last_error?.message}
 						mutationStatus={updatePasteLastHotkey.status}
 					/>
 				</div>
@@ -145,7 +145,7 @@ export function HotkeySettings() {
 							setHotkeyEnabled.mutate({ hotkeyType: "translation", enabled })
 						}
 						enabledLoading={setHotkeyEnabled.isPending}
-						registrationError={shortcutErrors?.translation_error}
+						registrationError={shortcutErrors?.translation_error?.message}
 						mutationStatus={updateTranslationHotkey.status}
 					/>
 				</div>
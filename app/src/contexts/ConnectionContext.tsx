@@ -2,6 +2,7 @@ import type { PipecatClient } from "@pipecat-ai/client-js";
 import { useSelector } from "@xstate/react";
 import { createContext, type ReactNode, useContext, useEffect } from "react";
 import { createActor } from "xstate";
+import type { DisconnectReason } from "../lib/events";
 import { tauriAPI } from "../lib/tauri";
 import {
 	type ConnectionMachineActor,
@@ -29,9 +30,14 @@ interface ConnectionProviderProps {
 }
 
 export function ConnectionProvider({ children }: ConnectionProviderProps) {
-	// Trigger initial connection on mount
+	// Trigger initial connection on mount (unless running local-only)
 	useEffect(() => {
 		const initConnection = async () => {
+			const settings = await tauriAPI.getSettings();
+			if (settings.operating_mode === "local_only") {
+				console.log("[XState] Local-only mode, not connecting");
+				return;
+			}
 			const serverUrl = await tauriAPI.getServerUrl();
 			if (serverUrl) {
 				connectionActor.send({ type: "CONNECT", serverUrl });
@@ -72,8 +78,27 @@ export function ConnectionProvider({ children }: ConnectionProviderProps) {
 
 		const subscribeToSettingsChanges = async () => {
 			const unsubscribeFn = await tauriAPI.onSettingsChanged(async () => {
-				const newServerUrl = await tauriAPI.getServerUrl();
+				const settings = await tauriAPI.getSettings();
 				const currentState = connectionActor.getSnapshot();
+
+				// Switched to local-only mode: drop the connection and stop retrying
+				if (settings.operating_mode === "local_only") {
+					if (!currentState.matches("disconnected")) {
+						console.log("[XState] Local-only mode enabled, disconnecting");
+						connectionActor.send({ type: "DISCONNECT" });
+					}
+					return;
+				}
+
+				const newServerUrl = await tauriAPI.getServerUrl();
+
+				// Switched back from local-only mode
+				if (currentState.matches("disconnected") && newServerUrl) {
+					console.log("[XState] Connected mode enabled, connecting");
+					connectionActor.send({ type: "CONNECT", serverUrl: newServerUrl });
+					return;
+				}
+
 				const shouldHandleUrlChange =
 					currentState.matches("idle") ||
 					currentState.matches("recording") ||
@@ -136,6 +161,14 @@ export function useConnectionState() {
 	return useSelector(actor, (state) => state.value);
 }
 
+/**
+ * Hook to get why the last connection was lost, or null while connected.
+ */
+export function useDisconnectReason(): DisconnectReason | null {
+	const actor = useConnectionActor();
+	return useSelector(actor, (state) => state.context.disconnectReason);
+}
+
 /**
  * Hook to get the current PipecatClient instance.
  * Returns null when not connected.
@@ -10,6 +10,7 @@
 
 import { emit, listen, type UnlistenFn } from "@tauri-apps/api/event";
 import type { ActiveWindowInfo } from "./formattingProfiles";
+import type { CleanupPromptSections, HistoryEntry } from "./tauri";
 
 // =============================================================================
 // Event Names - Must match src-tauri/src/events.rs
@@ -34,9 +35,12 @@ export const AppEvents = {
 	// Main → Overlay: Request reconnection
 	reconnectRequest: "request-reconnect",
 
-	// Overlay → Main: Connection state updates
+	// Rust → All: Connection state updates
 	connectionState: "connection-state-changed",
 
+	// Any window → Rust: Re-emit the current connection state
+	connectionStateRequest: "connection-state-request",
+
 	// Overlay → Main: Reconnection progress
 	reconnectStarted: "reconnect-started",
 	reconnectResult: "reconnect-result",
@@ -44,6 +48,21 @@ export const AppEvents = {
 	// Rust → All: History changed
 	historyChanged: "history-changed",
 
+	// Rust → All: Non-fatal notification
+	notification: "notification",
+
+	// Rust → All: Foreground window changed (while the window watcher runs)
+	activeWindowChanged: "active-window-changed",
+
+	// Rust → All: Recording started in a disabled app
+	recordingSuppressed: "recording-suppressed",
+
+	// Rust → All: Overlay hidden after a transcription (overlay_auto_hide_ms)
+	overlayHidden: "overlay-hidden",
+
+	// Rust → All: Recording shorter than min_recording_ms, sent instead of recording-stop
+	recordingDiscarded: "recording-discarded",
+
 	// Overlay → Main: LLM error notification
 	llmError: "llm-error",
 
@@ -55,6 +74,46 @@ export const AppEvents = {
 // Event Payloads - Must match src-tauri/src/events.rs
 // =============================================================================
 
+export type DisconnectReason =
+	| "timeout"
+	| "auth-failed"
+	| "user-requested"
+	| "server-closed"
+	| "error";
+
+export interface ConnectionStatePayload {
+	state: ConnectionState;
+	server_url?: string;
+	reason?: DisconnectReason;
+}
+
+/**
+ * Reconnect attempt started. `attempt` is set for automatic retries, not manual reconnects.
+ */
+export interface ReconnectStartedPayload {
+	attempt?: number;
+}
+
+/**
+ * Outcome of one reconnect attempt. `attempt` matches the started payload.
+ */
+export interface ReconnectResultPayload {
+	success: boolean;
+	error?: string;
+	attempt?: number;
+}
+
+/**
+ * What changed in the dictation history. "reloaded" means many entries
+ * changed at once (e.g. an import) and the list should be refetched.
+ */
+export type HistoryChangedPayload =
+	| { type: "added"; entry: HistoryEntry }
+	| { type: "updated"; entry: HistoryEntry }
+	| { type: "deleted"; ids: string[] }
+	| { type: "cleared" }
+	| { type: "reloaded" };
+
 export type ConnectionState =
 	| "disconnected"
 	| "connecting"
@@ -94,23 +153,62 @@ export interface ProviderChangeRequestPayload {
 	value: string;
 }
 
+export interface NotificationPayload {
+	message: string;
+}
+
 export interface RecordingStartPayload {
+	/** Correlates this recording's logs, events and server requests */
+	recording_id: string;
 	window_info?: ActiveWindowInfo;
+	/** Profile matched by the user's profile rules, if any */
+	profile_id?: string;
+	/** Effective prompt sections, when the matched profile overrides the base ones */
+	prompt_sections?: CleanupPromptSections;
+}
+
+export interface RecordingStopPayload {
+	/** Same ID as the recording-start event of this recording */
+	recording_id: string;
+}
+
+/** Sent instead of recording-stop when a recording is too short to keep */
+export interface RecordingDiscardedPayload {
+	/** Same ID as the recording-start event of this recording */
+	recording_id: string;
+	duration_ms: number;
+	min_recording_ms: number;
+}
+
+/**
+ * Sent when a recording is started in a disabled app.
+ * - overlay: recording went ahead with the overlay hidden
+ * - recording: nothing was recorded
+ */
+export interface RecordingSuppressedPayload {
+	app_name: string;
+	scope: "overlay" | "recording";
 }
 
 export interface EventPayloads {
 	[AppEvents.recordingStart]: RecordingStartPayload;
-	[AppEvents.recordingStop]: undefined;
+	[AppEvents.recordingStop]: RecordingStopPayload;
 	[AppEvents.prepareRecording]: undefined;
 	[AppEvents.translationTrigger]: undefined;
 	[AppEvents.configResponse]: ConfigResponse;
 	[AppEvents.requestDisconnect]: undefined;
 	[AppEvents.settingsChanged]: undefined;
 	[AppEvents.reconnectRequest]: undefined;
-	[AppEvents.connectionState]: { state: ConnectionState };
-	[AppEvents.reconnectStarted]: undefined;
-	[AppEvents.reconnectResult]: { success: boolean; error?: string };
-	[AppEvents.historyChanged]: undefined;
+	[AppEvents.connectionState]: ConnectionStatePayload;
+	[AppEvents.connectionStateRequest]: undefined;
+	[AppEvents.reconnectStarted]: ReconnectStartedPayload;
+	[AppEvents.reconnectResult]: ReconnectResultPayload;
+	[AppEvents.historyChanged]: HistoryChangedPayload;
+	[AppEvents.notification]: NotificationPayload;
+	[AppEvents.activeWindowChanged]: ActiveWindowInfo;
+	[AppEvents.recordingSuppressed]: RecordingSuppressedPayload;
+	[AppEvents.overlayHidden]: undefined;
+	[AppEvents.recordingDiscarded]: RecordingDiscardedPayload;
 	[AppEvents.llmError]: LLMErrorPayload;
 	[AppEvents.providerChangeRequest]: ProviderChangeRequestPayload;
 }
@@ -45,6 +45,8 @@ export const ActiveWindowInfoSchema = z.object({
 	bundle_id: z.string().optional(),
 	process_name: z.string().optional(),
 	url: z.string().optional(),
+	display_name: z.string().optional(),
+	is_private: z.boolean().optional(),
 });
 
 export type ActiveWindowInfo = z.infer<typeof ActiveWindowInfoSchema>;
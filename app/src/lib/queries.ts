@@ -368,8 +368,6 @@ export function useAddHistoryEntry() {
 			tauriAPI.addHistoryEntry(text, rawText),
 		onSuccess: () => {
 			queryClient.invalidateQueries({ queryKey: ["history"] });
-			// Notify other windows about history change
-			tauriAPI.emitHistoryChanged();
 		},
 	});
 }
@@ -380,8 +378,6 @@ export function useDeleteHistoryEntry() {
 		mutationFn: (id: string) => tauriAPI.deleteHistoryEntry(id),
 		onSuccess: () => {
 			queryClient.invalidateQueries({ queryKey: ["history"] });
-			// Notify other windows about history change
-			tauriAPI.emitHistoryChanged();
 		},
 	});
 }
@@ -392,8 +388,6 @@ export function useClearHistory() {
 		mutationFn: () => tauriAPI.clearHistory(),
 		onSuccess: () => {
 			queryClient.invalidateQueries({ queryKey: ["history"] });
-			// Notify other windows about history change
-			tauriAPI.emitHistoryChanged();
 		},
 	});
 }
@@ -476,6 +470,20 @@ export function useUpdateServerUrl() {
 	});
 }
 
+export function useUpdateApiBasePath() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: (path: string) => tauriAPI.updateApiBasePath(path),
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["settings"] });
+			showSettingsSuccess("API path updated successfully");
+		},
+		onError: (error) => {
+			showSettingsError(`Failed to update API path: ${error.message}`);
+		},
+	});
+}
+
 // =============================================================================
 // Provider Mutations with Server Confirmation
 // =============================================================================
@@ -809,7 +817,6 @@ export function useImportHistory() {
 		},
 		onSuccess: (result) => {
 			queryClient.invalidateQueries({ queryKey: ["history"] });
-			tauriAPI.emitHistoryChanged();
 			const imported = result.entries_imported ?? 0;
 			notifications.show({
 				title: "History Imported",
@@ -896,7 +903,6 @@ export function useFactoryReset() {
 			queryClient.invalidateQueries({ queryKey: ["history"] });
 			queryClient.invalidateQueries({ queryKey: ["shortcutErrors"] });
 			tauriAPI.emitSettingsChanged();
-			tauriAPI.emitHistoryChanged();
 			notifications.show({
 				title: "Factory Reset Complete",
 				message: "All settings and history have been reset to defaults",
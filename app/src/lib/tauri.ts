@@ -3,8 +3,9 @@ import type { UnlistenFn } from "@tauri-apps/api/event";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { Store } from "@tauri-apps/plugin-store";
 import ky from "ky";
-import { withoutTrailingSlash } from "ufo";
+import { joinURL, withoutTrailingSlash } from "ufo";
 import { z } from "zod";
+import type { ActiveWindowInfo } from "./formattingProfiles";
 
 // =============================================================================
 // Provider ID Constants - Single source of truth
@@ -284,26 +285,43 @@ export const KNOWN_SETTINGS = [
 	"stt-provider",
 	"llm-provider",
 	"prompt-sections",
+	"profile-prompts",
 	"stt-timeout",
 ] as const;
 
 export type {
 	ConfigResponse,
 	ConnectionState,
+	ConnectionStatePayload,
+	DisconnectReason,
+	HistoryChangedPayload,
 	LLMErrorPayload,
 	ProviderChangeRequestPayload,
+	ReconnectResultPayload,
+	ReconnectStartedPayload,
+	RecordingDiscardedPayload,
 	RecordingStartPayload,
+	RecordingStopPayload,
+	RecordingSuppressedPayload,
 } from "./events";
 
 import {
 	AppEvents,
 	type ConfigResponse,
 	type ConnectionState,
+	type ConnectionStatePayload,
+	type DisconnectReason,
 	emitEvent,
+	type HistoryChangedPayload,
 	type LLMErrorPayload,
 	listenEvent,
 	type ProviderChangeRequestPayload,
+	type ReconnectResultPayload,
+	type ReconnectStartedPayload,
+	type RecordingDiscardedPayload,
 	type RecordingStartPayload,
+	type RecordingStopPayload,
+	type RecordingSuppressedPayload,
 } from "./events";
 
 interface TypeTextResult {
@@ -317,11 +335,61 @@ export interface HotkeyConfig {
 	enabled: boolean;
 }
 
+export type ShortcutErrorKind =
+	| "parse_failed"
+	| "already_registered"
+	| "os_rejected";
+
+export interface ShortcutError {
+	accelerator: string;
+	kind: ShortcutErrorKind;
+	message: string;
+}
+
 export interface ShortcutErrors {
-	toggle_error: string | null;
-	hold_error: string | null;
-	paste_last_error: string | null;
-	translation_error: string | null;
+	toggle_error: ShortcutError | null;
+	hold_error: ShortcutError | null;
+	paste_last_error: ShortcutError | null;
+	translation_error: ShortcutError | null;
+}
+
+/**
+ * Reconnect progress, e.g. "retrying in 8s (attempt 4)"
+ */
+export interface ReconnectStatus {
+	active: boolean;
+	attempt: number;
+	retry_in_ms?: number;
+}
+
+export interface SyncErrorContext {
+	error: string;
+	idempotency_key: string;
+	attempts: number;
+}
+
+export interface SyncMetrics {
+	succeeded: number;
+	failed: number;
+	retries: number;
+	last_error: SyncErrorContext | null;
+	/** Requests built but not sent because dry-run mode is on */
+	dry_runs: number;
+	last_dry_run: DryRunRequest | null;
+}
+
+/** Where the last change to a synced setting stands with the server */
+export type SyncStatus =
+	| { status: "pending" }
+	| { status: "synced"; synced_ms_ago: number }
+	| { status: "failed"; error: string };
+
+/** A config request logged instead of sent, with credentials redacted */
+export interface DryRunRequest {
+	method: string;
+	url: string;
+	headers: [string, string][];
+	body: string;
 }
 
 export interface ShortcutRegistrationResult {
@@ -337,6 +405,7 @@ export interface HistoryEntry {
 	timestamp: string;
 	text: string;
 	raw_text: string;
+	duration_ms?: number;
 }
 
 // =============================================================================
@@ -385,6 +454,43 @@ export interface CleanupPromptSections {
 	dictionary: PromptSection;
 }
 
+/** A profile's changes to the base prompt sections; omitted sections use the base */
+export type PromptSectionOverrides = Partial<CleanupPromptSections>;
+
+/**
+ * What a profile rule looks at in the active window.
+ * Patterns are case-insensitive regular expressions. URL rules match the
+ * browser tab's host (`*.example.com` includes subdomains) and path prefix.
+ */
+export type MatcherKind =
+	| { type: "app"; pattern: string }
+	| { type: "title"; pattern: string }
+	| { type: "url"; host?: string | null; path_prefix?: string | null };
+
+/**
+ * Rule mapping matching windows to a formatting profile.
+ * Rules are checked in order; the first match wins.
+ */
+export interface ProfileRule {
+	profile_id: string;
+	matcher: MatcherKind;
+}
+
+/** Profile the current foreground window would get, for testing rules */
+export interface ProfileMatchResult {
+	window: ActiveWindowInfo;
+	/** Picked profile, or null for the default profile */
+	matched: string | null;
+	/** Index into profile_rules of the rule that picked `matched`, if any */
+	matched_rule_index: number | null;
+}
+
+/**
+ * Whether the app connects to a server. In "local_only" mode it never
+ * connects or syncs; history and profile matching still work.
+ */
+export type OperatingMode = "connected" | "local_only";
+
 export interface AppSettings {
 	toggle_hotkey: HotkeyConfig;
 	hold_hotkey: HotkeyConfig;
@@ -401,10 +507,34 @@ export interface AppSettings {
 	translation_enabled: boolean;
 	translation_target_languages: string[];
 	translation_default_language: string;
+	profile_rules: ProfileRule[];
+	disabled_apps: string[];
+	suppress_recording_in_disabled_apps: boolean;
+	store_empty_recordings: boolean;
+	history_dedup_enabled: boolean;
+	history_dedup_window_seconds: number;
+	/** Days history is kept; older entries are pruned on startup. null = forever */
+	history_retention_days: number | null;
+	operating_mode: OperatingMode;
+	/** Milliseconds the overlay stays up after a transcription; 0 = until the next action */
+	overlay_auto_hide_ms: number;
+	/** Prompt section overrides by formatting profile ID */
+	profile_prompt_overrides: Record<string, PromptSectionOverrides>;
+	/** Recordings shorter than this many milliseconds are discarded; 0 = keep all */
+	min_recording_ms: number;
+	/**
+	 * When false, no window is inspected and recordings use the default profile.
+	 * Disabled apps can't be recognised then, so recording is never suppressed in them.
+	 */
+	active_window_detection_enabled: boolean;
+	/** Path the server's API is mounted under (default "/api"), stored normalized */
+	api_base_path: string;
 }
 
 export const DEFAULT_SERVER_URL = "http://127.0.0.1:8765";
 
+export const DEFAULT_API_BASE_PATH = "/api";
+
 let storeInstance: Store | null = null;
 
 async function getStore(): Promise<Store> {
@@ -499,7 +629,9 @@ export const tauriAPI = {
 		return listenEvent(AppEvents.recordingStart, callback);
 	},
 
-	async onStopRecording(callback: () => void): Promise<UnlistenFn> {
+	async onStopRecording(
+		callback: (payload: RecordingStopPayload) => void,
+	): Promise<UnlistenFn> {
 		return listenEvent(AppEvents.recordingStop, callback);
 	},
 
@@ -507,6 +639,22 @@ export const tauriAPI = {
 		return listenEvent(AppEvents.prepareRecording, callback);
 	},
 
+	async onRecordingSuppressed(
+		callback: (payload: RecordingSuppressedPayload) => void,
+	): Promise<UnlistenFn> {
+		return listenEvent(AppEvents.recordingSuppressed, callback);
+	},
+
+	async onRecordingDiscarded(
+		callback: (payload: RecordingDiscardedPayload) => void,
+	): Promise<UnlistenFn> {
+		return listenEvent(AppEvents.recordingDiscarded, callback);
+	},
+
+	async onOverlayHidden(callback: () => void): Promise<UnlistenFn> {
+		return listenEvent(AppEvents.overlayHidden, callback);
+	},
+
 	async getSettings(): Promise<AppSettings> {
 		return invoke("get_settings");
 	},
@@ -547,6 +695,12 @@ export const tauriAPI = {
 		return invoke("update_cleanup_prompt_sections", { sections });
 	},
 
+	async updateProfilePromptOverrides(
+		overrides: Record<string, PromptSectionOverrides>,
+	): Promise<void> {
+		return invoke("update_profile_prompt_overrides", { overrides });
+	},
+
 	async updateSTTProvider(provider: STTProviderId): Promise<void> {
 		return invoke("update_stt_provider", { provider });
 	},
@@ -567,6 +721,54 @@ export const tauriAPI = {
 		return invoke("update_server_url", { url });
 	},
 
+	async updateProfileRules(rules: ProfileRule[]): Promise<void> {
+		return invoke("update_profile_rules", { rules });
+	},
+
+	async updateDisabledApps(apps: string[]): Promise<void> {
+		return invoke("update_disabled_apps", { apps });
+	},
+
+	async updateSuppressRecordingInDisabledApps(enabled: boolean): Promise<void> {
+		return invoke("update_suppress_recording_in_disabled_apps", { enabled });
+	},
+
+	async updateStoreEmptyRecordings(enabled: boolean): Promise<void> {
+		return invoke("update_store_empty_recordings", { enabled });
+	},
+
+	async updateOperatingMode(mode: OperatingMode): Promise<void> {
+		return invoke("update_operating_mode", { mode });
+	},
+
+	async updateHistoryDedupEnabled(enabled: boolean): Promise<void> {
+		return invoke("update_history_dedup_enabled", { enabled });
+	},
+
+	async updateHistoryDedupWindowSeconds(seconds: number): Promise<void> {
+		return invoke("update_history_dedup_window_seconds", { seconds });
+	},
+
+	async updateHistoryRetentionDays(days: number | null): Promise<void> {
+		return invoke("update_history_retention_days", { days });
+	},
+
+	async updateOverlayAutoHideMs(ms: number): Promise<void> {
+		return invoke("update_overlay_auto_hide_ms", { ms });
+	},
+
+	async updateMinRecordingMs(ms: number): Promise<void> {
+		return invoke("update_min_recording_ms", { ms });
+	},
+
+	async updateActiveWindowDetectionEnabled(enabled: boolean): Promise<void> {
+		return invoke("update_active_window_detection_enabled", { enabled });
+	},
+
+	async updateApiBasePath(path: string): Promise<void> {
+		return invoke("update_api_base_path", { path });
+	},
+
 	async isAudioMuteSupported(): Promise<boolean> {
 		return invoke("is_audio_mute_supported");
 	},
@@ -610,19 +812,44 @@ export const tauriAPI = {
 		return invoke("clear_history");
 	},
 
+	/** Delete entries older than `olderThanDays` days; 0 deletes all and needs `deleteAll` */
+	async pruneHistory(olderThanDays: number, deleteAll = false): Promise<number> {
+		return invoke("prune_history", { olderThanDays, deleteAll });
+	},
+
+	async pasteLastTranscription(): Promise<void> {
+		return invoke("paste_last_transcription");
+	},
+
 	// Overlay API
 	async resizeOverlay(width: number, height: number): Promise<void> {
 		return invoke("resize_overlay", { width, height });
 	},
 
+	async scheduleOverlayAutoHide(): Promise<void> {
+		return invoke("schedule_overlay_auto_hide");
+	},
+
 	async startDragging(): Promise<void> {
 		const window = getCurrentWindow();
 		return window.startDragging();
 	},
 
 	// Connection state sync between windows
-	async emitConnectionState(state: ConnectionState): Promise<void> {
-		return emitEvent(AppEvents.connectionState, { state });
+	async emitConnectionState(
+		state: ConnectionState,
+		reason?: DisconnectReason,
+	): Promise<void> {
+		// Routed through Rust so windows opened later can get the current state
+		return invoke("set_connection_state", { state, reason });
+	},
+
+	async getConnectionState(): Promise<ConnectionStatePayload> {
+		return invoke("get_connection_state");
+	},
+
+	async requestConnectionState(): Promise<void> {
+		return emitEvent(AppEvents.connectionStateRequest);
 	},
 
 	async onConnectionStateChanged(
@@ -633,12 +860,10 @@ export const tauriAPI = {
 		});
 	},
 
-	// History sync between windows
-	async emitHistoryChanged(): Promise<void> {
-		return emitEvent(AppEvents.historyChanged);
-	},
-
-	async onHistoryChanged(callback: () => void): Promise<UnlistenFn> {
+	// History sync between windows (Rust emits after every change)
+	async onHistoryChanged(
+		callback: (change: HistoryChangedPayload) => void,
+	): Promise<UnlistenFn> {
 		return listenEvent(AppEvents.historyChanged, callback);
 	},
 
@@ -661,20 +886,26 @@ export const tauriAPI = {
 	},
 
 	// Reconnection status (overlay -> main)
-	async emitReconnectStarted(): Promise<void> {
-		return emitEvent(AppEvents.reconnectStarted);
+	async emitReconnectStarted(attempt?: number): Promise<void> {
+		return emitEvent(AppEvents.reconnectStarted, { attempt });
 	},
 
-	async onReconnectStarted(callback: () => void): Promise<UnlistenFn> {
+	async onReconnectStarted(
+		callback: (payload: ReconnectStartedPayload) => void,
+	): Promise<UnlistenFn> {
 		return listenEvent(AppEvents.reconnectStarted, callback);
 	},
 
-	async emitReconnectResult(success: boolean, error?: string): Promise<void> {
-		return emitEvent(AppEvents.reconnectResult, { success, error });
+	async emitReconnectResult(
+		success: boolean,
+		error?: string,
+		attempt?: number,
+	): Promise<void> {
+		return emitEvent(AppEvents.reconnectResult, { success, error, attempt });
 	},
 
 	async onReconnectResult(
-		callback: (result: { success: boolean; error?: string }) => void,
+		callback: (result: ReconnectResultPayload) => void,
 	): Promise<UnlistenFn> {
 		return listenEvent(AppEvents.reconnectResult, callback);
 	},
@@ -714,6 +945,32 @@ export const tauriAPI = {
 		return listenEvent(AppEvents.providerChangeRequest, callback);
 	},
 
+	// Foreground window watcher (for switching profiles mid-session)
+	async startWindowWatcher(
+		intervalMs: number,
+		options?: { trackTitleChanges?: boolean; titleDebounceMs?: number },
+	): Promise<void> {
+		return invoke("start_window_watcher", {
+			intervalMs,
+			trackTitleChanges: options?.trackTitleChanges,
+			titleDebounceMs: options?.titleDebounceMs,
+		});
+	},
+
+	async stopWindowWatcher(): Promise<void> {
+		return invoke("stop_window_watcher");
+	},
+
+	async testCurrentProfileMatch(): Promise<ProfileMatchResult> {
+		return invoke("test_current_profile_match");
+	},
+
+	async onActiveWindowChanged(
+		callback: (info: ActiveWindowInfo) => void,
+	): Promise<UnlistenFn> {
+		return listenEvent(AppEvents.activeWindowChanged, callback);
+	},
+
 	// Server connection state management (for Rust-side config syncing)
 	async setServerConnected(
 		serverUrl: string,
@@ -722,8 +979,45 @@ export const tauriAPI = {
 		return invoke("set_server_connected", { serverUrl, clientUuid });
 	},
 
-	async setServerDisconnected(): Promise<void> {
-		return invoke("set_server_disconnected");
+	async setServerDisconnected(reason?: DisconnectReason): Promise<void> {
+		return invoke("set_server_disconnected", { reason });
+	},
+
+	/** Record the retry the connection machine just scheduled, for getReconnectStatus */
+	async setReconnectScheduled(
+		attempt: number,
+		retryInMs: number,
+	): Promise<void> {
+		return invoke("set_reconnect_scheduled", { attempt, retryInMs });
+	},
+
+	/** Clear the retry countdown after reconnecting, or a manual reconnect or disconnect */
+	async resetReconnectStatus(): Promise<void> {
+		return invoke("reset_reconnect_status");
+	},
+
+	async getReconnectStatus(): Promise<ReconnectStatus> {
+		return invoke("get_reconnect_status");
+	},
+
+	async getSyncMetrics(): Promise<SyncMetrics> {
+		return invoke("get_sync_metrics");
+	},
+
+	/** Status of each setting synced since the app started, by setting name */
+	async getSyncStatus(): Promise<
+		Partial<Record<(typeof KNOWN_SETTINGS)[number], SyncStatus>>
+	> {
+		return invoke("sync_status");
+	},
+
+	async setConfigSyncDryRun(enabled: boolean): Promise<void> {
+		return invoke("set_config_sync_dry_run", { enabled });
+	},
+
+	/** Normalized API base path, shared by every client of the server's API */
+	async getApiBasePath(): Promise<string> {
+		return invoke("get_api_base_path");
 	},
 
 	// Export/Import API
@@ -791,10 +1085,12 @@ export interface AvailableProvidersData {
 	llm: ProviderInfo[];
 }
 
-// Create ky instance with sensible defaults for API calls
-function createApiClient(serverUrl: string) {
+// Create ky instance with sensible defaults for API calls, rooted at the
+// server's API base path so endpoints are given relative to it
+async function createApiClient(serverUrl: string) {
+	const apiBasePath = await tauriAPI.getApiBasePath();
 	return ky.create({
-		prefixUrl: withoutTrailingSlash(serverUrl),
+		prefixUrl: withoutTrailingSlash(joinURL(serverUrl, apiBasePath)),
 		timeout: 10000,
 		retry: {
 			limit: 2,
@@ -810,17 +1106,17 @@ export const configAPI = {
 
 	// Static prompt defaults
 	getDefaultSections: async (serverUrl: string) => {
-		const api = createApiClient(serverUrl);
+		const api = await createApiClient(serverUrl);
 		return api
-			.get("api/prompt/sections/default")
+			.get("prompt/sections/default")
 			.json<DefaultSectionsResponse>();
 	},
 
 	// Client registration for UUID-based identification
 	registerClient: async (serverUrl: string): Promise<string> => {
-		const api = createApiClient(serverUrl);
+		const api = await createApiClient(serverUrl);
 		const response = await api
-			.post("api/client/register")
+			.post("client/register")
 			.json<{ uuid: string }>();
 		return response.uuid;
 	},
@@ -830,9 +1126,9 @@ export const configAPI = {
 		serverUrl: string,
 		clientUUID: string,
 	): Promise<boolean> => {
-		const api = createApiClient(serverUrl);
+		const api = await createApiClient(serverUrl);
 		const response = await api
-			.get(`api/client/verify/${clientUUID}`)
+			.get(`client/verify/${clientUUID}`)
 			.json<{ registered: boolean }>();
 		return response.registered;
 	},
@@ -841,8 +1137,8 @@ export const configAPI = {
 	getAvailableProviders: async (
 		serverUrl: string,
 	): Promise<AvailableProvidersData> => {
-		const api = createApiClient(serverUrl);
-		return api.get("api/providers").json<AvailableProvidersData>();
+		const api = await createApiClient(serverUrl);
+		return api.get("providers").json<AvailableProvidersData>();
 	},
 
 	// =========================================================================
@@ -858,12 +1154,18 @@ export const configAPI = {
 			window_title?: string;
 			url?: string;
 			profile_id?: string;
+			prompt_sections?: CleanupPromptSections;
 		},
+		recordingId?: string,
 	): Promise<{ success: boolean; setting: string; value: string }> => {
-		const api = createApiClient(serverUrl);
+		const api = await createApiClient(serverUrl);
 		return api
-			.put("api/config/app-context", {
-				headers: { "X-Client-UUID": clientUUID },
+			.put("config/app-context", {
+				headers: {
+					"X-Client-UUID": clientUUID,
+					// Lets server logs be matched with this recording's client logs
+					...(recordingId && { "X-Recording-ID": recordingId }),
+				},
 				json: context,
 			})
 			.json();
@@ -878,9 +1180,9 @@ export const configAPI = {
 		clientUUID: string,
 		targetLanguage: string | null,
 	): Promise<{ success: boolean; setting: string }> => {
-		const api = createApiClient(serverUrl);
+		const api = await createApiClient(serverUrl);
 		return api
-			.put("api/config/translation", {
+			.put("config/translation", {
 				headers: { "X-Client-UUID": clientUUID },
 				json: { target_language: targetLanguage },
 			})
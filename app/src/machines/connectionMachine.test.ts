@@ -1,11 +1,12 @@
 import type { PipecatClient } from "@pipecat-ai/client-js";
-import { describe, expect, it, vi } from "vitest";
+import { beforeEach, describe, expect, it, vi } from "vitest";
 import {
 	type AnyActorRef,
 	createActor,
 	fromCallback,
 	fromPromise,
 } from "xstate";
+import { tauriAPI } from "../lib/tauri";
 import { connectionMachine } from "./connectionMachine";
 
 // Mock the tauri module to avoid "window is not defined" errors
@@ -15,11 +16,14 @@ vi.mock("../lib/tauri", () => ({
 		emitConnectionState: vi.fn(),
 		emitReconnectStarted: vi.fn(),
 		emitReconnectResult: vi.fn(),
+		setReconnectScheduled: vi.fn(),
+		resetReconnectStatus: vi.fn(),
 		emitConfigResponse: vi.fn(),
 		getClientUUID: vi.fn().mockResolvedValue(null),
 		setClientUUID: vi.fn().mockResolvedValue(undefined),
 		clearClientUUID: vi.fn().mockResolvedValue(undefined),
 		onProviderChangeRequest: vi.fn().mockResolvedValue(() => {}),
+		getApiBasePath: vi.fn().mockResolvedValue("/api"),
 	},
 	configAPI: {
 		registerClient: vi.fn().mockResolvedValue("mock-uuid"),
@@ -121,8 +125,7 @@ function createTestMachine(config: {
 		},
 		actions: {
 			emitConnectionState: () => {},
-			emitReconnectStarted: () => {},
-			emitReconnectResult: () => {},
+			reportRetryScheduled: () => {},
 			cleanupClient: () => {},
 			logState: () => {},
 		},
@@ -310,6 +313,23 @@ describe("connectionMachine", () => {
 			actor.stop();
 		});
 
+		it("reports auth-failed after UUID_REJECTED", async () => {
+			const { machine, callbacks } = createTestMachine({
+				createClientBehavior: "success",
+			});
+			const actor = createActor(machine);
+			actor.start();
+
+			actor.send({ type: "CONNECT", serverUrl: "http://localhost:8000" });
+			await waitForState(actor, "connecting");
+			callbacks.connectSendBack?.({ type: "UUID_REJECTED" });
+			await new Promise((resolve) => setTimeout(resolve, 50));
+
+			expect(actor.getSnapshot().context.disconnectReason).toBe("auth-failed");
+
+			actor.stop();
+		});
+
 		it("transitions to retrying on connection timeout", async () => {
 			vi.useFakeTimers();
 
@@ -402,6 +422,19 @@ describe("connectionMachine", () => {
 			actor.stop();
 		});
 
+		it("disconnects with user-requested reason on DISCONNECT", async () => {
+			const { actor } = await setupIdleState();
+
+			actor.send({ type: "DISCONNECT" });
+			await waitForState(actor, "disconnected");
+
+			const { context } = actor.getSnapshot();
+			expect(context.client).toBeNull();
+			expect(context.disconnectReason).toBe("user-requested");
+
+			actor.stop();
+		});
+
 		it("resets retryCount on RECONNECT", async () => {
 			const { actor } = await setupIdleState();
 
@@ -593,6 +626,31 @@ describe("connectionMachine", () => {
 			vi.useRealTimers();
 		});
 
+		it("stops retrying on DISCONNECT", async () => {
+			vi.useFakeTimers();
+
+			const { machine } = createTestMachine({
+				createClientBehavior: "error",
+			});
+			const actor = createActor(machine);
+			actor.start();
+
+			actor.send({ type: "CONNECT", serverUrl: "http://localhost:8000" });
+			await vi.advanceTimersByTimeAsync(0);
+			expect(actor.getSnapshot().value).toBe("retrying");
+
+			actor.send({ type: "DISCONNECT" });
+			expect(actor.getSnapshot().value).toBe("disconnected");
+			expect(actor.getSnapshot().context.retryCount).toBe(0);
+
+			// No retry fires after the delay
+			await vi.advanceTimersByTimeAsync(2000);
+			expect(actor.getSnapshot().value).toBe("disconnected");
+
+			actor.stop();
+			vi.useRealTimers();
+		});
+
 		it("resets retryCount to 0 on RECONNECT event", async () => {
 			vi.useFakeTimers();
 
@@ -661,6 +719,89 @@ describe("connectionMachine", () => {
 		});
 	});
 
+	describe("Reconnect events", () => {
+		beforeEach(() => {
+			vi.clearAllMocks();
+		});
+
+		it("reports start and result around each retry attempt", async () => {
+			vi.useFakeTimers();
+
+			const { machine } = createTestMachine({
+				createClientBehavior: "error",
+			});
+			const actor = createActor(machine);
+			actor.start();
+
+			// The first connection isn't a reconnect
+			actor.send({ type: "CONNECT", serverUrl: "http://localhost:8000" });
+			await vi.advanceTimersByTimeAsync(0);
+			expect(tauriAPI.emitReconnectStarted).not.toHaveBeenCalled();
+			expect(tauriAPI.emitReconnectResult).not.toHaveBeenCalled();
+
+			// First retry starts after its delay and fails
+			await vi.advanceTimersByTimeAsync(2000);
+			expect(tauriAPI.emitReconnectStarted).toHaveBeenCalledWith(1);
+			expect(tauriAPI.emitReconnectResult).toHaveBeenCalledWith(
+				false,
+				"Client creation failed",
+				1,
+			);
+
+			actor.stop();
+			vi.useRealTimers();
+		});
+
+		it("reports success once reconnected", async () => {
+			const { machine, callbacks } = createTestMachine({
+				createClientBehavior: "success",
+			});
+			const actor = createActor(machine);
+			actor.start();
+
+			actor.send({ type: "CONNECT", serverUrl: "http://localhost:8000" });
+			await waitForState(actor, "connecting");
+			callbacks.connectSendBack?.({ type: "CONNECTED" });
+			await waitForState(actor, "idle");
+			expect(tauriAPI.emitReconnectResult).not.toHaveBeenCalled();
+
+			actor.send({ type: "RECONNECT" });
+			await waitForState(actor, "connecting");
+			expect(tauriAPI.emitReconnectStarted).toHaveBeenCalledWith(undefined);
+			callbacks.connectSendBack?.({ type: "CONNECTED" });
+			await waitForState(actor, "idle");
+
+			expect(tauriAPI.emitReconnectResult).toHaveBeenCalledWith(
+				true,
+				undefined,
+				undefined,
+			);
+			expect(actor.getSnapshot().context.reconnecting).toBe(false);
+
+			actor.stop();
+		});
+
+		it("clears Rust reconnect status on manual RECONNECT", async () => {
+			vi.useFakeTimers();
+
+			const { machine } = createTestMachine({
+				createClientBehavior: "error",
+			});
+			const actor = createActor(machine);
+			actor.start();
+
+			actor.send({ type: "CONNECT", serverUrl: "http://localhost:8000" });
+			await vi.advanceTimersByTimeAsync(0);
+			expect(actor.getSnapshot().value).toBe("retrying");
+
+			actor.send({ type: "RECONNECT" });
+			expect(tauriAPI.resetReconnectStatus).toHaveBeenCalledTimes(1);
+
+			actor.stop();
+			vi.useRealTimers();
+		});
+	});
+
 	describe("Delays: retryDelay (Exponential Backoff)", () => {
 		it("calculates correct delay for each retry count", () => {
 			const calculateDelay = (retryCount: number) =>
@@ -699,6 +840,7 @@ describe("connectionMachine", () => {
 					emitConnectionState: () => {},
 					emitReconnectStarted: () => {},
 					emitReconnectResult: () => {},
+					reportRetryScheduled: () => {},
 					cleanupClient: () => {},
 					logState: () => {},
 				},
@@ -764,6 +906,7 @@ describe("connectionMachine", () => {
 					emitConnectionState: () => {},
 					emitReconnectStarted: () => {},
 					emitReconnectResult: () => {},
+					reportRetryScheduled: () => {},
 					cleanupClient: () => {},
 					logState: () => {},
 				},
@@ -826,6 +969,7 @@ describe("connectionMachine", () => {
 					emitConnectionState: () => {},
 					emitReconnectStarted: () => {},
 					emitReconnectResult: () => {},
+					reportRetryScheduled: () => {},
 					cleanupClient: () => {},
 					logState: () => {},
 				},
@@ -978,6 +1122,7 @@ describe("connectionMachine", () => {
 					emitConnectionState: () => {},
 					emitReconnectStarted: () => {},
 					emitReconnectResult: () => {},
+					reportRetryScheduled: () => {},
 					cleanupClient: () => {},
 					logState: () => {},
 				},
@@ -10,7 +10,10 @@ import {
 	fromPromise,
 	setup,
 } from "xstate";
-import type { ProviderChangeRequestPayload } from "../lib/events";
+import type {
+	DisconnectReason,
+	ProviderChangeRequestPayload,
+} from "../lib/events";
 import {
 	type ConfigMessage,
 	sendConfigMessages,
@@ -52,6 +55,11 @@ interface ConnectionContext {
 	serverUrl: string;
 	retryCount: number;
 	error: string | null;
+	// Why the last connection was lost, reported to Rust with the state
+	disconnectReason: DisconnectReason | null;
+	// Restoring a lost connection (retrying or a manual reconnect) rather
+	// than connecting for the first time
+	reconnecting: boolean;
 }
 
 // Events that can be sent to the machine
@@ -62,8 +70,10 @@ type ConnectionEvents =
 	| { type: "CONNECTED" }
 	| { type: "DISCONNECTED" }
 	| { type: "RECONNECT" }
+	| { type: "DISCONNECT" }
 	| { type: "START_RECORDING" }
 	| { type: "STOP_RECORDING" }
+	| { type: "DISCARD_RECORDING" }
 	| { type: "RESPONSE_RECEIVED" }
 	| { type: "SERVER_URL_CHANGED"; serverUrl: string }
 	| { type: "COMMUNICATION_ERROR"; error: string }
@@ -170,13 +180,16 @@ const connectActor = fromCallback<
 	client.on(RTVIEvent.Disconnected, handleDisconnected);
 
 	// Start connection with clientUUID in requestData
-	client
-		.connect({
-			webrtcRequestParams: {
-				endpoint: joinURL(serverUrl, "api/offer"),
-				requestData: { clientUUID },
-			},
-		})
+	tauriAPI
+		.getApiBasePath()
+		.then((apiBasePath) =>
+			client.connect({
+				webrtcRequestParams: {
+					endpoint: joinURL(serverUrl, apiBasePath, "offer"),
+					requestData: { clientUUID },
+				},
+			}),
+		)
 		.catch((error: unknown) => {
 			console.error("[XState] Connection error:", error);
 			console.debug(
@@ -284,6 +297,45 @@ const disconnectListenerActor = fromCallback<
 // Provider Change Listener Actor
 // =============================================================================
 
+/**
+ * Delay before the next reconnect attempt.
+ * Exponential backoff: 1s, 2s, 4s, 8s... capped at 30s
+ */
+function retryDelayMs(retryCount: number): number {
+	return Math.min(1000 * 2 ** retryCount, 30000);
+}
+
+/**
+ * Attempt number reported with reconnect events: absent for manual reconnects,
+ * which reset the retry count.
+ */
+function attemptNumber(retryCount: number): number | undefined {
+	return retryCount > 0 ? retryCount : undefined;
+}
+
+/**
+ * Whether an HTTP error means the server refused this client (401/403).
+ */
+function isAuthRejection(error: unknown): boolean {
+	const httpError = error as { response?: { status?: number } };
+	const status = httpError?.response?.status;
+	return status === 401 || status === 403;
+}
+
+/**
+ * Why the machine moved to retrying, from the event that sent it there.
+ */
+function disconnectReasonFor(event: AnyEventObject): DisconnectReason {
+	if (event.type === "DISCONNECTED") return "server-closed";
+	// Delayed transitions (the connection timeout) arrive as "xstate.after.*"
+	if (event.type.startsWith("xstate.after")) return "timeout";
+	// Client registration refused by the server
+	if (event.type.startsWith("xstate.error") && isAuthRejection(event.error)) {
+		return "auth-failed";
+	}
+	return "error";
+}
+
 /**
  * Maps provider type to the corresponding setting name for error reporting.
  */
@@ -359,17 +411,38 @@ export const connectionMachine = setup({
 	},
 	actions: {
 		// Emit connection state to main window via Tauri events
-		emitConnectionState: (_, params: { state: ConnectionState }): void => {
-			tauriAPI.emitConnectionState(params.state);
-		},
-		emitReconnectStarted: (): void => {
-			tauriAPI.emitReconnectStarted();
-		},
-		emitReconnectResult: (
+		emitConnectionState: (
 			_,
-			params: { success: boolean; error?: string },
+			params: { state: ConnectionState; reason?: DisconnectReason },
 		): void => {
-			tauriAPI.emitReconnectResult(params.success, params.error);
+			tauriAPI.emitConnectionState(params.state, params.reason);
+		},
+		// Right before each reconnect attempt. Re-registering a rejected UUID
+		// continues the same attempt.
+		emitReconnectStarted: ({ context, event }): void => {
+			if (!context.reconnecting || event.type === "UUID_REJECTED") return;
+			tauriAPI.emitReconnectStarted(attemptNumber(context.retryCount));
+		},
+		// Once each reconnect attempt has succeeded or failed
+		emitReconnectResult: ({ context }, params: { success: boolean }): void => {
+			if (!context.reconnecting) return;
+			tauriAPI.emitReconnectResult(
+				params.success,
+				params.success ? undefined : (context.error ?? undefined),
+				attemptNumber(context.retryCount),
+			);
+		},
+		// Rust only records this schedule so other windows can show the retry countdown
+		reportRetryScheduled: ({ context }): void => {
+			tauriAPI.setReconnectScheduled(
+				context.retryCount,
+				retryDelayMs(context.retryCount),
+			);
+		},
+		// Drop the countdown Rust reports once no retry is scheduled any more
+		resetReconnectStatus: ({ context }): void => {
+			if (!context.reconnecting) return;
+			tauriAPI.resetReconnectStatus();
 		},
 		cleanupClient: ({ context }): void => {
 			if (!context.client) return;
@@ -378,15 +451,20 @@ export const connectionMachine = setup({
 			clearKeepAliveInterval(context.client);
 			context.client.disconnect().catch(() => {});
 		},
+		// Explicit disconnect (e.g. switching to local-only): no retries follow
+		resetForUserDisconnect: assign({
+			client: () => null,
+			retryCount: () => 0,
+			reconnecting: () => false,
+			disconnectReason: (): DisconnectReason => "user-requested",
+		}),
 		logState: (_, params: { state: string }): void => {
 			console.log(`[XState] → ${params.state}`);
 		},
 	},
 	delays: {
 		connectionTimeout: 30000,
-		// Exponential backoff: 1s, 2s, 4s, 8s... capped at 30s
-		retryDelay: ({ context }) =>
-			Math.min(1000 * 2 ** context.retryCount, 30000),
+		retryDelay: ({ context }) => retryDelayMs(context.retryCount),
 	},
 }).createMachine({
 	id: "connection",
@@ -397,18 +475,29 @@ export const connectionMachine = setup({
 		serverUrl: "",
 		retryCount: 0,
 		error: null,
+		disconnectReason: null,
+		reconnecting: false,
 	},
 
 	states: {
 		disconnected: {
 			entry: [
-				{ type: "emitConnectionState", params: { state: "disconnected" } },
+				{
+					type: "emitConnectionState",
+					params: ({ context }) => ({
+						state: "disconnected",
+						reason: context.disconnectReason ?? undefined,
+					}),
+				},
 				{ type: "logState", params: { state: "disconnected" } },
 			],
 			on: {
 				CONNECT: {
 					target: "initializing",
-					actions: assign({ serverUrl: ({ event }) => event.serverUrl }),
+					actions: assign({
+						serverUrl: ({ event }) => event.serverUrl,
+						disconnectReason: () => null,
+					}),
 				},
 			},
 		},
@@ -416,7 +505,14 @@ export const connectionMachine = setup({
 		// Create a fresh PipecatClient and ensure UUID is registered
 		initializing: {
 			entry: [
-				{ type: "emitConnectionState", params: { state: "connecting" } },
+				{
+					type: "emitConnectionState",
+					params: ({ context }) => ({
+						state: "connecting",
+						reason: context.disconnectReason ?? undefined,
+					}),
+				},
+				"emitReconnectStarted",
 				{ type: "logState", params: { state: "initializing" } },
 			],
 			invoke: {
@@ -439,6 +535,17 @@ export const connectionMachine = setup({
 					}),
 				},
 			},
+			on: {
+				// User asked to disconnect (e.g. switched to local-only)
+				DISCONNECT: {
+					target: "disconnected",
+					actions: [
+						"cleanupClient",
+						"resetReconnectStatus",
+						"resetForUserDisconnect",
+					],
+				},
+			},
 		},
 
 		// Connect the client to the server
@@ -456,9 +563,22 @@ export const connectionMachine = setup({
 			on: {
 				CONNECTED: {
 					target: "idle",
-					actions: assign({ retryCount: 0, error: null }),
+					actions: assign({
+						retryCount: 0,
+						error: null,
+						disconnectReason: null,
+					}),
 				},
 				DISCONNECTED: "retrying",
+				// User asked to disconnect (e.g. switched to local-only)
+				DISCONNECT: {
+					target: "disconnected",
+					actions: [
+						"cleanupClient",
+						"resetReconnectStatus",
+						"resetForUserDisconnect",
+					],
+				},
 				// UUID rejected by server (e.g., after server restart)
 				// Clear stored UUID and go back to initializing to re-register
 				UUID_REJECTED: {
@@ -472,6 +592,7 @@ export const connectionMachine = setup({
 						assign({
 							client: () => null,
 							clientUUID: () => null,
+							disconnectReason: (): DisconnectReason => "auth-failed",
 						}),
 					],
 				},
@@ -489,6 +610,8 @@ export const connectionMachine = setup({
 			entry: [
 				{ type: "emitConnectionState", params: { state: "idle" } },
 				{ type: "emitReconnectResult", params: { success: true } },
+				"resetReconnectStatus",
+				assign({ reconnecting: false }),
 				{ type: "logState", params: { state: "idle" } },
 			],
 			invoke: [
@@ -514,6 +637,15 @@ export const connectionMachine = setup({
 					actions: "cleanupClient",
 				},
 				START_RECORDING: "recording",
+				// User asked to disconnect (e.g. switched to local-only)
+				DISCONNECT: {
+					target: "disconnected",
+					actions: [
+						"cleanupClient",
+						"resetReconnectStatus",
+						"resetForUserDisconnect",
+					],
+				},
 				SERVER_URL_CHANGED: {
 					target: "initializing",
 					actions: [
@@ -529,8 +661,12 @@ export const connectionMachine = setup({
 					target: "initializing",
 					actions: [
 						"cleanupClient",
-						"emitReconnectStarted",
-						assign({ client: () => null, retryCount: () => 0 }),
+						assign({
+							client: () => null,
+							retryCount: () => 0,
+							reconnecting: () => true,
+						}),
+						"resetReconnectStatus",
 					],
 				},
 			},
@@ -559,13 +695,28 @@ export const connectionMachine = setup({
 					actions: "cleanupClient",
 				},
 				STOP_RECORDING: "processing",
+				// User asked to disconnect (e.g. switched to local-only)
+				DISCONNECT: {
+					target: "disconnected",
+					actions: [
+						"cleanupClient",
+						"resetReconnectStatus",
+						"resetForUserDisconnect",
+					],
+				},
+				// Too short to keep: nothing is sent for transcription
+				DISCARD_RECORDING: "idle",
 				// Handle manual reconnect during recording
 				RECONNECT: {
 					target: "initializing",
 					actions: [
 						"cleanupClient",
-						"emitReconnectStarted",
-						assign({ client: () => null, retryCount: () => 0 }),
+						assign({
+							client: () => null,
+							retryCount: () => 0,
+							reconnecting: () => true,
+						}),
+						"resetReconnectStatus",
 					],
 				},
 			},
@@ -594,13 +745,26 @@ export const connectionMachine = setup({
 					actions: "cleanupClient",
 				},
 				RESPONSE_RECEIVED: "idle",
+				// User asked to disconnect (e.g. switched to local-only)
+				DISCONNECT: {
+					target: "disconnected",
+					actions: [
+						"cleanupClient",
+						"resetReconnectStatus",
+						"resetForUserDisconnect",
+					],
+				},
 				// Handle manual reconnect during processing
 				RECONNECT: {
 					target: "initializing",
 					actions: [
 						"cleanupClient",
-						"emitReconnectStarted",
-						assign({ client: () => null, retryCount: () => 0 }),
+						assign({
+							client: () => null,
+							retryCount: () => 0,
+							reconnecting: () => true,
+						}),
+						"resetReconnectStatus",
 					],
 				},
 			},
@@ -609,13 +773,23 @@ export const connectionMachine = setup({
 		// Reconnecting with exponential backoff
 		retrying: {
 			entry: [
-				{ type: "emitConnectionState", params: { state: "reconnecting" } },
-				"emitReconnectStarted",
+				// Reports the attempt that just failed, if this was a reconnect
+				{ type: "emitReconnectResult", params: { success: false } },
 				"cleanupClient",
 				assign({
 					retryCount: ({ context }) => context.retryCount + 1,
 					client: () => null,
+					disconnectReason: ({ event }) => disconnectReasonFor(event),
+					reconnecting: () => true,
 				}),
+				{
+					type: "emitConnectionState",
+					params: ({ context }) => ({
+						state: "reconnecting",
+						reason: context.disconnectReason ?? undefined,
+					}),
+				},
+				"reportRetryScheduled",
 				{ type: "logState", params: { state: "retrying" } },
 			],
 			after: {
@@ -625,16 +799,29 @@ export const connectionMachine = setup({
 				// Manual reconnect resets retry counter and retries immediately
 				RECONNECT: {
 					target: "initializing",
-					actions: assign({ retryCount: () => 0 }),
+					actions: [assign({ retryCount: () => 0 }), "resetReconnectStatus"],
+				},
+				// User asked to disconnect (e.g. switched to local-only)
+				DISCONNECT: {
+					target: "disconnected",
+					actions: [
+						"cleanupClient",
+						"resetReconnectStatus",
+						"resetForUserDisconnect",
+					],
 				},
 				// Server URL changed - immediately reconnect with new URL
 				// No need for cleanupClient since it already runs on entry to retrying
 				SERVER_URL_CHANGED: {
 					target: "initializing",
-					actions: assign({
-						serverUrl: ({ event }) => event.serverUrl,
-						retryCount: () => 0,
-					}),
+					actions: [
+						"resetReconnectStatus",
+						assign({
+							serverUrl: ({ event }) => event.serverUrl,
+							retryCount: () => 0,
+							reconnecting: () => false,
+						}),
+					],
 				},
 			},
 		},